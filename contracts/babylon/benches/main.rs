@@ -46,6 +46,7 @@ pub fn setup_instance() -> Instance<MockApi, MockStorage, MockQuerier> {
         babylon_tag: "01020304".to_string(),
         consumer_name: None,
         consumer_description: None,
+        signet_challenge: None,
         btc_confirmation_depth: 10,
         checkpoint_finalization_timeout: 1,
         notify_cosmos_zone: false,
@@ -76,6 +77,7 @@ fn setup_benchmark() -> (
 
     let benchmark_msg = ExecuteMsg::BtcHeaders {
         headers: test_headers[0..=1].to_owned(),
+        signet_proofs: None,
     };
 
     // init call
@@ -95,6 +97,7 @@ fn bench_btc_light_client(c: &mut Criterion) {
         b.iter(|| {
             let benchmark_msg = ExecuteMsg::BtcHeaders {
                 headers: test_headers[i..=i + 1].to_owned(),
+                signet_proofs: None,
             };
             execute::<_, _, _, _, BabylonMsg>(&mut deps, env.clone(), info.clone(), benchmark_msg)
                 .unwrap();
@@ -112,6 +115,7 @@ fn bench_btc_light_client(c: &mut Criterion) {
             for _ in 0..iter {
                 let benchmark_msg = ExecuteMsg::BtcHeaders {
                     headers: test_headers[i..=i + 1].to_owned(),
+                    signet_proofs: None,
                 };
                 let gas_before = deps.get_gas_left();
                 execute::<_, _, _, _, BabylonMsg>(
@@ -142,6 +146,7 @@ fn bench_btc_light_client(c: &mut Criterion) {
             for _ in 0..iter {
                 let benchmark_msg = ExecuteMsg::BtcHeaders {
                     headers: test_headers[i..=i + 1].to_owned(),
+                    signet_proofs: None,
                 };
                 let gas_before = deps.get_gas_left();
                 execute::<_, _, _, _, BabylonMsg>(
@@ -1,15 +1,15 @@
 use cosmwasm_schema::write_api;
-use cosmwasm_std::Empty;
 
-use babylon_contract::msg::contract::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use babylon_contract::msg::contract::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
 
 fn main() {
     // Clear & write standard API
     write_api! {
         instantiate: InstantiateMsg,
         query: QueryMsg,
-        migrate: Empty,
+        migrate: MigrateMsg,
         execute: ExecuteMsg,
+        sudo: SudoMsg,
     }
 
     // Schemas for inter-contract communication
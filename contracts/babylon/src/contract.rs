@@ -1,38 +1,46 @@
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, QueryResponse, Reply,
-    Response, SubMsg, SubMsgResponse, WasmMsg,
+    to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, QueryResponse, Reply, Response,
+    StdResult, Storage, SubMsg, SubMsgResponse, WasmMsg,
 };
 use cw2::set_contract_version;
-use cw_utils::ParseReplyError;
+use cw_utils::{maybe_addr, ParseReplyError};
 
 use babylon_apis::{btc_staking_api, finality_api};
 use babylon_bindings::BabylonMsg;
 
 use crate::error::ContractError;
-use crate::ibc::{ibc_packet, IBC_CHANNEL};
-use crate::msg::contract::{ContractMsg, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::ibc::{ibc_packet, retry_failed_slashing_packets, IBC_CHANNEL};
+use crate::msg::contract::{
+    validate_consumer_metadata, ContractMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    SudoMsg,
+};
 use crate::queries;
 use crate::state::btc_light_client;
-use crate::state::config::{Config, CONFIG};
+use crate::state::config::{Config, ADMIN, CONFIG};
+use crate::state::cz_header_chain::{record_cz_block, CzBlock};
 
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const REPLY_ID_INSTANTIATE_STAKING: u64 = 2;
 const REPLY_ID_INSTANTIATE_FINALITY: u64 = 3;
+pub(crate) const REPLY_ID_BTC_STAKING_ITEM: u64 = 4;
 
 /// When we instantiate the Babylon contract, it will optionally instantiate a BTC staking
 /// contract – if its code id is provided – to work with it for BTC re-staking support,
 /// as they both need references to each other.
 /// The admin of the BTC staking contract is taken as an explicit argument.
 pub fn instantiate(
-    deps: DepsMut,
+    mut deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response<BabylonMsg>, ContractError> {
     msg.validate()?;
 
+    let api = deps.api;
+    ADMIN.set(deps.branch(), maybe_addr(api, msg.admin.clone())?)?;
+
     // Initialize config with None values for consumer fields
     let mut cfg = Config {
         network: msg.network.clone(),
@@ -44,6 +52,7 @@ pub fn instantiate(
         btc_finality: None, // Will be set in `reply` if `btc_finality_code_id` is provided
         consumer_name: None,
         consumer_description: None,
+        signet_challenge: msg.signet_challenge_to_bytes()?,
     };
 
     let mut res = Response::new().add_attribute("action", "instantiate");
@@ -106,6 +115,7 @@ pub fn reply(
     match reply.id {
         REPLY_ID_INSTANTIATE_STAKING => reply_init_callback_staking(deps, reply.result.unwrap()),
         REPLY_ID_INSTANTIATE_FINALITY => reply_init_finality_callback(deps, reply.result.unwrap()),
+        REPLY_ID_BTC_STAKING_ITEM => ibc_packet::reply_btc_staking_item(deps, reply),
         _ => Err(ContractError::InvalidReplyId(reply.id)),
     }
 }
@@ -200,13 +210,95 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<QueryResponse, Cont
     }
 }
 
-/// this is a no-op just to test how this integrates with wasmd
+/// migrate sets the admin of the contract, as contracts deployed before the admin was introduced
+/// don't have one.
+/// The admin is taken from the migrate msg, or else kept, or else taken from the contract's wasm
+/// admin
 pub fn migrate(
-    _deps: DepsMut,
-    _env: Env,
-    _msg: Empty,
+    mut deps: DepsMut,
+    env: Env,
+    msg: MigrateMsg,
+) -> Result<Response<BabylonMsg>, ContractError> {
+    let api = deps.api;
+    let admin = match maybe_addr(api, msg.admin)? {
+        Some(admin) => Some(admin),
+        // The admin is not stored by contracts predating it
+        None => match ADMIN.get(deps.as_ref()).ok().flatten() {
+            Some(admin) => Some(admin),
+            None => {
+                deps.querier
+                    .query_wasm_contract_info(env.contract.address)?
+                    .admin
+            }
+        },
+    };
+    ADMIN.set(deps.branch(), admin.clone())?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute(
+            "admin",
+            admin.map(|admin| admin.to_string()).unwrap_or_default(),
+        ))
+}
+
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response<BabylonMsg>, ContractError> {
+    match msg {
+        SudoMsg::BeginBlock {
+            hash_hex,
+            app_hash_hex,
+        } => handle_begin_block(deps, env, &hash_hex, &app_hash_hex),
+    }
+}
+
+fn handle_begin_block(
+    deps: DepsMut,
+    env: Env,
+    hash_hex: &str,
+    app_hash_hex: &str,
 ) -> Result<Response<BabylonMsg>, ContractError> {
-    Ok(Response::default())
+    // Record the Consumer's own block, for cross-checking the CZ headers finalised by Babylon
+    let cz_block = CzBlock {
+        hash: hex::decode(hash_hex)?,
+        app_hash: hex::decode(app_hash_hex)?,
+    };
+    record_cz_block(deps.storage, env.block.height, &cz_block)?;
+
+    // Re-send the slashing packets that were rejected by Babylon
+    let msgs = retry_failed_slashing_packets(deps.storage, &env)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "begin_block")
+        .add_attribute("retried_slashing_packets", msgs.len().to_string())
+        .add_messages(msgs))
+}
+
+/// btc_tip_update_msg builds the message notifying the BTC staking contract of the current BTC
+/// tip, if the tip has changed since `prev_tip_height` and the BTC staking contract is set
+pub(crate) fn btc_tip_update_msg(
+    storage: &dyn Storage,
+    prev_tip_height: Option<u64>,
+) -> StdResult<Option<WasmMsg>> {
+    let Ok(tip) = btc_light_client::get_tip(storage) else {
+        return Ok(None);
+    };
+    if prev_tip_height == Some(tip.height) {
+        return Ok(None);
+    }
+    let cfg = CONFIG.load(storage)?;
+    let Some(btc_staking) = cfg.btc_staking else {
+        return Ok(None);
+    };
+    let msg = btc_staking_api::ExecuteMsg::BtcTipUpdate {
+        height: tip.height,
+        checkpoint_finalization_timeout: cfg.checkpoint_finalization_timeout,
+    };
+    Ok(Some(WasmMsg::Execute {
+        contract_addr: btc_staking.to_string(),
+        msg: to_json_binary(&msg)?,
+        funds: vec![],
+    }))
 }
 
 pub fn execute(
@@ -218,14 +310,28 @@ pub fn execute(
     match msg {
         ExecuteMsg::BtcHeaders {
             headers: btc_headers,
+            signet_proofs,
         } => {
+            let signet_proofs = signet_proofs.as_deref();
+            let prev_tip_height = btc_light_client::get_tip(deps.storage)
+                .ok()
+                .map(|tip| tip.height);
             if btc_light_client::is_initialized(deps.storage) {
-                btc_light_client::handle_btc_headers_from_user(deps.storage, &btc_headers)?;
+                btc_light_client::handle_btc_headers_from_user(
+                    deps.storage,
+                    &btc_headers,
+                    signet_proofs,
+                )?;
             } else {
-                btc_light_client::init_from_user(deps.storage, &btc_headers)?;
+                btc_light_client::init_from_user(deps.storage, &btc_headers, signet_proofs)?;
+            }
+            // Notify the BTC staking contract of the new BTC tip
+            let mut res = Response::new();
+            if let Some(msg) = btc_tip_update_msg(deps.storage, prev_tip_height)? {
+                res = res.add_message(msg);
             }
             // TODO: Add events
-            Ok(Response::new())
+            Ok(res)
         }
         ExecuteMsg::Slashing { evidence } => {
             // This is an internal routing message from the `btc_finality` contract
@@ -269,15 +375,50 @@ pub fn execute(
             // TODO: Add events
             Ok(res)
         }
+        ExecuteMsg::UpdateConsumerMetadata {
+            consumer_name,
+            consumer_description,
+        } => handle_update_consumer_metadata(deps, env, info, consumer_name, consumer_description),
     }
 }
 
+fn handle_update_consumer_metadata(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    consumer_name: String,
+    consumer_description: String,
+) -> Result<Response<BabylonMsg>, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    validate_consumer_metadata(&consumer_name, &consumer_description)?;
+
+    let mut cfg = CONFIG.load(deps.storage)?;
+    cfg.consumer_name = Some(consumer_name.clone());
+    cfg.consumer_description = Some(consumer_description.clone());
+    CONFIG.save(deps.storage, &cfg)?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "update_consumer_metadata")
+        .add_attribute("consumer_name", &consumer_name)
+        .add_attribute("consumer_description", &consumer_description);
+
+    // Announce the new metadata to Babylon, if the channel is open already.
+    // Otherwise, it will be sent upon registration, when the channel is connected
+    if let Some(channel) = IBC_CHANNEL.may_load(deps.storage)? {
+        let ibc_msg =
+            ibc_packet::consumer_update_msg(&env, &channel, &consumer_name, &consumer_description);
+        res = res.add_message(ibc_msg);
+    }
+    Ok(res)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use babylon_bitcoin::BlockHeader;
     use cosmwasm_std::testing::message_info;
     use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::{ContractInfoResponse, ContractResult, SystemResult, WasmQuery};
 
     const CREATOR: &str = "creator";
 
@@ -305,9 +446,278 @@ mod tests {
             admin: None,
             consumer_name: None,
             consumer_description: None,
+            signet_challenge: None,
         };
         let info = message_info(&deps.api.addr_make(CREATOR), &[]);
         let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
         assert_eq!(0, res.messages.len());
     }
+
+    #[test]
+    fn signet_challenge_requires_signet_network() {
+        use babylon_bitcoin::chain_params::{ChainParamsOverride, Network};
+
+        let signet_custom = Network::Custom(ChainParamsOverride {
+            base: Box::new(Network::Signet),
+            pow_limit: None,
+            pow_target_spacing: Some(60),
+            pow_target_timespan: None,
+            allow_min_difficulty_blocks: None,
+            no_pow_retargeting: None,
+        });
+        let instantiate_msg = |network: Network| InstantiateMsg {
+            network,
+            babylon_tag: "01020304".to_string(),
+            btc_confirmation_depth: 10,
+            checkpoint_finalization_timeout: 100,
+            notify_cosmos_zone: false,
+            btc_staking_code_id: None,
+            btc_staking_msg: None,
+            btc_finality_code_id: None,
+            btc_finality_msg: None,
+            admin: None,
+            consumer_name: None,
+            consumer_description: None,
+            signet_challenge: Some("51".to_string()),
+        };
+
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            instantiate_msg(Network::Regtest),
+        )
+        .unwrap_err();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            instantiate_msg(Network::Signet),
+        )
+        .unwrap();
+        // Custom networks based on signet support signet challenges too
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            instantiate_msg(signet_custom),
+        )
+        .unwrap();
+        let cfg = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(cfg.signet_challenge, Some(vec![0x51]));
+    }
+
+    #[test]
+    fn sudo_begin_block_records_cz_blocks() {
+        use crate::state::cz_header_chain::{CZ_BLOCKS, CZ_BLOCKS_RETENTION};
+
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            network: babylon_bitcoin::chain_params::Network::Regtest,
+            babylon_tag: "01020304".to_string(),
+            btc_confirmation_depth: 10,
+            checkpoint_finalization_timeout: 100,
+            notify_cosmos_zone: false,
+            btc_staking_code_id: None,
+            btc_staking_msg: None,
+            btc_finality_code_id: None,
+            btc_finality_msg: None,
+            admin: None,
+            consumer_name: None,
+            consumer_description: None,
+            signet_challenge: None,
+        };
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let begin_block = |deps: DepsMut, height: u64, hash_hex: &str| {
+            let mut env = mock_env();
+            env.block.height = height;
+            let msg = SudoMsg::BeginBlock {
+                hash_hex: hash_hex.to_string(),
+                app_hash_hex: "deadbeef".to_string(),
+            };
+            sudo(deps, env, msg)
+        };
+
+        let res = begin_block(deps.as_mut(), 1, "0102").unwrap();
+        assert_eq!(0, res.messages.len());
+        let cz_block = CZ_BLOCKS.load(&deps.storage, 1).unwrap();
+        assert_eq!(cz_block.hash, vec![1, 2]);
+        assert_eq!(cz_block.app_hash, hex::decode("deadbeef").unwrap());
+
+        // Invalid hashes are rejected
+        begin_block(deps.as_mut(), 2, "not hex").unwrap_err();
+
+        // Blocks out of the retention window are pruned
+        begin_block(deps.as_mut(), CZ_BLOCKS_RETENTION + 2, "0304").unwrap();
+        assert!(!CZ_BLOCKS.has(&deps.storage, 1));
+        assert!(CZ_BLOCKS.has(&deps.storage, CZ_BLOCKS_RETENTION + 2));
+    }
+
+    #[test]
+    fn btc_tip_updates_are_sent_to_btc_staking() {
+        let mut deps = mock_dependencies();
+        let w = btc_light_client::tests::setup(deps.as_mut().storage);
+
+        // No BTC tip yet
+        assert_eq!(btc_tip_update_msg(&deps.storage, None).unwrap(), None);
+
+        let headers = test_utils::get_btc_lc_headers();
+        btc_light_client::init(deps.as_mut().storage, &headers[0..w + 1]).unwrap();
+        let tip_height = headers[w].height;
+
+        // No BTC staking contract yet
+        assert_eq!(btc_tip_update_msg(&deps.storage, None).unwrap(), None);
+
+        let btc_staking = deps.api.addr_make("btc_staking");
+        CONFIG
+            .update(deps.as_mut().storage, |mut cfg| -> StdResult<_> {
+                cfg.btc_staking = Some(btc_staking.clone());
+                Ok(cfg)
+            })
+            .unwrap();
+
+        // The new BTC tip is sent to the BTC staking contract
+        let msg = btc_tip_update_msg(&deps.storage, None).unwrap().unwrap();
+        let expected = btc_staking_api::ExecuteMsg::BtcTipUpdate {
+            height: tip_height,
+            checkpoint_finalization_timeout: w as u64,
+        };
+        assert_eq!(
+            msg,
+            WasmMsg::Execute {
+                contract_addr: btc_staking.to_string(),
+                msg: to_json_binary(&expected).unwrap(),
+                funds: vec![],
+            }
+        );
+
+        // An unchanged BTC tip is not sent
+        assert_eq!(
+            btc_tip_update_msg(&deps.storage, Some(tip_height)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn update_consumer_metadata_works() {
+        let mut deps = mock_dependencies();
+        let admin = deps.api.addr_make("admin");
+        let msg = InstantiateMsg {
+            network: babylon_bitcoin::chain_params::Network::Regtest,
+            babylon_tag: "01020304".to_string(),
+            btc_confirmation_depth: 10,
+            checkpoint_finalization_timeout: 100,
+            notify_cosmos_zone: false,
+            btc_staking_code_id: None,
+            btc_staking_msg: None,
+            btc_finality_code_id: None,
+            btc_finality_msg: None,
+            admin: Some(admin.to_string()),
+            consumer_name: None,
+            consumer_description: None,
+            signet_challenge: None,
+        };
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let update_msg = ExecuteMsg::UpdateConsumerMetadata {
+            consumer_name: "new name".to_string(),
+            consumer_description: "new description".to_string(),
+        };
+
+        // Only the admin can update the metadata
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, update_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Admin(_)));
+
+        // Metadata is stored, but not sent until the channel is open
+        let info = message_info(&admin, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), update_msg.clone()).unwrap();
+        assert_eq!(0, res.messages.len());
+        let cfg = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(cfg.consumer_name.as_deref(), Some("new name"));
+        assert_eq!(cfg.consumer_description.as_deref(), Some("new description"));
+
+        // Metadata is announced over the open channel
+        let channel = cosmwasm_std::testing::mock_ibc_channel(
+            "channel-123",
+            cosmwasm_std::IbcOrder::Ordered,
+            "babylon",
+        );
+        IBC_CHANNEL.save(deps.as_mut().storage, &channel).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), update_msg).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        // Empty metadata is rejected
+        let update_msg = ExecuteMsg::UpdateConsumerMetadata {
+            consumer_name: " ".to_string(),
+            consumer_description: "new description".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), info, update_msg).unwrap_err();
+    }
+
+    #[test]
+    fn migrate_sets_admin() {
+        let mut deps = mock_dependencies();
+        let wasm_admin = deps.api.addr_make("wasm_admin");
+        let querier_wasm_admin = wasm_admin.clone();
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::ContractInfo { .. } => {
+                let info = ContractInfoResponse::new(
+                    1,
+                    Addr::unchecked(CREATOR),
+                    Some(querier_wasm_admin.clone()),
+                    false,
+                    None,
+                );
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&info).unwrap()))
+            }
+            _ => unreachable!("unexpected query"),
+        });
+        // A contract deployed before the admin was introduced
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config {
+                    network: babylon_bitcoin::chain_params::Network::Regtest,
+                    babylon_tag: vec![1, 2, 3, 4],
+                    btc_confirmation_depth: 10,
+                    checkpoint_finalization_timeout: 100,
+                    notify_cosmos_zone: false,
+                    btc_staking: None,
+                    btc_finality: None,
+                    consumer_name: None,
+                    consumer_description: None,
+                    signet_challenge: None,
+                },
+            )
+            .unwrap();
+
+        // The admin defaults to the wasm admin
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        assert_eq!(ADMIN.get(deps.as_ref()).unwrap(), Some(wasm_admin.clone()));
+
+        let update_msg = ExecuteMsg::UpdateConsumerMetadata {
+            consumer_name: "new name".to_string(),
+            consumer_description: "new description".to_string(),
+        };
+        let info = message_info(&wasm_admin, &[]);
+        execute(deps.as_mut(), mock_env(), info, update_msg).unwrap();
+
+        // The admin can be set explicitly
+        let admin = deps.api.addr_make("admin");
+        let msg = MigrateMsg {
+            admin: Some(admin.to_string()),
+        };
+        migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(ADMIN.get(deps.as_ref()).unwrap(), Some(admin.clone()));
+
+        // The current admin is kept
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        assert_eq!(ADMIN.get(deps.as_ref()).unwrap(), Some(admin));
+    }
 }
@@ -1,5 +1,6 @@
 use babylon_bitcoin::Work;
 use cosmwasm_std::StdError;
+use cw_controllers::AdminError;
 use cw_utils::ParseReplyError;
 use hex::FromHexError;
 use prost::DecodeError;
@@ -12,6 +13,8 @@ pub enum ContractError {
     StdError(#[from] StdError),
     #[error("{0}")]
     ParseReply(#[from] ParseReplyError),
+    #[error("{0}")]
+    Admin(#[from] AdminError),
     #[error("Invalid reply id: {0}")]
     InvalidReplyId(u64),
     #[error("{0}")]
@@ -38,6 +41,8 @@ pub enum ContractError {
     BtcFinalityNotSet {},
     #[error("Invalid configuration: {msg}")]
     InvalidConfig { msg: String },
+    #[error("{0}")]
+    HexError(#[from] FromHexError),
 }
 
 #[derive(Error, Debug, PartialEq)]
@@ -58,6 +63,8 @@ pub enum CZHeaderChainError {
     CZHeaderNotFoundError { height: u64 },
     #[error("There is no finalized CZ header yet")]
     NoCZHeader {},
+    #[error("The CZ header with height {height} does not match the Consumer's own block hash")]
+    CZHeaderHashMismatch { height: u64 },
 }
 
 #[derive(Error, Debug, PartialEq)]
@@ -96,6 +103,10 @@ pub enum BTCLightclientError {
     BTCWrongHeight(usize, u64, u64),
     #[error("The new chain's work ({0}), is not better than the current chain's work ({1})")]
     BTCChainWithNotEnoughWork(Work, Work),
+    #[error("Signet proofs are required for all the BTC headers. Expected {0}, got {1}")]
+    SignetProofsMismatch(usize, usize),
+    #[error("The BTC header's signet solution is invalid: {0}")]
+    SignetSolutionError(String),
 }
 
 #[derive(Error, Debug, PartialEq)]
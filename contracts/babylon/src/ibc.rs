@@ -6,13 +6,15 @@ use babylon_proto::babylon::zoneconcierge::v1::{
 };
 
 use crate::state::config::CONFIG;
+use babylon_apis::btc_staking_api::BtcStakingItemResult;
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    Binary, DepsMut, Env, Event, Ibc3ChannelOpenResponse, IbcBasicResponse, IbcChannel,
+    from_json, Binary, DepsMut, Env, Event, Ibc3ChannelOpenResponse, IbcBasicResponse, IbcChannel,
     IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse, IbcMsg,
     IbcOrder, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse,
-    IbcTimeout, Never, StdAck, StdError, StdResult,
+    IbcTimeout, Never, Order, StdAck, StdError, StdResult, Storage,
 };
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
 use prost::Message;
 
 pub const IBC_VERSION: &str = "zoneconcierge-1";
@@ -20,6 +22,31 @@ pub const IBC_ORDERING: IbcOrder = IbcOrder::Ordered;
 
 // IBC specific state
 pub const IBC_CHANNEL: Item<IbcChannel> = Item::new("ibc_channel");
+/// Slashing packets that were rejected by Babylon or timed out, indexed by packet data.
+/// They are re-sent in a later `BeginBlock` with exponential backoff, up to `MAX_SLASHING_RETRIES`
+/// times
+pub const FAILED_SLASHING_PACKETS: Map<&[u8], SlashingRetry> = Map::new("failed_slashing_packets");
+
+pub const MAX_SLASHING_RETRIES: u32 = 3;
+/// Maximum number of failed slashing packets re-sent in a single `BeginBlock`.
+/// The rest are re-sent in the next blocks
+pub const MAX_SLASHING_RETRIES_PER_BLOCK: usize = 10;
+/// Number of blocks to wait before the first retry of a failed slashing packet.
+/// The wait doubles with every retry
+pub const SLASHING_RETRY_BACKOFF_BLOCKS: u64 = 10;
+/// Results of the items of the last BtcStaking packet, as forwarded to the BTC staking contract
+pub const BTC_STAKING_RESULTS: Item<Vec<BtcStakingItemResult>> = Item::new("btc_staking_results");
+
+#[cw_serde]
+pub struct SlashingRetry {
+    /// `retries` is the number of times the packet has been re-sent
+    pub retries: u32,
+    /// `in_flight` indicates whether the re-sent packet is waiting for its acknowledgement
+    pub in_flight: bool,
+    /// `next_retry_height` is the height from which the packet can be re-sent
+    #[serde(default)]
+    pub next_retry_height: u64,
+}
 
 /// This is executed during the ChannelOpenInit and ChannelOpenTry
 /// of the IBC 4-step channel protocol
@@ -108,15 +135,24 @@ pub fn ibc_channel_connect(
 }
 
 /// This is invoked on the IBC Channel Close message
-/// We perform any cleanup related to the channel
+/// We perform any cleanup related to the channel.
+/// As the channel is ordered, this also happens when a packet times out.
+/// The channel is forgotten, so that no more packets are sent over it, and a new channel can be
+/// opened
 pub fn ibc_channel_close(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     msg: IbcChannelCloseMsg,
 ) -> StdResult<IbcBasicResponse> {
     let channel = msg.channel();
     // Get contract address and remove lookup
     let channel_id = channel.endpoint.channel_id.as_str();
+    if IBC_CHANNEL
+        .may_load(deps.storage)?
+        .is_some_and(|open_channel| open_channel.endpoint.channel_id == channel_id)
+    {
+        IBC_CHANNEL.remove(deps.storage);
+    }
 
     Ok(IbcBasicResponse::new()
         .add_attribute("action", "ibc_close")
@@ -161,6 +197,9 @@ pub fn ibc_packet_receive(
             Packet::ConsumerSlashing(_) => Err(StdError::generic_err(
                 "ConsumerSlashing packet should not be received",
             )),
+            Packet::ConsumerUpdate(_) => Err(StdError::generic_err(
+                "ConsumerUpdate packet should not be received",
+            )),
         }
     })()
     .or_else(|e| {
@@ -176,18 +215,28 @@ pub fn ibc_packet_receive(
 // Methods to handle PacketMsg variants
 pub(crate) mod ibc_packet {
     use super::*;
+    use crate::contract::{btc_tip_update_msg, REPLY_ID_BTC_STAKING_ITEM};
+    use crate::state::btc_light_client;
     use crate::state::config::CONFIG;
+    use babylon_apis::btc_staking_api::BtcStakingItemKind;
     use babylon_apis::btc_staking_api::SlashedBtcDelegation;
     use babylon_apis::btc_staking_api::{
-        ActiveBtcDelegation, BtcUndelegationInfo, CovenantAdaptorSignatures,
-        FinalityProviderDescription, NewFinalityProvider, ProofOfPossessionBtc, SignatureInfo,
-        UnbondedBtcDelegation,
+        ActiveBtcDelegation, BtcUndelegationInfo, CovenantAdaptorSignatures, EditFinalityProvider,
+        FinalityProviderDescription, InclusionProof, NewFinalityProvider, ProofOfPossessionBtc,
+        SignatureInfo, UnbondedBtcDelegation,
     };
     use babylon_apis::finality_api::Evidence;
     use babylon_proto::babylon::btcstaking::v1::BtcStakingIbcPacket;
-    use babylon_proto::babylon::zoneconcierge::v1::zoneconcierge_packet_data::Packet::ConsumerSlashing;
-    use babylon_proto::babylon::zoneconcierge::v1::ConsumerSlashingIbcPacket;
-    use cosmwasm_std::{to_json_binary, Decimal, IbcChannel, IbcMsg, WasmMsg};
+    use babylon_proto::babylon::zoneconcierge::v1::zoneconcierge_packet_data::Packet::{
+        ConsumerSlashing, ConsumerUpdate,
+    };
+    use babylon_proto::babylon::zoneconcierge::v1::{
+        ConsumerSlashingIbcPacket, ConsumerUpdateIbcPacket,
+    };
+    use cosmwasm_std::{
+        to_json_binary, to_json_string, Decimal, IbcChannel, IbcMsg, Reply, Response, SubMsg,
+        SubMsgResult, WasmMsg,
+    };
     use std::str::FromStr;
 
     pub fn handle_btc_timestamp(
@@ -199,6 +248,9 @@ pub(crate) mod ibc_packet {
         let cfg = CONFIG.load(storage)?;
 
         // handle the BTC timestamp, i.e., verify the BTC timestamp and update the contract state
+        let prev_tip_height = btc_light_client::get_tip(storage)
+            .ok()
+            .map(|tip| tip.height);
         let msg_option = crate::state::handle_btc_timestamp(storage, btc_ts)?;
 
         // construct response
@@ -216,6 +268,11 @@ pub(crate) mod ibc_packet {
             }
         }
 
+        // notify the BTC staking contract of the new BTC tip, if any
+        if let Some(msg) = btc_tip_update_msg(storage, prev_tip_height)? {
+            resp = resp.add_message(msg);
+        }
+
         Ok(resp)
     }
 
@@ -232,131 +289,302 @@ pub(crate) mod ibc_packet {
             .btc_staking
             .ok_or(StdError::generic_err("btc_staking contract not set"))?;
 
-        // Build the message to send to the BTC staking contract
-        let msg = babylon_apis::btc_staking_api::ExecuteMsg::BtcStaking {
-            new_fp: btc_staking
-                .new_fp
-                .iter()
-                .map(|fp| {
-                    Ok(NewFinalityProvider {
-                        description: fp
-                            .description
-                            .as_ref()
-                            .map(|d| FinalityProviderDescription {
-                                moniker: d.moniker.clone(),
-                                identity: d.identity.clone(),
-                                website: d.website.clone(),
-                                security_contact: d.security_contact.clone(),
-                                details: d.details.clone(),
-                            }),
-                        commission: Decimal::from_str(&fp.commission)?,
-                        addr: fp.addr.clone(),
-                        btc_pk_hex: fp.btc_pk_hex.clone(),
-                        pop: fp.pop.as_ref().map(|pop| ProofOfPossessionBtc {
-                            btc_sig_type: pop.btc_sig_type,
-                            btc_sig: pop.btc_sig.to_vec().into(),
+        // Build the items to send to the BTC staking contract.
+        // Items that cannot be converted are reported as failed, while the rest are still sent
+        let new_fp: Vec<StdResult<_>> = btc_staking
+            .new_fp
+            .iter()
+            .map(|fp| {
+                Ok(NewFinalityProvider {
+                    description: fp
+                        .description
+                        .as_ref()
+                        .map(|d| FinalityProviderDescription {
+                            moniker: d.moniker.clone(),
+                            identity: d.identity.clone(),
+                            website: d.website.clone(),
+                            security_contact: d.security_contact.clone(),
+                            details: d.details.clone(),
                         }),
-                        consumer_id: fp.consumer_id.clone(),
-                    })
+                    commission: Decimal::from_str(&fp.commission)?,
+                    addr: fp.addr.clone(),
+                    btc_pk_hex: fp.btc_pk_hex.clone(),
+                    pop: fp.pop.as_ref().map(|pop| ProofOfPossessionBtc {
+                        btc_sig_type: pop.btc_sig_type,
+                        btc_sig: pop.btc_sig.to_vec().into(),
+                    }),
+                    consumer_id: fp.consumer_id.clone(),
                 })
-                .collect::<StdResult<_>>()?,
-            active_del: btc_staking
-                .active_del
-                .iter()
-                .map(|d| {
-                    Ok(ActiveBtcDelegation {
-                        staker_addr: d.staker_addr.clone(),
-                        btc_pk_hex: d.btc_pk_hex.clone(),
-                        fp_btc_pk_list: d.fp_btc_pk_list.clone(),
-                        start_height: d.start_height,
-                        end_height: d.end_height,
-                        total_sat: d.total_sat,
-                        staking_tx: d.staking_tx.to_vec().into(),
-                        slashing_tx: d.slashing_tx.to_vec().into(),
-                        delegator_slashing_sig: d.delegator_slashing_sig.to_vec().into(),
-                        covenant_sigs: d
-                            .covenant_sigs
-                            .iter()
-                            .map(|s| CovenantAdaptorSignatures {
-                                cov_pk: s.cov_pk.to_vec().into(),
-                                adaptor_sigs: s
-                                    .adaptor_sigs
-                                    .iter()
-                                    .map(|a| a.to_vec().into())
-                                    .collect(),
+            })
+            .collect();
+        let active_del: Vec<StdResult<_>> = btc_staking
+            .active_del
+            .iter()
+            .map(|d| {
+                Ok(ActiveBtcDelegation {
+                    staker_addr: d.staker_addr.clone(),
+                    btc_pk_hex: d.btc_pk_hex.clone(),
+                    fp_btc_pk_list: d.fp_btc_pk_list.clone(),
+                    start_height: d.start_height,
+                    end_height: d.end_height,
+                    total_sat: d.total_sat,
+                    staking_tx: d.staking_tx.to_vec().into(),
+                    slashing_tx: d.slashing_tx.to_vec().into(),
+                    delegator_slashing_sig: d.delegator_slashing_sig.to_vec().into(),
+                    covenant_sigs: d
+                        .covenant_sigs
+                        .iter()
+                        .map(|s| CovenantAdaptorSignatures {
+                            cov_pk: s.cov_pk.to_vec().into(),
+                            adaptor_sigs: s
+                                .adaptor_sigs
+                                .iter()
+                                .map(|a| a.to_vec().into())
+                                .collect(),
+                        })
+                        .collect(),
+                    staking_output_idx: d.staking_output_idx,
+                    unbonding_time: d.unbonding_time,
+                    undelegation_info: d
+                        .undelegation_info
+                        .as_ref()
+                        .map(|ui| BtcUndelegationInfo {
+                            unbonding_tx: ui.unbonding_tx.to_vec().into(),
+                            delegator_unbonding_sig: ui.delegator_unbonding_sig.to_vec().into(),
+                            covenant_unbonding_sig_list: ui
+                                .covenant_unbonding_sig_list
+                                .iter()
+                                .map(|s| SignatureInfo {
+                                    pk: s.pk.to_vec().into(),
+                                    sig: s.sig.to_vec().into(),
+                                })
+                                .collect(),
+                            slashing_tx: ui.slashing_tx.to_vec().into(),
+                            delegator_slashing_sig: ui.delegator_slashing_sig.to_vec().into(),
+                            covenant_slashing_sigs: ui
+                                .covenant_slashing_sigs
+                                .iter()
+                                .map(|s| CovenantAdaptorSignatures {
+                                    cov_pk: s.cov_pk.to_vec().into(),
+                                    adaptor_sigs: s
+                                        .adaptor_sigs
+                                        .iter()
+                                        .map(|a| a.to_vec().into())
+                                        .collect(),
+                                })
+                                .collect(),
+                        })
+                        .ok_or(StdError::generic_err("undelegation info not set"))?,
+                    params_version: d.params_version,
+                    staking_tx_inclusion_proof: d
+                        .staking_tx_inclusion_proof
+                        .as_ref()
+                        .map(|p| {
+                            let key = p.key.as_ref().ok_or(StdError::generic_err(
+                                "staking tx inclusion proof key not set",
+                            ))?;
+                            // The block hash is encoded as a (byte-reversed) hex string
+                            let block_hash_hex =
+                                hex::encode(key.hash.iter().rev().cloned().collect::<Vec<_>>());
+                            Ok::<_, StdError>(InclusionProof {
+                                block_hash_hex,
+                                index: key.index,
+                                proof: p.proof.to_vec().into(),
                             })
-                            .collect(),
-                        staking_output_idx: d.staking_output_idx,
-                        unbonding_time: d.unbonding_time,
-                        undelegation_info: d
-                            .undelegation_info
-                            .as_ref()
-                            .map(|ui| BtcUndelegationInfo {
-                                unbonding_tx: ui.unbonding_tx.to_vec().into(),
-                                delegator_unbonding_sig: ui.delegator_unbonding_sig.to_vec().into(),
-                                covenant_unbonding_sig_list: ui
-                                    .covenant_unbonding_sig_list
-                                    .iter()
-                                    .map(|s| SignatureInfo {
-                                        pk: s.pk.to_vec().into(),
-                                        sig: s.sig.to_vec().into(),
-                                    })
-                                    .collect(),
-                                slashing_tx: ui.slashing_tx.to_vec().into(),
-                                delegator_slashing_sig: ui.delegator_slashing_sig.to_vec().into(),
-                                covenant_slashing_sigs: ui
-                                    .covenant_slashing_sigs
-                                    .iter()
-                                    .map(|s| CovenantAdaptorSignatures {
-                                        cov_pk: s.cov_pk.to_vec().into(),
-                                        adaptor_sigs: s
-                                            .adaptor_sigs
-                                            .iter()
-                                            .map(|a| a.to_vec().into())
-                                            .collect(),
-                                    })
-                                    .collect(),
-                            })
-                            .ok_or(StdError::generic_err("undelegation info not set"))?,
-                        params_version: d.params_version,
-                    })
+                        })
+                        .transpose()?,
                 })
-                .collect::<StdResult<_>>()?,
-            slashed_del: btc_staking
-                .slashed_del
-                .iter()
-                .map(|d| SlashedBtcDelegation {
-                    staking_tx_hash: d.staking_tx_hash.clone(),
-                    recovered_fp_btc_sk: d.recovered_fp_btc_sk.clone(),
+            })
+            .collect();
+        let edit_fp: Vec<StdResult<_>> = btc_staking
+            .edit_fp
+            .iter()
+            .map(|fp| {
+                Ok(EditFinalityProvider {
+                    btc_pk_hex: fp.btc_pk_hex.clone(),
+                    description: fp
+                        .description
+                        .as_ref()
+                        .map(|d| FinalityProviderDescription {
+                            moniker: d.moniker.clone(),
+                            identity: d.identity.clone(),
+                            website: d.website.clone(),
+                            security_contact: d.security_contact.clone(),
+                            details: d.details.clone(),
+                        }),
+                    // An empty commission leaves the commission unchanged
+                    commission: (!fp.commission.is_empty())
+                        .then(|| Decimal::from_str(&fp.commission))
+                        .transpose()?,
                 })
-                .collect(),
-            unbonded_del: btc_staking
-                .unbonded_del
+            })
+            .collect();
+        let slashed_del: Vec<_> = btc_staking
+            .slashed_del
+            .iter()
+            .map(|d| SlashedBtcDelegation {
+                staking_tx_hash: d.staking_tx_hash.clone(),
+                recovered_fp_btc_sk: d.recovered_fp_btc_sk.clone(),
+            })
+            .collect();
+        let unbonded_del: Vec<_> = btc_staking
+            .unbonded_del
+            .iter()
+            .map(|u| UnbondedBtcDelegation {
+                staking_tx_hash: u.staking_tx_hash.clone(),
+                unbonding_tx_sig: u.unbonding_tx_sig.to_vec().into(),
+            })
+            .collect();
+        // Forward each item to the BTC staking contract on its own, so that an invalid item is
+        // reverted and reported in the acknowledgement, while the rest are still applied
+        let mut items = vec![];
+        items.extend(btc_staking.new_fp.iter().zip(new_fp).map(|(proto_fp, fp)| {
+            (
+                BtcStakingItemKind::NewFp,
+                proto_fp.btc_pk_hex.clone(),
+                fp.map(|fp| btc_staking_msg(vec![fp], vec![], vec![], vec![], vec![])),
+            )
+        }));
+        items.extend(
+            btc_staking
+                .edit_fp
                 .iter()
-                .map(|u| UnbondedBtcDelegation {
-                    staking_tx_hash: u.staking_tx_hash.clone(),
-                    unbonding_tx_sig: u.unbonding_tx_sig.to_vec().into(),
-                })
-                .collect(),
-        };
+                .zip(edit_fp)
+                .map(|(proto_fp, fp)| {
+                    (
+                        BtcStakingItemKind::EditFp,
+                        proto_fp.btc_pk_hex.clone(),
+                        fp.map(|fp| btc_staking_msg(vec![], vec![], vec![], vec![], vec![fp])),
+                    )
+                }),
+        );
+        items.extend(
+            btc_staking
+                .active_del
+                .iter()
+                .zip(active_del)
+                .map(|(proto_del, del)| {
+                    let id = babylon_bitcoin::deserialize::<babylon_bitcoin::Transaction>(
+                        &proto_del.staking_tx,
+                    )
+                    .map(|tx| tx.txid().to_string())
+                    .unwrap_or_default();
+                    (
+                        BtcStakingItemKind::ActiveDel,
+                        id,
+                        del.map(|del| btc_staking_msg(vec![], vec![del], vec![], vec![], vec![])),
+                    )
+                }),
+        );
+        items.extend(slashed_del.into_iter().map(|del| {
+            let id = del.staking_tx_hash.clone();
+            (
+                BtcStakingItemKind::SlashedDel,
+                id,
+                Ok(btc_staking_msg(vec![], vec![], vec![del], vec![], vec![])),
+            )
+        }));
+        items.extend(unbonded_del.into_iter().map(|del| {
+            let id = del.staking_tx_hash.clone();
+            (
+                BtcStakingItemKind::UnbondedDel,
+                id,
+                Ok(btc_staking_msg(vec![], vec![], vec![], vec![del], vec![])),
+            )
+        }));
 
-        let wasm_msg = WasmMsg::Execute {
-            contract_addr: btc_staking_addr.to_string(),
-            msg: to_json_binary(&msg)?,
-            funds: vec![],
-        };
+        let mut results = Vec::with_capacity(items.len());
+        let mut submsgs = Vec::with_capacity(items.len());
+        for (index, (kind, id, msg)) in items.into_iter().enumerate() {
+            let msg = match msg {
+                Ok(msg) => msg,
+                Err(err) => {
+                    results.push(BtcStakingItemResult {
+                        kind,
+                        id,
+                        error: Some(err.to_string()),
+                    });
+                    continue;
+                }
+            };
+            results.push(BtcStakingItemResult {
+                kind,
+                id,
+                error: None,
+            });
+            let wasm_msg = WasmMsg::Execute {
+                contract_addr: btc_staking_addr.to_string(),
+                msg: to_json_binary(&msg)?,
+                funds: vec![],
+            };
+            submsgs.push(
+                SubMsg::reply_on_error(wasm_msg, REPLY_ID_BTC_STAKING_ITEM)
+                    .with_payload(to_json_binary(&index)?),
+            );
+        }
+        // Items failing in the BTC staking contract are filled in by the reply handler
+        BTC_STAKING_RESULTS.save(storage, &results)?;
 
         // construct response
-        let mut resp: IbcReceiveResponse<BabylonMsg> =
-            IbcReceiveResponse::new(StdAck::success(vec![])); // TODO: design response format
-                                                              // add wasm message to response
-        resp = resp.add_message(wasm_msg);
+        let ack = if results.iter().any(|result| result.error.is_some()) {
+            StdAck::error(to_json_string(&results)?)
+        } else {
+            StdAck::success(vec![]) // TODO: design response format
+        };
+        let mut resp: IbcReceiveResponse<BabylonMsg> = IbcReceiveResponse::new(ack);
+        // add wasm messages to response
+        resp = resp.add_submessages(submsgs);
         // add attribute to response
         resp = resp.add_attribute("action", "receive_btc_staking");
-
         Ok(resp)
     }
 
+    fn btc_staking_msg(
+        new_fp: Vec<NewFinalityProvider>,
+        active_del: Vec<ActiveBtcDelegation>,
+        slashed_del: Vec<SlashedBtcDelegation>,
+        unbonded_del: Vec<UnbondedBtcDelegation>,
+        edit_fp: Vec<EditFinalityProvider>,
+    ) -> babylon_apis::btc_staking_api::ExecuteMsg {
+        babylon_apis::btc_staking_api::ExecuteMsg::BtcStaking {
+            new_fp,
+            active_del,
+            slashed_del,
+            unbonded_del,
+            edit_fp,
+            best_effort: None,
+        }
+    }
+
+    /// reply_btc_staking_item records the failure of a BTC staking item forwarded to the BTC
+    /// staking contract, and sets the acknowledgement of the BtcStaking packet accordingly.
+    ///
+    /// Reply data overrides the packet acknowledgement, and the last reply of the packet carries
+    /// all the failures, so the final acknowledgement is an error listing all the item results
+    pub fn reply_btc_staking_item(
+        deps: DepsMut,
+        reply: Reply,
+    ) -> Result<Response<BabylonMsg>, ContractError> {
+        let index: usize = from_json(&reply.payload)?;
+        let error = match reply.result {
+            SubMsgResult::Err(err) => err,
+            SubMsgResult::Ok(_) => return Ok(Response::new()),
+        };
+
+        let mut results = BTC_STAKING_RESULTS.load(deps.storage)?;
+        let item = results
+            .get_mut(index)
+            .ok_or_else(|| StdError::generic_err(format!("unknown BTC staking item {index}")))?;
+        item.error = Some(error);
+        BTC_STAKING_RESULTS.save(deps.storage, &results)?;
+
+        let ack = StdAck::error(to_json_string(&results)?);
+        Ok(Response::new()
+            .add_attribute("action", "btc_staking_item_failed")
+            .add_attribute("index", index.to_string())
+            .set_data(ack))
+    }
+
     pub fn slashing_msg(
         env: &Env,
         channel: &IbcChannel,
@@ -382,6 +610,25 @@ pub(crate) mod ibc_packet {
         };
         Ok(msg)
     }
+
+    pub fn consumer_update_msg(
+        env: &Env,
+        channel: &IbcChannel,
+        consumer_name: &str,
+        consumer_description: &str,
+    ) -> IbcMsg {
+        let packet = ZoneconciergePacketData {
+            packet: Some(ConsumerUpdate(ConsumerUpdateIbcPacket {
+                consumer_name: consumer_name.to_string(),
+                consumer_description: consumer_description.to_string(),
+            })),
+        };
+        IbcMsg::SendPacket {
+            channel_id: channel.endpoint.channel_id.clone(),
+            data: Binary::new(packet.encode_to_vec()),
+            timeout: packet_timeout(env),
+        }
+    }
 }
 
 const DEFAULT_TIMEOUT: u64 = 10 * 60;
@@ -392,18 +639,123 @@ pub fn packet_timeout(env: &Env) -> IbcTimeout {
 }
 
 pub fn ibc_packet_ack(
-    _deps: DepsMut,
-    _env: Env,
-    _msg: IbcPacketAckMsg,
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketAckMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
-    Ok(IbcBasicResponse::default())
+    let packet_data = msg.original_packet.data.as_slice();
+    if !is_slashing_packet(packet_data) {
+        return Ok(IbcBasicResponse::default());
+    }
+
+    let res = IbcBasicResponse::new().add_attribute("action", "slashing_ack");
+    let ack: StdAck = from_json(&msg.acknowledgement.data)?;
+    if ack.is_success() {
+        FAILED_SLASHING_PACKETS.remove(deps.storage, packet_data);
+        return Ok(res.add_attribute("result", "success"));
+    }
+
+    let result = record_failed_slashing_packet(deps.storage, &env, packet_data)?;
+    Ok(res.add_attribute("result", result))
 }
 
+fn is_slashing_packet(packet_data: &[u8]) -> bool {
+    matches!(
+        ZoneconciergePacketData::decode(packet_data),
+        Ok(ZoneconciergePacketData {
+            packet: Some(Packet::ConsumerSlashing(_))
+        })
+    )
+}
+
+/// record_failed_slashing_packet queues a rejected or timed out slashing packet for re-sending
+/// after a backoff, clearing its in-flight flag, or drops it once it has been retried
+/// `MAX_SLASHING_RETRIES` times.
+/// Returns the outcome, i.e. "retry" or "dropped"
+fn record_failed_slashing_packet(
+    storage: &mut dyn Storage,
+    env: &Env,
+    packet_data: &[u8],
+) -> StdResult<&'static str> {
+    let retry = FAILED_SLASHING_PACKETS.may_load(storage, packet_data)?;
+    match retry {
+        Some(retry) if retry.retries >= MAX_SLASHING_RETRIES => {
+            // Give up
+            FAILED_SLASHING_PACKETS.remove(storage, packet_data);
+            Ok("dropped")
+        }
+        _ => {
+            let retries = retry.map(|r| r.retries).unwrap_or_default();
+            FAILED_SLASHING_PACKETS.save(
+                storage,
+                packet_data,
+                &SlashingRetry {
+                    retries,
+                    in_flight: false,
+                    next_retry_height: env.block.height
+                        + (SLASHING_RETRY_BACKOFF_BLOCKS << retries),
+                },
+            )?;
+            Ok("retry")
+        }
+    }
+}
+
+/// retry_failed_slashing_packets re-sends the failed slashing packets that are not in flight and
+/// whose backoff has elapsed, up to `MAX_SLASHING_RETRIES_PER_BLOCK` of them.
+/// Nothing is sent while there is no open channel; the packets are kept for the next channel
+pub fn retry_failed_slashing_packets(
+    storage: &mut dyn Storage,
+    env: &Env,
+) -> Result<Vec<IbcMsg>, ContractError> {
+    let Some(channel) = IBC_CHANNEL.may_load(storage)? else {
+        return Ok(vec![]);
+    };
+    let to_retry = FAILED_SLASHING_PACKETS
+        .range(storage, None, None, Order::Ascending)
+        .filter(|item| {
+            !matches!(item, Ok((_, retry))
+                if retry.in_flight || retry.next_retry_height > env.block.height)
+        })
+        .take(MAX_SLASHING_RETRIES_PER_BLOCK)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut msgs = Vec::with_capacity(to_retry.len());
+    for (packet_data, retry) in to_retry {
+        FAILED_SLASHING_PACKETS.save(
+            storage,
+            &packet_data,
+            &SlashingRetry {
+                retries: retry.retries + 1,
+                in_flight: true,
+                ..retry
+            },
+        )?;
+        msgs.push(IbcMsg::SendPacket {
+            channel_id: channel.endpoint.channel_id.clone(),
+            data: Binary::new(packet_data),
+            timeout: packet_timeout(env),
+        });
+    }
+    Ok(msgs)
+}
+
+/// ibc_packet_timeout re-queues timed out slashing packets, as for rejected ones.
+/// As the channel is ordered, the timeout closes it, so they are only re-sent once a new channel
+/// is open
 pub fn ibc_packet_timeout(
-    _deps: DepsMut,
-    _env: Env,
+    deps: DepsMut,
+    env: Env,
     msg: IbcPacketTimeoutMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
+    let packet_data = msg.packet.data.as_slice();
+    if is_slashing_packet(packet_data) {
+        let result = record_failed_slashing_packet(deps.storage, &env, packet_data)?;
+        return Ok(IbcBasicResponse::new()
+            .add_attribute("action", "slashing_timeout")
+            .add_attribute("result", result));
+    }
+
     // TODO: handle the timeout / error
     Err(ContractError::IbcTimeout(
         msg.packet.dest.channel_id,
@@ -416,11 +768,13 @@ mod tests {
     use super::*;
     use crate::contract::instantiate;
     use crate::msg::contract::InstantiateMsg;
+    use babylon_proto::babylon::zoneconcierge::v1::ConsumerSlashingIbcPacket;
     use cosmwasm_std::testing::message_info;
     use cosmwasm_std::testing::{
-        mock_dependencies, mock_env, mock_ibc_channel_open_try, MockApi, MockQuerier, MockStorage,
+        mock_dependencies, mock_env, mock_ibc_channel, mock_ibc_channel_open_try, MockApi,
+        MockQuerier, MockStorage,
     };
-    use cosmwasm_std::OwnedDeps;
+    use cosmwasm_std::{Addr, IbcAcknowledgement, IbcPacket, OwnedDeps};
 
     const CREATOR: &str = "creator";
 
@@ -439,6 +793,7 @@ mod tests {
             admin: None,
             consumer_name: None,
             consumer_description: None,
+            signet_challenge: None,
         };
         let info = message_info(&deps.api.addr_make(CREATOR), &[]);
         let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -459,4 +814,382 @@ mod tests {
         let valid_handshake = mock_ibc_channel_open_try("channel-12", IBC_ORDERING, IBC_VERSION);
         ibc_channel_open(deps.as_mut(), mock_env(), valid_handshake).unwrap();
     }
+
+    fn slashing_packet(id: u8) -> Vec<u8> {
+        ZoneconciergePacketData {
+            packet: Some(Packet::ConsumerSlashing(ConsumerSlashingIbcPacket {
+                evidence: Some(babylon_proto::babylon::finality::v1::Evidence {
+                    block_height: id as u64,
+                    ..Default::default()
+                }),
+            })),
+        }
+        .encode_to_vec()
+    }
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn ibc_packet(channel: &IbcChannel, packet_data: &[u8]) -> IbcPacket {
+        IbcPacket::new(
+            packet_data.to_vec(),
+            channel.endpoint.clone(),
+            channel.counterparty_endpoint.clone(),
+            1,
+            packet_timeout(&mock_env()),
+        )
+    }
+
+    #[test]
+    fn failed_slashing_packets_are_retried() {
+        let mut deps = setup();
+        let channel = mock_ibc_channel("channel-12", IBC_ORDERING, IBC_VERSION);
+        IBC_CHANNEL.save(deps.as_mut().storage, &channel).unwrap();
+
+        let packet_data = slashing_packet(1);
+        let ack_msg = |ack: StdAck| {
+            IbcPacketAckMsg::new(
+                IbcAcknowledgement::new(ack),
+                ibc_packet(&channel, &packet_data),
+                Addr::unchecked("relayer"),
+            )
+        };
+
+        // A failed slashing packet is re-sent once its backoff has elapsed
+        let mut height = 100;
+        ibc_packet_ack(
+            deps.as_mut(),
+            env_at(height),
+            ack_msg(StdAck::error("fail")),
+        )
+        .unwrap();
+        let msgs =
+            retry_failed_slashing_packets(deps.as_mut().storage, &env_at(height + 1)).unwrap();
+        assert_eq!(0, msgs.len());
+        height += SLASHING_RETRY_BACKOFF_BLOCKS;
+        let msgs = retry_failed_slashing_packets(deps.as_mut().storage, &env_at(height)).unwrap();
+        assert_eq!(1, msgs.len());
+        // but only once while in flight
+        let msgs = retry_failed_slashing_packets(deps.as_mut().storage, &env_at(height)).unwrap();
+        assert_eq!(0, msgs.len());
+
+        // The backoff doubles with every retry, and retries are bounded
+        for retries in 1..MAX_SLASHING_RETRIES {
+            ibc_packet_ack(
+                deps.as_mut(),
+                env_at(height),
+                ack_msg(StdAck::error("fail")),
+            )
+            .unwrap();
+            let backoff = SLASHING_RETRY_BACKOFF_BLOCKS << retries;
+            let msgs =
+                retry_failed_slashing_packets(deps.as_mut().storage, &env_at(height + backoff - 1))
+                    .unwrap();
+            assert_eq!(0, msgs.len());
+            height += backoff;
+            let msgs =
+                retry_failed_slashing_packets(deps.as_mut().storage, &env_at(height)).unwrap();
+            assert_eq!(1, msgs.len());
+        }
+        ibc_packet_ack(
+            deps.as_mut(),
+            env_at(height),
+            ack_msg(StdAck::error("fail")),
+        )
+        .unwrap();
+        assert!(!FAILED_SLASHING_PACKETS.has(&deps.storage, &packet_data));
+
+        // A successful retry clears the failed packet
+        ibc_packet_ack(
+            deps.as_mut(),
+            env_at(height),
+            ack_msg(StdAck::error("fail")),
+        )
+        .unwrap();
+        ibc_packet_ack(
+            deps.as_mut(),
+            env_at(height),
+            ack_msg(StdAck::success(b"\x01")),
+        )
+        .unwrap();
+        assert!(!FAILED_SLASHING_PACKETS.has(&deps.storage, &packet_data));
+    }
+
+    #[test]
+    fn slashing_retries_per_block_are_bounded() {
+        let mut deps = setup();
+        let channel = mock_ibc_channel("channel-12", IBC_ORDERING, IBC_VERSION);
+        IBC_CHANNEL.save(deps.as_mut().storage, &channel).unwrap();
+
+        let failed = MAX_SLASHING_RETRIES_PER_BLOCK + 2;
+        for id in 0..failed {
+            let msg = IbcPacketAckMsg::new(
+                IbcAcknowledgement::new(StdAck::error("fail")),
+                ibc_packet(&channel, &slashing_packet(id as u8)),
+                Addr::unchecked("relayer"),
+            );
+            ibc_packet_ack(deps.as_mut(), env_at(100), msg).unwrap();
+        }
+
+        let env = env_at(100 + SLASHING_RETRY_BACKOFF_BLOCKS);
+        let msgs = retry_failed_slashing_packets(deps.as_mut().storage, &env).unwrap();
+        assert_eq!(MAX_SLASHING_RETRIES_PER_BLOCK, msgs.len());
+        // The rest are re-sent in the next block
+        let msgs = retry_failed_slashing_packets(deps.as_mut().storage, &env).unwrap();
+        assert_eq!(failed - MAX_SLASHING_RETRIES_PER_BLOCK, msgs.len());
+    }
+
+    #[test]
+    fn timed_out_slashing_packets_are_retried_on_a_new_channel() {
+        let mut deps = setup();
+        let channel = mock_ibc_channel("channel-12", IBC_ORDERING, IBC_VERSION);
+        IBC_CHANNEL.save(deps.as_mut().storage, &channel).unwrap();
+
+        let packet_data = slashing_packet(1);
+        let timeout_msg = |channel: &IbcChannel| {
+            IbcPacketTimeoutMsg::new(
+                ibc_packet(channel, &packet_data),
+                Addr::unchecked("relayer"),
+            )
+        };
+
+        // The timeout closes the ordered channel
+        ibc_packet_timeout(deps.as_mut(), env_at(100), timeout_msg(&channel)).unwrap();
+        let close_msg = IbcChannelCloseMsg::new_confirm(channel.clone());
+        ibc_channel_close(deps.as_mut(), env_at(100), close_msg).unwrap();
+        assert!(!IBC_CHANNEL.exists(&deps.storage));
+
+        // The timed out packet is kept, but not sent while there is no channel
+        let height = 100 + SLASHING_RETRY_BACKOFF_BLOCKS;
+        let msgs = retry_failed_slashing_packets(deps.as_mut().storage, &env_at(height)).unwrap();
+        assert_eq!(0, msgs.len());
+        let retry = FAILED_SLASHING_PACKETS
+            .load(&deps.storage, &packet_data)
+            .unwrap();
+        assert!(!retry.in_flight);
+        assert_eq!(retry.retries, 0);
+
+        // It is re-sent over the new channel
+        let new_channel = mock_ibc_channel("channel-13", IBC_ORDERING, IBC_VERSION);
+        IBC_CHANNEL
+            .save(deps.as_mut().storage, &new_channel)
+            .unwrap();
+        let msgs = retry_failed_slashing_packets(deps.as_mut().storage, &env_at(height)).unwrap();
+        assert_eq!(1, msgs.len());
+        assert!(matches!(
+            &msgs[0],
+            IbcMsg::SendPacket { channel_id, .. } if channel_id == "channel-13"
+        ));
+
+        // Timed out retries are bounded too
+        let mut height = height;
+        for retries in 1..MAX_SLASHING_RETRIES {
+            ibc_packet_timeout(deps.as_mut(), env_at(height), timeout_msg(&new_channel)).unwrap();
+            height += SLASHING_RETRY_BACKOFF_BLOCKS << retries;
+            let msgs =
+                retry_failed_slashing_packets(deps.as_mut().storage, &env_at(height)).unwrap();
+            assert_eq!(1, msgs.len());
+        }
+        ibc_packet_timeout(deps.as_mut(), env_at(height), timeout_msg(&new_channel)).unwrap();
+        assert!(!FAILED_SLASHING_PACKETS.has(&deps.storage, &packet_data));
+    }
+
+    #[test]
+    fn closing_another_channel_keeps_the_open_one() {
+        let mut deps = setup();
+        let channel = mock_ibc_channel("channel-12", IBC_ORDERING, IBC_VERSION);
+        IBC_CHANNEL.save(deps.as_mut().storage, &channel).unwrap();
+
+        let other = mock_ibc_channel("channel-99", IBC_ORDERING, IBC_VERSION);
+        let close_msg = IbcChannelCloseMsg::new_confirm(other);
+        ibc_channel_close(deps.as_mut(), mock_env(), close_msg).unwrap();
+        assert_eq!(IBC_CHANNEL.load(&deps.storage).unwrap(), channel);
+    }
+
+    #[test]
+    fn btc_staking_items_are_forwarded_and_acked_one_by_one() {
+        use crate::contract::{reply, REPLY_ID_BTC_STAKING_ITEM};
+        use babylon_apis::btc_staking_api::BtcStakingItemKind;
+        use babylon_proto::babylon::btcstaking::v1::{
+            BtcStakingIbcPacket, EditFinalityProvider, NewFinalityProvider, UnbondedBtcDelegation,
+        };
+        use cosmwasm_std::{
+            to_json_binary, CosmosMsg, Decimal, Reply, SubMsgResponse, SubMsgResult, WasmMsg,
+        };
+
+        let mut deps = setup();
+        CONFIG
+            .update(deps.as_mut().storage, |mut cfg| {
+                cfg.btc_staking = Some(Addr::unchecked("btc_staking"));
+                Ok::<_, StdError>(cfg)
+            })
+            .unwrap();
+
+        let packet = BtcStakingIbcPacket {
+            new_fp: vec![NewFinalityProvider {
+                commission: "0.05".to_string(),
+                btc_pk_hex: "f1".to_string(),
+                ..Default::default()
+            }],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![UnbondedBtcDelegation {
+                staking_tx_hash: "d1".to_string(),
+                ..Default::default()
+            }],
+            edit_fp: vec![EditFinalityProvider {
+                btc_pk_hex: "f1".to_string(),
+                commission: "0.1".to_string(),
+                ..Default::default()
+            }],
+        };
+        let res = ibc_packet::handle_btc_staking(deps.as_mut(), "channel-1".to_string(), &packet)
+            .unwrap();
+        assert_eq!(3, res.messages.len());
+        assert_eq!(
+            Some(StdAck::success(vec![])),
+            res.acknowledgement.map(|ack| from_json(ack).unwrap())
+        );
+
+        // The finality provider edit is forwarded, leaving the description unchanged
+        let CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) = &res.messages[1].msg else {
+            panic!("expected a wasm execute msg");
+        };
+        let babylon_apis::btc_staking_api::ExecuteMsg::BtcStaking { edit_fp, .. } =
+            from_json(msg).unwrap()
+        else {
+            panic!("expected a BtcStaking msg");
+        };
+        assert_eq!(
+            edit_fp,
+            vec![babylon_apis::btc_staking_api::EditFinalityProvider {
+                btc_pk_hex: "f1".to_string(),
+                description: None,
+                commission: Some(Decimal::percent(10)),
+            }]
+        );
+
+        // The last item fails
+        let failed = &res.messages[2];
+        assert_eq!(REPLY_ID_BTC_STAKING_ITEM, failed.id);
+        let reply_msg = Reply {
+            id: failed.id,
+            payload: failed.payload.clone(),
+            gas_used: 0,
+            result: SubMsgResult::Err("delegation not found".to_string()),
+        };
+        let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+        let ack: StdAck = from_json(res.data.unwrap()).unwrap();
+        let StdAck::Error(err) = ack else {
+            panic!("expected an error ack");
+        };
+        let results: Vec<BtcStakingItemResult> = from_json(err.as_bytes()).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                BtcStakingItemResult {
+                    kind: BtcStakingItemKind::NewFp,
+                    id: "f1".to_string(),
+                    error: None,
+                },
+                BtcStakingItemResult {
+                    kind: BtcStakingItemKind::EditFp,
+                    id: "f1".to_string(),
+                    error: None,
+                },
+                BtcStakingItemResult {
+                    kind: BtcStakingItemKind::UnbondedDel,
+                    id: "d1".to_string(),
+                    error: Some("delegation not found".to_string()),
+                },
+            ]
+        );
+
+        // Successful items don't change the ack
+        #[allow(deprecated)]
+        let reply_msg = Reply {
+            id: REPLY_ID_BTC_STAKING_ITEM,
+            payload: to_json_binary(&0usize).unwrap(),
+            gas_used: 0,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+                msg_responses: vec![],
+            }),
+        };
+        let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+        assert_eq!(None, res.data);
+    }
+
+    #[test]
+    fn invalid_btc_staking_items_are_reported_without_failing_the_packet() {
+        use babylon_apis::btc_staking_api::BtcStakingItemKind;
+        use babylon_proto::babylon::btcstaking::v1::{
+            ActiveBtcDelegation, BtcStakingIbcPacket, NewFinalityProvider, UnbondedBtcDelegation,
+        };
+        use cosmwasm_std::to_json_binary;
+
+        let mut deps = setup();
+        CONFIG
+            .update(deps.as_mut().storage, |mut cfg| {
+                cfg.btc_staking = Some(Addr::unchecked("btc_staking"));
+                Ok::<_, StdError>(cfg)
+            })
+            .unwrap();
+
+        let packet = BtcStakingIbcPacket {
+            // Invalid commission
+            new_fp: vec![NewFinalityProvider {
+                commission: "five percent".to_string(),
+                btc_pk_hex: "f1".to_string(),
+                ..Default::default()
+            }],
+            // Missing undelegation info
+            active_del: vec![ActiveBtcDelegation::default()],
+            slashed_del: vec![],
+            unbonded_del: vec![UnbondedBtcDelegation {
+                staking_tx_hash: "d1".to_string(),
+                ..Default::default()
+            }],
+            edit_fp: vec![],
+        };
+        let res = ibc_packet::handle_btc_staking(deps.as_mut(), "channel-1".to_string(), &packet)
+            .unwrap();
+
+        // Only the valid item is forwarded, with its original index
+        assert_eq!(1, res.messages.len());
+        assert_eq!(
+            to_json_binary(&2usize).unwrap(),
+            res.messages[0].payload.clone()
+        );
+
+        // The invalid items are reported in the ack
+        let ack: StdAck = from_json(res.acknowledgement.unwrap()).unwrap();
+        let StdAck::Error(err) = ack else {
+            panic!("expected an error ack");
+        };
+        let results: Vec<BtcStakingItemResult> = from_json(err.as_bytes()).unwrap();
+        assert_eq!(3, results.len());
+        assert_eq!(BtcStakingItemKind::NewFp, results[0].kind);
+        assert_eq!("f1", results[0].id);
+        assert!(results[0].error.is_some());
+        assert_eq!(BtcStakingItemKind::ActiveDel, results[1].kind);
+        assert!(results[1]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("undelegation info not set"));
+        assert_eq!(
+            BtcStakingItemResult {
+                kind: BtcStakingItemKind::UnbondedDel,
+                id: "d1".to_string(),
+                error: None,
+            },
+            results[2]
+        );
+        assert_eq!(results, BTC_STAKING_RESULTS.load(&deps.storage).unwrap());
+    }
 }
@@ -1,7 +1,7 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    Binary, Deps, DepsMut, Empty, Env, IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg,
+    Binary, Deps, DepsMut, Env, IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg,
     IbcChannelOpenMsg, IbcChannelOpenResponse, IbcPacketAckMsg, IbcPacketReceiveMsg,
     IbcPacketTimeoutMsg, IbcReceiveResponse, MessageInfo, Never, Reply, Response, StdResult,
 };
@@ -10,7 +10,7 @@ use babylon_bindings::BabylonMsg;
 
 use crate::error::ContractError;
 pub use crate::msg::contract::ExecuteMsg;
-use crate::msg::contract::InstantiateMsg;
+use crate::msg::contract::{InstantiateMsg, SudoMsg};
 
 mod bindings;
 pub mod contract;
@@ -44,7 +44,11 @@ pub fn query(deps: Deps, env: Env, msg: msg::contract::QueryMsg) -> Result<Binar
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, env: Env, msg: Empty) -> Result<Response<BabylonMsg>, ContractError> {
+pub fn migrate(
+    deps: DepsMut,
+    env: Env,
+    msg: msg::contract::MigrateMsg,
+) -> Result<Response<BabylonMsg>, ContractError> {
     contract::migrate(deps, env, msg)
 }
 
@@ -58,6 +62,11 @@ pub fn execute(
     contract::execute(deps, env, info, msg)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response<BabylonMsg>, ContractError> {
+    contract::sudo(deps, env, msg)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_channel_open(
     deps: DepsMut,
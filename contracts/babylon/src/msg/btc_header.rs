@@ -40,6 +40,20 @@ pub struct BtcHeader {
     pub nonce: u32,
 }
 
+/// Signet solution proof of a Bitcoin header.
+///
+/// Signet headers alone do not carry the block signature, which lives in the block's coinbase tx.
+/// This struct carries the coinbase tx along with its merkle proof, so that the block signature
+/// can be verified against the signet challenge (BIP325).
+#[cw_serde]
+pub struct SignetProof {
+    /// The hex-encoded coinbase tx of the block.
+    pub coinbase_tx: String,
+    /// The merkle proof of the coinbase tx, i.e. the hex-encoded sibling hashes (in internal byte
+    /// order) from the coinbase tx up to the merkle root.
+    pub coinbase_merkle_proof: Vec<String>,
+}
+
 impl BtcHeader {
     pub fn to_btc_header_info(
         &self,
@@ -3,7 +3,7 @@ use cosmwasm_std::{Binary, StdError, StdResult};
 
 use babylon_apis::finality_api::Evidence;
 
-use crate::msg::btc_header::BtcHeader;
+use crate::msg::btc_header::{BtcHeader, SignetProof};
 #[cfg(not(target_arch = "wasm32"))]
 use {
     crate::msg::btc_header::{BtcHeaderResponse, BtcHeadersResponse},
@@ -18,6 +18,23 @@ const BABYLON_TAG_BYTES: usize = 4;
 pub trait ContractMsg {
     fn validate(&self) -> StdResult<()>;
     fn babylon_tag_to_bytes(&self) -> StdResult<Vec<u8>>;
+    fn signet_challenge_to_bytes(&self) -> StdResult<Option<Vec<u8>>>;
+}
+
+/// validate_consumer_metadata ensures the Consumer name and description are not empty
+pub fn validate_consumer_metadata(
+    consumer_name: &str,
+    consumer_description: &str,
+) -> StdResult<()> {
+    if consumer_name.trim().is_empty() {
+        return Err(StdError::generic_err("Consumer name cannot be empty"));
+    }
+    if consumer_description.trim().is_empty() {
+        return Err(StdError::generic_err(
+            "Consumer description cannot be empty",
+        ));
+    }
+    Ok(())
 }
 
 #[cw_serde]
@@ -45,13 +62,17 @@ pub struct InstantiateMsg {
     /// This message is opaque to the Babylon contract, and depends on the specific finality contract
     /// being instantiated
     pub btc_finality_msg: Option<Binary>,
-    /// If set, this will be the Wasm migration / upgrade admin of the BTC staking contract and the
-    /// BTC finality contract
+    /// If set, this will be the admin of the contract, and the Wasm migration / upgrade admin of
+    /// the BTC staking contract and the BTC finality contract
     pub admin: Option<String>,
     /// Name of the consumer
     pub consumer_name: Option<String>,
     /// Description of the consumer
     pub consumer_description: Option<String>,
+    /// If set, this is the (hex-encoded) signet challenge script that BTC headers submitted by
+    /// users have to satisfy, as per BIP325.
+    /// Only valid for the signet network, or custom networks based on it
+    pub signet_challenge: Option<String>,
 }
 
 impl ContractMsg for InstantiateMsg {
@@ -64,18 +85,24 @@ impl ContractMsg for InstantiateMsg {
         }
         let _ = self.babylon_tag_to_bytes()?;
 
+        self.network
+            .validate()
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+        if self.signet_challenge.is_some() {
+            if !self.network.is_signet() {
+                return Err(StdError::generic_err(
+                    "Signet challenge is only supported on the signet network",
+                ));
+            }
+            let _ = self.signet_challenge_to_bytes()?;
+        }
+
         if self.btc_staking_code_id.is_some() {
             if let (Some(consumer_name), Some(consumer_description)) =
                 (&self.consumer_name, &self.consumer_description)
             {
-                if consumer_name.trim().is_empty() {
-                    return Err(StdError::generic_err("Consumer name cannot be empty"));
-                }
-                if consumer_description.trim().is_empty() {
-                    return Err(StdError::generic_err(
-                        "Consumer description cannot be empty",
-                    ));
-                }
+                validate_consumer_metadata(consumer_name, consumer_description)?;
             } else {
                 return Err(StdError::generic_err(
                     "Consumer name and description are required when btc_staking_code_id is set",
@@ -94,6 +121,28 @@ impl ContractMsg for InstantiateMsg {
             ))
         })
     }
+
+    fn signet_challenge_to_bytes(&self) -> StdResult<Option<Vec<u8>>> {
+        self.signet_challenge
+            .as_ref()
+            .map(|challenge| {
+                hex::decode(challenge).map_err(|_| {
+                    StdError::generic_err(format!(
+                        "signet_challenge is not a valid hex string: {challenge}"
+                    ))
+                })
+            })
+            .transpose()
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {
+    /// `admin` is the address that can update the Consumer metadata.
+    /// If not set, the current admin is kept, or the contract's wasm admin is used if there is none
+    /// (e.g. when upgrading from a version without an admin)
+    pub admin: Option<String>,
 }
 
 #[cw_serde]
@@ -102,12 +151,35 @@ pub enum ExecuteMsg {
         /// `headers` is a list of BTC headers. Typically:
         /// - A given delta of headers a user wants to add to the tip or fork of the BTC chain.
         headers: Vec<BtcHeader>,
+        /// `signet_proofs` are the signet solutions of the headers, one per header and in the same
+        /// order.
+        /// Required if the contract is configured with a signet challenge, ignored otherwise
+        signet_proofs: Option<Vec<SignetProof>>,
     },
     /// `slashing` is a slashing event from the BTC staking contract.
     ///
     /// This will be forwarded over IBC to the Babylon side for propagation to other Consumers, and
     /// Babylon itself
     Slashing { evidence: Evidence },
+    /// `update_consumer_metadata` updates the Consumer's name and description, and announces them
+    /// to Babylon over IBC, if the channel is open.
+    /// Only the admin can update the Consumer metadata
+    UpdateConsumerMetadata {
+        consumer_name: String,
+        consumer_description: String,
+    },
+}
+
+#[cw_serde]
+pub enum SudoMsg {
+    /// The SDK should call SudoMsg::BeginBlock{} once per block (in BeginBlock).
+    /// It allows the contract to record the Consumer's own block for cross-checking the CZ headers
+    /// finalised through Babylon, and to do periodic maintenance, like pruning old state and
+    /// retrying failed slashing packets
+    BeginBlock {
+        hash_hex: String,
+        app_hash_hex: String,
+    },
 }
 
 #[cw_serde]
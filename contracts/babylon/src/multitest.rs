@@ -52,13 +52,13 @@ mod instantiation {
 
 mod migration {
     use super::*;
-    use cosmwasm_std::Empty;
+    use crate::msg::contract::MigrateMsg;
 
     #[test]
     fn migrate_works() {
         let mut suite = SuiteBuilder::new().build();
         let admin = suite.admin().to_string();
 
-        suite.migrate(&admin, Empty {}).unwrap();
+        suite.migrate(&admin, MigrateMsg::default()).unwrap();
     }
 }
@@ -1,37 +1,39 @@
-use crate::msg::contract::{InstantiateMsg, QueryMsg};
+use crate::msg::contract::{InstantiateMsg, MigrateMsg, QueryMsg};
 use crate::multitest::{CONTRACT1_ADDR, CONTRACT2_ADDR};
 use crate::state::config::Config;
 use anyhow::Result as AnyResult;
-use babylon_bindings::BabylonMsg;
-use babylon_bindings_test::BabylonApp;
+use babylon_bindings::{BabylonMsg, BabylonQuery};
+use babylon_bindings_test::{BabylonApp, EmptyQueryContract};
 use babylon_bitcoin::chain_params::Network;
-use cosmwasm_std::{Addr, Empty};
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::Addr;
 use cw_multi_test::{AppResponse, Contract, ContractWrapper, Executor};
 use derivative::Derivative;
 
-fn contract_btc_staking() -> Box<dyn Contract<BabylonMsg>> {
+fn contract_btc_staking() -> Box<dyn Contract<BabylonMsg, BabylonQuery>> {
     let contract = ContractWrapper::new(
         btc_staking::contract::execute,
         btc_staking::contract::instantiate,
         btc_staking::contract::query,
     );
-    Box::new(contract)
+    Box::new(EmptyQueryContract::new(contract))
 }
 
-fn contract_btc_finality() -> Box<dyn Contract<BabylonMsg>> {
+fn contract_btc_finality() -> Box<dyn Contract<BabylonMsg, BabylonQuery>> {
     let contract = ContractWrapper::new(
         btc_finality::contract::execute,
         btc_finality::contract::instantiate,
         btc_finality::contract::query,
     );
-    Box::new(contract)
+    Box::new(EmptyQueryContract::new(contract))
 }
 
-fn contract_babylon() -> Box<dyn Contract<BabylonMsg>> {
+fn contract_babylon() -> Box<dyn Contract<BabylonMsg, BabylonQuery>> {
     let contract = ContractWrapper::new(crate::execute, crate::instantiate, crate::query)
+        .with_sudo(crate::sudo)
         .with_reply(crate::reply)
         .with_migrate(crate::migrate);
-    Box::new(contract)
+    Box::new(EmptyQueryContract::new(contract))
 }
 
 #[derive(Derivative)]
@@ -52,7 +54,7 @@ impl SuiteBuilder {
     pub fn build(self) -> Suite {
         let _funds = self.funds;
 
-        let owner = Addr::unchecked("owner");
+        let owner = MockApi::default().addr_make("owner");
 
         let mut app = BabylonApp::new(owner.as_str());
 
@@ -83,6 +85,7 @@ impl SuiteBuilder {
                     admin: Some(owner.to_string()),
                     consumer_name: Some("TestConsumer".to_string()),
                     consumer_description: Some("Test Consumer Description".to_string()),
+                    signet_challenge: None,
                 },
                 &[],
                 "babylon",
@@ -141,7 +144,7 @@ impl Suite {
             .unwrap()
     }
 
-    pub fn migrate(&mut self, addr: &str, msg: Empty) -> AnyResult<AppResponse> {
+    pub fn migrate(&mut self, addr: &str, msg: MigrateMsg) -> AnyResult<AppResponse> {
         self.app.migrate_contract(
             Addr::unchecked(addr),
             self.contract.clone(),
@@ -1,5 +1,5 @@
 //! btc_light_client is the storage for the BTC header chain
-use babylon_bitcoin::{BlockHash, BlockHeader};
+use babylon_bitcoin::{BlockHash, BlockHeader, ScriptBuf, Transaction};
 use prost::Message;
 use std::str::FromStr;
 
@@ -11,7 +11,7 @@ use hex::ToHex;
 use babylon_proto::babylon::btclightclient::v1::BtcHeaderInfo;
 
 use crate::error::BTCLightclientError;
-use crate::msg::btc_header::BtcHeader;
+use crate::msg::btc_header::{BtcHeader, SignetProof};
 use crate::state::config::CONFIG;
 use crate::utils::btc_light_client::{total_work, verify_headers, zero_work};
 
@@ -210,7 +210,10 @@ pub fn init(
 pub fn init_from_user(
     storage: &mut dyn Storage,
     headers: &[BtcHeader],
+    signet_proofs: Option<&[SignetProof]>,
 ) -> Result<(), BTCLightclientError> {
+    verify_signet_proofs(storage, headers, signet_proofs)?;
+
     let mut prev_height = 0;
     let mut prev_work = zero_work();
     let headers = headers
@@ -311,7 +314,10 @@ pub fn handle_btc_headers_from_babylon(
 pub fn handle_btc_headers_from_user(
     storage: &mut dyn Storage,
     new_btc_headers: &[BtcHeader],
+    signet_proofs: Option<&[SignetProof]>,
 ) -> Result<(), BTCLightclientError> {
+    verify_signet_proofs(storage, new_btc_headers, signet_proofs)?;
+
     let first_new_btc_header = new_btc_headers
         .first()
         .ok_or(BTCLightclientError::BTCHeaderEmpty {})?;
@@ -337,6 +343,54 @@ pub fn handle_btc_headers_from_user(
     handle_btc_headers_from_babylon(storage, &new_headers_info)
 }
 
+/// verify_signet_proofs ensures that each of the given BTC headers carries a valid signet
+/// solution, if the contract is configured with a signet challenge.
+///
+/// Only headers submitted by users are subject to this check. Headers from Babylon have been
+/// verified by Babylon already.
+fn verify_signet_proofs(
+    storage: &dyn Storage,
+    btc_headers: &[BtcHeader],
+    signet_proofs: Option<&[SignetProof]>,
+) -> Result<(), BTCLightclientError> {
+    let cfg = CONFIG.load(storage)?;
+    let Some(challenge) = cfg.signet_challenge else {
+        return Ok(());
+    };
+    let challenge = ScriptBuf::from_bytes(challenge);
+
+    let signet_proofs = signet_proofs.unwrap_or_default();
+    if signet_proofs.len() != btc_headers.len() {
+        return Err(BTCLightclientError::SignetProofsMismatch(
+            btc_headers.len(),
+            signet_proofs.len(),
+        ));
+    }
+
+    for (btc_header, signet_proof) in btc_headers.iter().zip(signet_proofs) {
+        let block_header: BlockHeader = btc_header.try_into()?;
+        let coinbase_tx: Transaction = babylon_bitcoin::deserialize(&hex::decode(
+            &signet_proof.coinbase_tx,
+        )?)
+        .map_err(|_| BTCLightclientError::SignetSolutionError("invalid coinbase tx".to_string()))?;
+        let merkle_proof = signet_proof
+            .coinbase_merkle_proof
+            .iter()
+            .map(hex::decode)
+            .collect::<Result<Vec<_>, _>>()?;
+        let merkle_proof: Vec<&[u8]> = merkle_proof.iter().map(Vec::as_slice).collect();
+
+        babylon_bitcoin::signet::verify_signet_solution(
+            &challenge,
+            &block_header,
+            &coinbase_tx,
+            &merkle_proof,
+        )
+        .map_err(BTCLightclientError::SignetSolutionError)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -360,6 +414,7 @@ pub(crate) mod tests {
             btc_finality: None,
             consumer_name: None,
             consumer_description: None,
+            signet_challenge: None,
         };
         CONFIG.save(storage, &cfg).unwrap();
         w
@@ -369,8 +424,8 @@ pub(crate) mod tests {
         let testdata = get_btc_lc_fork_msg();
         let resp: ExecuteMsg = from_json(testdata).unwrap();
         match resp {
-            ExecuteMsg::BtcHeaders { headers } => headers,
-            ExecuteMsg::Slashing { .. } => unreachable!("unexpected slashing message"),
+            ExecuteMsg::BtcHeaders { headers, .. } => headers,
+            _ => unreachable!("unexpected message"),
         }
     }
 
@@ -686,7 +741,7 @@ pub(crate) mod tests {
         let test_fork_msg_headers = get_fork_msg_test_headers();
 
         // handling fork headers
-        handle_btc_headers_from_user(&mut storage, &test_fork_msg_headers).unwrap();
+        handle_btc_headers_from_user(&mut storage, &test_fork_msg_headers, None).unwrap();
 
         // ensure the base header is set
         let base_expected = test_headers.first().unwrap();
@@ -710,4 +765,32 @@ pub(crate) mod tests {
             assert!(get_header_height(&storage, header_expected.hash.as_ref()).is_err());
         }
     }
+
+    // btc_lc_signet_proofs_required checks that, with a signet challenge configured, headers
+    // submitted by users without signet proofs are rejected.
+    #[test]
+    fn btc_lc_signet_proofs_required() {
+        let deps = mock_dependencies();
+        let mut storage = deps.storage;
+        setup(&mut storage);
+        CONFIG
+            .update(&mut storage, |mut cfg| -> StdResult<_> {
+                cfg.signet_challenge = Some(vec![0x51]);
+                Ok(cfg)
+            })
+            .unwrap();
+
+        let test_headers = get_btc_lc_headers();
+        init(&mut storage, &test_headers).unwrap();
+
+        let test_fork_msg_headers = get_fork_msg_test_headers();
+        let res = handle_btc_headers_from_user(&mut storage, &test_fork_msg_headers, None);
+        assert_eq!(
+            res.unwrap_err(),
+            BTCLightclientError::SignetProofsMismatch(test_fork_msg_headers.len(), 0)
+        );
+
+        // ensure base and tip are unchanged
+        ensure_base_and_tip(&storage, &test_headers);
+    }
 }
@@ -1,8 +1,11 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Addr;
+use cw_controllers::Admin;
 use cw_storage_plus::Item;
 
 pub(crate) const CONFIG: Item<Config> = Item::new("config");
+/// Storage for admin
+pub(crate) const ADMIN: Admin = Admin::new("admin");
 
 // TODO: Add necessary config entries to Config struct
 #[cw_serde]
@@ -23,4 +26,6 @@ pub struct Config {
     pub consumer_name: Option<String>,
     /// Consumer description
     pub consumer_description: Option<String>,
+    /// If set, the signet challenge script that BTC headers submitted by users have to satisfy
+    pub signet_challenge: Option<Vec<u8>>,
 }
@@ -4,8 +4,9 @@
 use prost::Message;
 use tendermint_proto::crypto::ProofOps;
 
-use cosmwasm_std::{StdResult, Storage};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Item, Map};
 
 use babylon_proto::babylon::epoching::v1::Epoch;
 use babylon_proto::babylon::zoneconcierge::v1::IndexedHeader;
@@ -15,6 +16,22 @@ use crate::{error, utils};
 
 pub const CZ_HEADERS: Map<u64, Vec<u8>> = Map::new("cz_headers");
 pub const CZ_HEADER_LAST: Item<Vec<u8>> = Item::new("cz_header_last");
+/// CZ_BLOCKS are the Consumer's own recent blocks, as reported by the Consumer chain itself
+/// through the `BeginBlock` sudo hook
+pub const CZ_BLOCKS: Map<u64, CzBlock> = Map::new("cz_blocks");
+
+/// How many of the Consumer's own blocks are kept for cross-checking CZ headers.
+/// CZ headers are finalised when their Babylon epoch is w-deep on BTC, so this has to cover
+/// (at least) that delay
+pub const CZ_BLOCKS_RETENTION: u64 = 100_000;
+/// Max number of expired CZ blocks pruned per block, to bound gas usage
+const CZ_BLOCKS_PRUNE_LIMIT: usize = 10;
+
+#[cw_serde]
+pub struct CzBlock {
+    pub hash: Vec<u8>,
+    pub app_hash: Vec<u8>,
+}
 
 // getter/setter for last finalised CZ header
 pub fn get_last_cz_header(
@@ -62,6 +79,15 @@ fn verify_cz_header(
     // check if the corresponding CZ header is in the Babylon epoch
     utils::cz_header_chain::verify_cz_header_in_epoch(cz_header, epoch, proof_cz_header_in_epoch)?;
 
+    // cross-check the CZ header against the Consumer's own block, if still recorded
+    if let Some(cz_block) = CZ_BLOCKS.may_load(storage, cz_header.height)? {
+        if cz_block.hash != cz_header.hash.as_ref() {
+            return Err(error::CZHeaderChainError::CZHeaderHashMismatch {
+                height: cz_header.height,
+            });
+        }
+    }
+
     // TODO: check if IndexedHeader is conflicted or not. Still not sure if this check should happen
     // in a relayer/monitor or the smart contract, given that smart contract has no access to the
     // Tendermint ledger
@@ -78,6 +104,30 @@ fn insert_cz_header(storage: &mut dyn Storage, cz_header: &IndexedHeader) -> Std
     set_last_cz_header(storage, cz_header)
 }
 
+/// record_cz_block records the Consumer's own block at the given height, and prunes the blocks
+/// that are older than the retention window
+pub fn record_cz_block(
+    storage: &mut dyn Storage,
+    height: u64,
+    cz_block: &CzBlock,
+) -> StdResult<()> {
+    CZ_BLOCKS.save(storage, height, cz_block)?;
+
+    let expired = CZ_BLOCKS
+        .keys(
+            storage,
+            None,
+            Some(Bound::exclusive(height.saturating_sub(CZ_BLOCKS_RETENTION))),
+            Order::Ascending,
+        )
+        .take(CZ_BLOCKS_PRUNE_LIMIT)
+        .collect::<StdResult<Vec<_>>>()?;
+    for expired_height in expired {
+        CZ_BLOCKS.remove(storage, expired_height);
+    }
+    Ok(())
+}
+
 // TODO: unit test
 pub fn handle_cz_header(
     storage: &mut dyn Storage,
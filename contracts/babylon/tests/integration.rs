@@ -44,6 +44,7 @@ fn setup() -> Instance<MockApi, MockStorage, MockQuerier> {
         babylon_tag: "01020304".to_string(),
         consumer_name: None,
         consumer_description: None,
+        signet_challenge: None,
         btc_confirmation_depth: 10,
         checkpoint_finalization_timeout: 99,
         notify_cosmos_zone: false,
@@ -74,8 +75,8 @@ fn get_fork_msg_test_headers() -> Vec<BtcHeader> {
     let testdata = get_btc_lc_fork_msg();
     let resp: ExecuteMsg = from_json(testdata).unwrap();
     match resp {
-        ExecuteMsg::BtcHeaders { headers } => headers,
-        ExecuteMsg::Slashing { .. } => unreachable!("unexpected slashing message"),
+        ExecuteMsg::BtcHeaders { headers, .. } => headers,
+        _ => unreachable!("unexpected message"),
     }
 }
 
@@ -98,6 +99,7 @@ fn instantiate_works() {
         babylon_tag: "01020304".to_string(),
         consumer_name: None,
         consumer_description: None,
+        signet_challenge: None,
         btc_confirmation_depth: 10,
         checkpoint_finalization_timeout: 100,
         notify_cosmos_zone: false,
@@ -137,6 +139,7 @@ fn btc_headers_works() {
 
     let execute_msg = babylon_contract::msg::contract::ExecuteMsg::BtcHeaders {
         headers: test_headers,
+        signet_proofs: None,
     };
 
     execute::<_, _, _, _, BabylonMsg>(&mut deps, env, info, execute_msg).unwrap();
@@ -153,6 +156,7 @@ fn btc_headers_fork_works() {
 
     let execute_msg = babylon_contract::msg::contract::ExecuteMsg::BtcHeaders {
         headers: test_headers,
+        signet_proofs: None,
     };
 
     execute::<_, _, _, _, BabylonMsg>(&mut deps, env.clone(), info.clone(), execute_msg).unwrap();
@@ -162,6 +166,7 @@ fn btc_headers_fork_works() {
 
     let execute_msg = babylon_contract::msg::contract::ExecuteMsg::BtcHeaders {
         headers: test_headers,
+        signet_proofs: None,
     };
 
     execute::<_, _, _, _, BabylonMsg>(&mut deps, env, info, execute_msg).unwrap();
@@ -177,6 +182,7 @@ fn btc_headers_query_works() {
 
     let execute_msg = babylon_contract::msg::contract::ExecuteMsg::BtcHeaders {
         headers: test_headers.clone(),
+        signet_proofs: None,
     };
 
     execute::<_, _, _, _, BabylonMsg>(&mut deps, env.clone(), info, execute_msg).unwrap();
@@ -3,7 +3,7 @@ use babylon_bindings::BabylonMsg;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_json_binary, Addr, CustomQuery, Deps, DepsMut, Empty, Env, MessageInfo,
+    attr, to_json_binary, Addr, CustomQuery, Deps, DepsMut, Empty, Env, Event, MessageInfo,
     QuerierWrapper, QueryRequest, QueryResponse, Reply, Response, StdResult, WasmQuery,
 };
 use cw2::set_contract_version;
@@ -16,7 +16,7 @@ use crate::finality::{
     compute_active_finality_providers, handle_finality_signature, handle_public_randomness_commit,
 };
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::config::{Config, ADMIN, CONFIG, PARAMS};
+use crate::state::config::{Config, Params, ADMIN, CONFIG, PARAMS};
 use crate::{finality, queries, state};
 
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -25,7 +25,7 @@ pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     mut deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response<BabylonMsg>, ContractError> {
@@ -40,7 +40,8 @@ pub fn instantiate(
     ADMIN.set(deps.branch(), maybe_addr(api, msg.admin.clone())?)?;
 
     let params = msg.params.unwrap_or_default();
-    PARAMS.save(deps.storage, &params)?;
+    validate_params(&params)?;
+    PARAMS.save(deps.storage, &params, env.block.height)?;
     // initialize storage, so no issue when reading for the first time
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
@@ -119,6 +120,7 @@ pub fn execute(
             .execute_update_admin(deps, info, maybe_addr(api, admin)?)
             .map_err(Into::into),
         ExecuteMsg::UpdateStaking { staking } => handle_update_staking(deps, info, staking),
+        ExecuteMsg::UpdateParams { params } => handle_update_params(deps, env, info, params),
         ExecuteMsg::SubmitFinalitySignature {
             fp_pubkey_hex,
             height,
@@ -188,6 +190,45 @@ fn handle_update_staking(
     Ok(Response::new().add_attributes(attributes))
 }
 
+fn handle_update_params(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    params: Params,
+) -> Result<Response<BabylonMsg>, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.babylon && !ADMIN.is_admin(deps.as_ref(), &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    validate_params(&params)?;
+    PARAMS.save(deps.storage, &params, env.block.height)?;
+
+    let event = Event::new("params_updated")
+        .add_attribute(
+            "max_active_finality_providers",
+            params.max_active_finality_providers.to_string(),
+        )
+        .add_attribute("min_pub_rand", params.min_pub_rand.to_string())
+        .add_attribute("height", env.block.height.to_string());
+    let attributes = vec![attr("action", "update_params"), attr("sender", info.sender)];
+    Ok(Response::new().add_attributes(attributes).add_event(event))
+}
+
+/// validate_params ensures the finality params are usable
+fn validate_params(params: &Params) -> Result<(), ContractError> {
+    if params.max_active_finality_providers == 0 {
+        return Err(ContractError::InvalidParams(
+            "max_active_finality_providers must be positive".to_string(),
+        ));
+    }
+    if params.min_pub_rand == 0 {
+        return Err(ContractError::InvalidParams(
+            "min_pub_rand must be positive".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 fn handle_begin_block(deps: &mut DepsMut, env: Env) -> Result<Response<BabylonMsg>, ContractError> {
     // Compute active finality provider set
     let max_active_fps = PARAMS.load(deps.storage)?.max_active_finality_providers as usize;
@@ -357,4 +398,75 @@ pub(crate) mod tests {
         // Use assert_admin to verify that the admin was updated correctly
         ADMIN.assert_admin(deps.as_ref(), &new_admin).unwrap();
     }
+
+    #[test]
+    fn test_update_params() {
+        let mut deps = mock_dependencies();
+        let init_admin = deps.api.addr_make(INIT_ADMIN);
+
+        let instantiate_msg = InstantiateMsg {
+            params: None,
+            admin: Some(init_admin.to_string()),
+        };
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+        instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+        let old_params = PARAMS.load(&deps.storage).unwrap();
+        let new_params = Params {
+            max_active_finality_providers: old_params.max_active_finality_providers + 1,
+            ..old_params.clone()
+        };
+
+        // Only the admin or the babylon contract can update the params
+        let non_admin_info = message_info(&deps.api.addr_make("non_admin"), &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            non_admin_info,
+            ExecuteMsg::UpdateParams {
+                params: new_params.clone(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized);
+
+        // Invalid params are rejected
+        let admin_info = message_info(&init_admin, &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            ExecuteMsg::UpdateParams {
+                params: Params {
+                    min_pub_rand: 0,
+                    ..old_params.clone()
+                },
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidParams(_)));
+
+        // Valid params are saved at the next block, keeping the previous ones in the history
+        let mut env = mock_env();
+        env.block.height += 1;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info,
+            ExecuteMsg::UpdateParams {
+                params: new_params.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(1, res.events.len());
+        assert_eq!("params_updated", res.events[0].ty);
+
+        assert_eq!(PARAMS.load(&deps.storage).unwrap(), new_params);
+        assert_eq!(
+            PARAMS
+                .may_load_at_height(&deps.storage, env.block.height)
+                .unwrap(),
+            Some(old_params)
+        );
+    }
 }
@@ -2,6 +2,7 @@ use anyhow::Result as AnyResult;
 use derivative::Derivative;
 use hex::ToHex;
 
+use cosmwasm_std::testing::MockApi;
 use cosmwasm_std::{to_json_binary, Addr};
 
 use cw_multi_test::{AppResponse, Contract, ContractWrapper, Executor};
@@ -9,8 +10,8 @@ use cw_multi_test::{AppResponse, Contract, ContractWrapper, Executor};
 use babylon_apis::btc_staking_api::{ActiveBtcDelegation, FinalityProvider, NewFinalityProvider};
 use babylon_apis::finality_api::{IndexedBlock, PubRandCommit};
 use babylon_apis::{btc_staking_api, finality_api};
-use babylon_bindings::BabylonMsg;
-use babylon_bindings_test::BabylonApp;
+use babylon_bindings::{BabylonMsg, BabylonQuery};
+use babylon_bindings_test::{BabylonApp, EmptyQueryContract};
 use babylon_bitcoin::chain_params::Network;
 
 use btc_staking::msg::{ActivatedHeightResponse, FinalityProviderInfo};
@@ -18,34 +19,35 @@ use btc_staking::msg::{ActivatedHeightResponse, FinalityProviderInfo};
 use crate::msg::{EvidenceResponse, FinalitySignatureResponse};
 use crate::multitest::{CONTRACT1_ADDR, CONTRACT2_ADDR};
 
-fn contract_btc_staking() -> Box<dyn Contract<BabylonMsg>> {
+fn contract_btc_staking() -> Box<dyn Contract<BabylonMsg, BabylonQuery>> {
     let contract = ContractWrapper::new(
         btc_staking::contract::execute,
         btc_staking::contract::instantiate,
         btc_staking::contract::query,
     );
-    Box::new(contract)
+    Box::new(EmptyQueryContract::new(contract))
 }
 
-fn contract_btc_finality() -> Box<dyn Contract<BabylonMsg>> {
+fn contract_btc_finality() -> Box<dyn Contract<BabylonMsg, BabylonQuery>> {
     let contract = ContractWrapper::new(
         crate::contract::execute,
         crate::contract::instantiate,
         crate::contract::query,
     )
     .with_sudo(crate::contract::sudo);
-    Box::new(contract)
+    Box::new(EmptyQueryContract::new(contract))
 }
 
-fn contract_babylon() -> Box<dyn Contract<BabylonMsg>> {
+fn contract_babylon() -> Box<dyn Contract<BabylonMsg, BabylonQuery>> {
     let contract = ContractWrapper::new(
         babylon_contract::execute,
         babylon_contract::instantiate,
         babylon_contract::query,
     )
+    .with_sudo(babylon_contract::sudo)
     .with_reply(babylon_contract::reply)
     .with_migrate(babylon_contract::migrate);
-    Box::new(contract)
+    Box::new(EmptyQueryContract::new(contract))
 }
 
 #[derive(Derivative)]
@@ -62,7 +64,7 @@ impl SuiteBuilder {
 
     #[track_caller]
     pub fn build(self) -> Suite {
-        let owner = Addr::unchecked("owner");
+        let owner = MockApi::default().addr_make("owner");
 
         let mut app = BabylonApp::new_at_height(owner.as_str(), self.height.unwrap_or(1));
 
@@ -100,6 +102,7 @@ impl SuiteBuilder {
                     admin: Some(owner.to_string()),
                     consumer_name: Some("TestConsumer".to_string()),
                     consumer_description: Some("Test Consumer Description".to_string()),
+                    signet_challenge: None,
                 },
                 &[],
                 "babylon",
@@ -262,6 +265,8 @@ impl Suite {
                 active_del: vec![],
                 slashed_del: vec![],
                 unbonded_del: vec![],
+                edit_fp: vec![],
+                best_effort: None,
             },
             &[],
         )
@@ -277,6 +282,8 @@ impl Suite {
                 active_del: dels.to_vec(),
                 slashed_del: vec![],
                 unbonded_del: vec![],
+                edit_fp: vec![],
+                best_effort: None,
             },
             &[],
         )
@@ -1,13 +1,19 @@
-use derivative::Derivative;
-
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Addr;
 
 use cw_controllers::Admin;
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, SnapshotItem, Strategy};
+
+pub use babylon_apis::finality_api::Params;
 
 pub(crate) const CONFIG: Item<Config> = Item::new("config");
-pub(crate) const PARAMS: Item<Params> = Item::new("params");
+/// Params, with their history by block height
+pub(crate) const PARAMS: SnapshotItem<Params> = SnapshotItem::new(
+    "params",
+    "params__checkpoints",
+    "params__changelog",
+    Strategy::EveryBlock,
+);
 /// Storage for admin
 pub(crate) const ADMIN: Admin = Admin::new("admin");
 
@@ -18,18 +24,3 @@ pub struct Config {
     pub babylon: Addr,
     pub staking: Addr,
 }
-
-// TODO: Add / enable param entries as needed
-#[cw_serde]
-#[derive(Derivative)]
-#[derivative(Default)]
-pub struct Params {
-    /// `max_active_finality_providers` is the maximum number of active finality providers in the
-    /// BTC staking protocol
-    #[derivative(Default(value = "100"))]
-    pub max_active_finality_providers: u32,
-    /// `min_pub_rand` is the minimum amount of public randomness each public randomness commitment
-    /// should commit
-    #[derivative(Default(value = "1"))]
-    pub min_pub_rand: u64,
-}
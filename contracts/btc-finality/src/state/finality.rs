@@ -15,8 +15,10 @@ pub const NEXT_HEIGHT: Item<u64> = Item::new("next_height");
 /// `FP_SET` is the calculated list of the active finality providers by height
 pub const FP_SET: Map<u64, Vec<FinalityProviderInfo>> = Map::new("fp_set");
 
-/// `TOTAL_POWER` is the total power of all finality providers
-// FIXME: Store by height? Remove? Not currently being used in the contract
+/// `TOTAL_POWER` is the total power of the active finality provider set, at the last height the
+/// set was computed.
+/// The total power of all finality providers, by height, is available from the btc-staking
+/// contract `StakingStats` query
 pub const TOTAL_POWER: Item<u64> = Item::new("total_power");
 
 /// Map of double signing evidence by FP and block height
@@ -1,7 +1,7 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Deps, DepsMut, Empty, Env, MessageInfo, QueryResponse, Reply, Response,
+    to_json_binary, Deps, DepsMut, Empty, Env, Event, MessageInfo, QueryResponse, Reply, Response,
     StdResult,
 };
 use cw2::set_contract_version;
@@ -10,10 +10,16 @@ use cw_utils::{maybe_addr, nonpayable};
 use babylon_bindings::BabylonMsg;
 
 use crate::error::ContractError;
+use crate::migrations::{
+    migrate_delegation_expiry_queue, migrate_delegation_indexes, migrate_fp_delegations,
+    migrate_params, migrate_staking_stats,
+};
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 use crate::queries;
-use crate::staking::{handle_btc_staking, handle_slash_fp};
-use crate::state::config::{Config, ADMIN, CONFIG, PARAMS};
+use crate::staking::{handle_btc_staking, handle_btc_tip_update, handle_slash_fp};
+use crate::state::config::{add_params, Config, Params, ADMIN, CONFIG};
+use crate::state::staking::{StakingStats, STAKING_STATS};
+use crate::validation::verify_params;
 
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -21,7 +27,7 @@ pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     mut deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response<BabylonMsg>, ContractError> {
@@ -35,8 +41,11 @@ pub fn instantiate(
     ADMIN.set(deps.branch(), maybe_addr(api, msg.admin.clone())?)?;
 
     let params = msg.params.unwrap_or_default();
-    PARAMS.save(deps.storage, &params)?;
+    verify_params(&params)?;
+    // The initial params are Babylon's genesis version, applying from genesis onwards
+    add_params(deps.storage, 0, &params, 0)?;
     // initialize storage, so no issue when reading for the first time
+    STAKING_STATS.save(deps.storage, &StakingStats::default(), env.block.height)?;
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::new().add_attribute("action", "instantiate"))
@@ -52,6 +61,9 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<QueryResponse, Cont
     match msg {
         QueryMsg::Config {} => Ok(to_json_binary(&queries::config(deps)?)?),
         QueryMsg::Params {} => Ok(to_json_binary(&queries::params(deps)?)?),
+        QueryMsg::ParamsByVersion { version } => {
+            Ok(to_json_binary(&queries::params_by_version(deps, version)?)?)
+        }
         QueryMsg::Admin {} => to_json_binary(&ADMIN.query_admin(deps)?).map_err(Into::into),
         QueryMsg::FinalityProvider { btc_pk_hex } => Ok(to_json_binary(
             &queries::finality_provider(deps, btc_pk_hex)?,
@@ -75,23 +87,83 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<QueryResponse, Cont
             limit,
             active,
         )?)?),
-        QueryMsg::DelegationsByFP { btc_pk_hex } => Ok(to_json_binary(
-            &queries::delegations_by_fp(deps, btc_pk_hex)?,
-        )?),
+        QueryMsg::DelegationsByFP {
+            btc_pk_hex,
+            start_after,
+            limit,
+            status,
+            include_delegations,
+        } => Ok(to_json_binary(&queries::delegations_by_fp(
+            deps,
+            btc_pk_hex,
+            start_after,
+            limit,
+            status,
+            include_delegations,
+        )?)?),
+        QueryMsg::DelegationsByStaker {
+            staker_addr,
+            start_after,
+            limit,
+            status,
+        } => Ok(to_json_binary(&queries::delegations_by_staker(
+            deps,
+            staker_addr,
+            start_after,
+            limit,
+            status,
+        )?)?),
+        QueryMsg::DelegationsByStakerBtcPk {
+            btc_pk_hex,
+            start_after,
+            limit,
+            status,
+        } => Ok(to_json_binary(&queries::delegations_by_staker_btc_pk(
+            deps,
+            btc_pk_hex,
+            start_after,
+            limit,
+            status,
+        )?)?),
         QueryMsg::FinalityProviderInfo { btc_pk_hex, height } => Ok(to_json_binary(
             &queries::finality_provider_info(deps, btc_pk_hex, height)?,
         )?),
         QueryMsg::FinalityProvidersByPower { start_after, limit } => Ok(to_json_binary(
             &queries::finality_providers_by_power(deps, start_after, limit)?,
         )?),
+        QueryMsg::FinalityProviderCommissionHistory {
+            btc_pk_hex,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(
+            &queries::finality_provider_commission_history(deps, btc_pk_hex, start_after, limit)?,
+        )?),
+        QueryMsg::StakingStats { height, top_n } => Ok(to_json_binary(&queries::staking_stats(
+            deps, height, top_n,
+        )?)?),
         QueryMsg::ActivatedHeight {} => Ok(to_json_binary(&queries::activated_height(deps)?)?),
     }
 }
 
-/// This is a no-op just to test how this integrates with wasmd
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, env: Env, _msg: Empty) -> StdResult<Response> {
+    let migrated_params = migrate_params(deps.storage)?;
+    let migrated_fp_delegations = migrate_fp_delegations(deps.storage)?;
+    let indexed_delegations = migrate_delegation_indexes(deps.storage)?;
+    let queued_delegations = migrate_delegation_expiry_queue(deps.storage)?;
+    let migrated_staking_stats = migrate_staking_stats(deps.storage, env.block.height)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("migrated_params", migrated_params.to_string())
+        .add_attribute(
+            "migrated_fp_delegations",
+            migrated_fp_delegations.to_string(),
+        )
+        .add_attribute("indexed_delegations", indexed_delegations.to_string())
+        .add_attribute("queued_delegations", queued_delegations.to_string())
+        .add_attribute("migrated_staking_stats", migrated_staking_stats.to_string()))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -111,6 +183,8 @@ pub fn execute(
             active_del,
             slashed_del,
             unbonded_del,
+            edit_fp,
+            best_effort,
         } => handle_btc_staking(
             deps,
             env,
@@ -119,9 +193,43 @@ pub fn execute(
             &active_del,
             &slashed_del,
             &unbonded_del,
+            &edit_fp,
+            best_effort.unwrap_or_default(),
         ),
         ExecuteMsg::Slash { fp_btc_pk_hex } => handle_slash_fp(deps, env, &info, &fp_btc_pk_hex),
+        ExecuteMsg::UpdateParams {
+            version,
+            params,
+            btc_activation_height,
+        } => handle_update_params(deps, &info, version, params, btc_activation_height),
+        ExecuteMsg::BtcTipUpdate {
+            height,
+            checkpoint_finalization_timeout,
+        } => handle_btc_tip_update(deps, env, &info, height, checkpoint_finalization_timeout),
+    }
+}
+
+/// handle_update_params adds a new version of the params, after verifying them
+fn handle_update_params(
+    deps: DepsMut,
+    info: &MessageInfo,
+    version: u32,
+    params: Params,
+    btc_activation_height: u64,
+) -> Result<Response<BabylonMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.babylon && !ADMIN.is_admin(deps.as_ref(), &info.sender)? {
+        return Err(ContractError::Unauthorized);
     }
+    verify_params(&params)?;
+    add_params(deps.storage, version, &params, btc_activation_height)?;
+
+    let event = Event::new("params_updated")
+        .add_attribute("version", version.to_string())
+        .add_attribute("btc_activation_height", btc_activation_height.to_string());
+    Ok(Response::new()
+        .add_attribute("action", "update_params")
+        .add_event(event))
 }
 
 #[cfg(test)]
@@ -131,13 +239,38 @@ pub mod tests {
     use cosmwasm_std::{
         from_json,
         testing::{message_info, mock_dependencies, mock_env},
+        Decimal,
     };
     use cw_controllers::AdminResponse;
 
+    use babylon_bitcoin::chain_params::Network;
+
+    use crate::msg::ParamsByVersionResponse;
+    use crate::test_utils::staking_params;
+
     pub(crate) const CREATOR: &str = "creator";
     pub(crate) const INIT_ADMIN: &str = "initial_admin";
     const NEW_ADMIN: &str = "new_admin";
 
+    #[test]
+    fn btc_staking_msg_without_edit_fp_is_accepted() {
+        let msg: ExecuteMsg = from_json(
+            r#"{"btc_staking":{"new_fp":[],"active_del":[],"slashed_del":[],"unbonded_del":[]}}"#,
+        )
+        .unwrap();
+        match msg {
+            ExecuteMsg::BtcStaking {
+                edit_fp,
+                best_effort,
+                ..
+            } => {
+                assert!(edit_fp.is_empty());
+                assert_eq!(best_effort, None);
+            }
+            _ => panic!("unexpected msg: {msg:?}"),
+        }
+    }
+
     #[test]
     fn instantiate_without_admin() {
         let mut deps = mock_dependencies();
@@ -242,4 +375,174 @@ pub mod tests {
         // Use assert_admin to verify that the admin was updated correctly
         ADMIN.assert_admin(deps.as_ref(), &new_admin).unwrap();
     }
+
+    #[test]
+    fn test_update_params() {
+        let mut deps = mock_dependencies();
+        let init_admin = deps.api.addr_make(INIT_ADMIN);
+
+        let params = staking_params();
+        let instantiate_msg = InstantiateMsg {
+            params: Some(params.clone()),
+            admin: Some(init_admin.to_string()),
+        };
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+        instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+        let new_params = Params {
+            min_slashing_tx_fee_sat: params.min_slashing_tx_fee_sat + 1,
+            ..params.clone()
+        };
+
+        // Only the admin or the babylon contract can update the params
+        let non_admin_info = message_info(&deps.api.addr_make("non_admin"), &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            non_admin_info,
+            ExecuteMsg::UpdateParams {
+                version: 1,
+                params: new_params.clone(),
+                btc_activation_height: 100,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized);
+
+        // The covenant quorum cannot exceed the number of covenant keys
+        let admin_info = message_info(&init_admin, &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            ExecuteMsg::UpdateParams {
+                version: 1,
+                params: Params {
+                    covenant_quorum: params.covenant_pks.len() as u32 + 1,
+                    ..params.clone()
+                },
+                btc_activation_height: 100,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidParams(_)));
+
+        // The slashing address must be valid for the BTC network
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            ExecuteMsg::UpdateParams {
+                version: 1,
+                params: Params {
+                    btc_network: Network::Mainnet,
+                    ..params.clone()
+                },
+                btc_activation_height: 100,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidParams(_)));
+
+        // The slashing rate must have at most 2 decimal places
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            ExecuteMsg::UpdateParams {
+                version: 1,
+                params: Params {
+                    slashing_rate: Decimal::permille(1),
+                    ..params.clone()
+                },
+                btc_activation_height: 100,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidParams(_)));
+
+        // The min commission rate must be a proper rate
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            ExecuteMsg::UpdateParams {
+                version: 1,
+                params: Params {
+                    min_commission_rate: Decimal::percent(101),
+                    ..params.clone()
+                },
+                btc_activation_height: 100,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidParams(_)));
+
+        // A delegation must be able to restake to at least one finality provider
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            ExecuteMsg::UpdateParams {
+                version: 1,
+                params: Params {
+                    max_fps_per_delegation: 0,
+                    ..params.clone()
+                },
+                btc_activation_height: 100,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidParams(_)));
+
+        // The version must be the one following the latest version
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            ExecuteMsg::UpdateParams {
+                version: 2,
+                params: new_params.clone(),
+                btc_activation_height: 100,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::UnexpectedParamsVersion(2, 1));
+
+        // Valid params are added as a new version
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info,
+            ExecuteMsg::UpdateParams {
+                version: 1,
+                params: new_params.clone(),
+                btc_activation_height: 100,
+            },
+        )
+        .unwrap();
+        assert_eq!(1, res.events.len());
+        assert_eq!("params_updated", res.events[0].ty);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ParamsByVersion { version: 1 },
+        )
+        .unwrap();
+        let res: ParamsByVersionResponse = from_json(res).unwrap();
+        assert_eq!(res.btc_start_height, 100);
+        assert_eq!(res.btc_end_height, None);
+        assert_eq!(res.params, new_params);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ParamsByVersion { version: 0 },
+        )
+        .unwrap();
+        let res: ParamsByVersionResponse = from_json(res).unwrap();
+        assert_eq!(res.btc_end_height, Some(99));
+        assert_eq!(res.params, params);
+    }
 }
@@ -5,7 +5,7 @@ use thiserror::Error;
 use bitcoin::hashes::FromSliceError;
 use bitcoin::hex::HexToArrayError;
 
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError};
 use cw_controllers::AdminError;
 use cw_utils::PaymentError;
 
@@ -22,6 +22,8 @@ pub enum ContractError {
     Payment(#[from] PaymentError),
     #[error("{0}")]
     BTCStaking(#[from] babylon_btcstaking::error::Error),
+    #[error("{0}")]
+    Bitcoin(#[from] babylon_bitcoin::error::Error),
     #[error("error converting from hex to array: {0}")]
     HexArrayError(#[from] HexToArrayError),
     #[error("{0}")]
@@ -46,14 +48,52 @@ pub enum ContractError {
     FinalityProviderAlreadyExists(String),
     #[error("No finality providers are registered in this Consumer")]
     FinalityProviderNotRegistered,
+    #[error("The delegation restakes to {0} finality providers, more than the max of {1}")]
+    TooManyFinalityProviders(usize, u32),
+    #[error("The delegation restakes to more than one finality provider of Consumer {0}")]
+    DuplicatedConsumerFinalityProvider(String),
     #[error("Finality provider not found: {0}")]
     FinalityProviderNotFound(String),
+    #[error("Commission rate {0} is lower than the min commission rate {1}")]
+    CommissionTooLow(Decimal, Decimal),
+    #[error("Commission rate change {0} is larger than the max commission change rate {1}")]
+    CommissionChangeTooLarge(Decimal, Decimal),
     #[error("Staking tx hash already exists: {0}")]
     DelegationAlreadyExists(String),
     #[error("BTC delegation is not active: {0}")]
     DelegationIsNotActive(String),
+    #[error("Invalid params: {0}")]
+    InvalidParams(String),
+    #[error("Params version {0} not found")]
+    ParamsVersionNotFound(u32),
+    #[error("Params version {0} does not apply to BTC height {1}")]
+    InvalidParamsVersion(u32, u64),
+    #[error("Params activation BTC height {0} must be higher than the latest one ({1})")]
+    InvalidParamsActivationHeight(u64, u64),
+    #[error("Params version {0} does not follow the latest version; expected version {1}")]
+    UnexpectedParamsVersion(u32, u32),
+    #[error("Missing inclusion proof for staking tx {0}")]
+    MissingStakingTxInclusionProof(String),
+    #[error("Invalid inclusion proof for staking tx {0}")]
+    InvalidStakingTxInclusionProof(String),
+    #[error("Staking tx {0} is included at BTC height {1}, but the delegation starts at {2}")]
+    StakingTxHeightMismatch(String, u64, u64),
+    #[error("Staking tx {0} is not k-deep: depth {1}, required {2}")]
+    StakingTxNotKDeep(String, u64, u64),
+    #[error("BTC delegation {0} has expired at BTC height {1}")]
+    DelegationExpired(String, u64),
+    #[error("BTC delegation {0} starts at BTC height {1}, after the BTC tip ({2})")]
+    DelegationNotStarted(String, u64, u64),
     #[error("Invalid covenant signature: {0}")]
     InvalidCovenantSig(String),
+    #[error("Duplicate covenant signature by {0}")]
+    DuplicateCovenantSig(String),
+    #[error("Not enough covenant signatures over the slashing tx: {0} (quorum: {1})")]
+    InsufficientCovenantSigs(usize, u32),
+    #[error("Not enough covenant signatures over the unbonding tx: {0} (quorum: {1})")]
+    InsufficientCovenantUnbondingSigs(usize, u32),
+    #[error("Not enough covenant signatures over the unbonding slashing tx: {0} (quorum: {1})")]
+    InsufficientCovenantUnbondingSlashingSigs(usize, u32),
     #[error("Invalid Btc tx: {0}")]
     InvalidBtcTx(String),
     #[error("Empty signature from the delegator")]
@@ -62,6 +102,8 @@ pub enum ContractError {
     ErrInvalidLockType,
     #[error("Invalid lock time blocks: {0}, max: {1}")]
     ErrInvalidLockTime(u32, u32),
+    #[error("Invalid staking time: start height {0}, end height {1}")]
+    InvalidStakingTime(u64, u64),
     #[error("The finality provider {0} does not have voting power at height {1}")]
     NoVotingPower(String, u64),
     #[error("The chain has not reached the given height yet")]
@@ -1,3 +1,4 @@
+mod migrations;
 mod staking;
 mod validation;
 
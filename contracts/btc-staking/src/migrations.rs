@@ -0,0 +1,387 @@
+use std::str::FromStr;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, Order, StdError, StdResult, Storage};
+use cw_storage_plus::{Index, Item, Map};
+
+use babylon_bitcoin::chain_params::Network;
+
+use crate::state::config::{Params, PARAMS, PARAMS_BTC_ACTIVATION_HEIGHTS};
+use babylon_apis::btc_staking_api::HASH_SIZE;
+
+use crate::state::staking::{
+    btc_delegations, fps, StakingStats, BTC_TIP, DELEGATION_EXPIRY_QUEUE, FPS, FP_DELEGATIONS,
+    STAKING_STATS,
+};
+
+/// Legacy single version params, as stored before the params history
+#[cw_serde]
+struct LegacyParams {
+    covenant_pks: Vec<String>,
+    covenant_quorum: u32,
+    btc_network: Network,
+    slashing_address: String,
+    min_slashing_tx_fee_sat: u64,
+    slashing_rate: String,
+}
+
+/// Legacy single version params
+const LEGACY_PARAMS: Item<LegacyParams> = Item::new("params");
+
+/// Legacy map of staking hashes by finality provider, as a single vector per finality provider
+const LEGACY_FP_DELEGATIONS: Map<&str, Vec<Vec<u8>>> = Map::new("fp_delegations");
+
+/// migrate_fp_delegations moves the legacy staking hashes by finality provider vectors to the
+/// composite-key `FP_DELEGATIONS` map.
+/// Returns the number of migrated staking hashes
+pub(crate) fn migrate_fp_delegations(storage: &mut dyn Storage) -> StdResult<usize> {
+    let legacy = LEGACY_FP_DELEGATIONS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut migrated = 0;
+    for (fp_btc_pk_hex, staking_tx_hashes) in legacy {
+        for staking_tx_hash in staking_tx_hashes {
+            FP_DELEGATIONS.save(storage, (&fp_btc_pk_hex, &staking_tx_hash), &())?;
+            migrated += 1;
+        }
+        LEGACY_FP_DELEGATIONS.remove(storage, &fp_btc_pk_hex);
+    }
+    Ok(migrated)
+}
+
+/// migrate_params moves the legacy single version params to the params history, as the initial
+/// version, applying from genesis onwards.
+/// Returns whether the params were migrated
+pub(crate) fn migrate_params(storage: &mut dyn Storage) -> StdResult<bool> {
+    let Some(legacy) = LEGACY_PARAMS.may_load(storage)? else {
+        return Ok(false);
+    };
+    let slashing_rate = Decimal::from_str(&legacy.slashing_rate).map_err(|e| {
+        StdError::generic_err(format!(
+            "invalid legacy slashing rate {}: {e}",
+            legacy.slashing_rate
+        ))
+    })?;
+    // The params added after the legacy params take their default values
+    let params = Params {
+        covenant_pks: legacy.covenant_pks,
+        covenant_quorum: legacy.covenant_quorum,
+        btc_network: legacy.btc_network,
+        min_commission_rate: Decimal::zero(),
+        max_commission_change_rate: Decimal::one(),
+        slashing_address: legacy.slashing_address,
+        min_slashing_tx_fee_sat: legacy.min_slashing_tx_fee_sat,
+        slashing_rate,
+        min_unbonding_time: 0,
+        min_unbonding_rate: Decimal::percent(80),
+        unbonding_fee_sat: 1000,
+        max_fps_per_delegation: 5,
+        require_staking_tx_inclusion_proof: false,
+    };
+    PARAMS.save(storage, 0, &params)?;
+    PARAMS_BTC_ACTIVATION_HEIGHTS.save(storage, 0, &0)?;
+    LEGACY_PARAMS.remove(storage);
+    Ok(true)
+}
+
+/// migrate_delegation_expiry_queue adds the delegations that still have voting power (i.e. that
+/// were neither unbonded early, slashed, nor expired) to the expiry queue, by the end BTC height of
+/// their time-lock.
+/// Delegations already past their end height are expired on the next BTC tip update.
+/// Returns the number of queued delegations
+pub(crate) fn migrate_delegation_expiry_queue(storage: &mut dyn Storage) -> StdResult<usize> {
+    let delegations = btc_delegations()
+        .range_raw(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut queued = 0;
+    for (staking_tx_hash, del) in delegations {
+        if !del.is_active(None) {
+            continue;
+        }
+        let staking_tx_hash: [u8; HASH_SIZE] = staking_tx_hash
+            .try_into()
+            .map_err(|_| StdError::generic_err("invalid staking tx hash length"))?;
+        let key = (del.end_height, &staking_tx_hash);
+        if !DELEGATION_EXPIRY_QUEUE.has(storage, key) {
+            DELEGATION_EXPIRY_QUEUE.save(storage, key, &())?;
+            queued += 1;
+        }
+    }
+    Ok(queued)
+}
+
+/// migrate_delegation_indexes builds the staker address and staker BTC PK indexes over the
+/// existing delegations.
+/// Returns the number of indexed delegations, or zero if the delegations are already indexed
+pub(crate) fn migrate_delegation_indexes(storage: &mut dyn Storage) -> StdResult<usize> {
+    let delegations = btc_delegations();
+    if delegations
+        .idx
+        .staker
+        .keys_raw(storage, None, None, Order::Ascending)
+        .next()
+        .is_some()
+    {
+        return Ok(0);
+    }
+
+    let existing = delegations
+        .range_raw(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (staking_tx_hash, del) in &existing {
+        delegations.idx.staker.save(storage, staking_tx_hash, del)?;
+        delegations
+            .idx
+            .staker_btc_pk
+            .save(storage, staking_tx_hash, del)?;
+    }
+    Ok(existing.len())
+}
+
+/// migrate_staking_stats computes the initial staking statistics from the existing delegations
+/// and finality providers, at the given height.
+/// Delegations hold voting power (i.e. are active) if they are neither unbonded early, slashed,
+/// nor expired at the current BTC tip.
+/// Returns whether the statistics were computed
+pub(crate) fn migrate_staking_stats(storage: &mut dyn Storage, height: u64) -> StdResult<bool> {
+    if STAKING_STATS.may_load(storage)?.is_some() {
+        return Ok(false);
+    }
+
+    let mut stats = StakingStats::default();
+    let btc_tip = BTC_TIP.may_load(storage)?;
+    for item in btc_delegations().range_raw(storage, None, None, Order::Ascending) {
+        let (_, del) = item?;
+        let expired = btc_tip
+            .as_ref()
+            .is_some_and(|tip| del.is_expired(tip.height, tip.checkpoint_finalization_timeout));
+        if del.slashed {
+            stats.slashed_delegations += 1;
+        } else if !del.is_active(None) || expired {
+            stats.unbonded_delegations += 1;
+        } else {
+            stats.active_delegations += 1;
+            stats.total_active_sat += del.total_sat;
+        }
+    }
+    for item in FPS.range_raw(storage, None, None, Order::Ascending) {
+        let (_, fp) = item?;
+        stats.finality_providers += 1;
+        if fp.slashed_height > 0 {
+            stats.slashed_finality_providers += 1;
+        }
+    }
+    for item in fps().range_raw(storage, None, None, Order::Ascending) {
+        let (_, fp_state) = item?;
+        stats.total_power += fp_state.power;
+    }
+
+    STAKING_STATS.save(storage, &stats, height)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn fp_delegations_are_migrated() {
+        let mut deps = mock_dependencies();
+        let storage = deps.as_mut().storage;
+
+        LEGACY_FP_DELEGATIONS
+            .save(storage, "f1", &vec![vec![1; 32], vec![2; 32]])
+            .unwrap();
+        LEGACY_FP_DELEGATIONS
+            .save(storage, "f2", &vec![vec![2; 32]])
+            .unwrap();
+
+        assert_eq!(migrate_fp_delegations(storage).unwrap(), 3);
+
+        let f1 = FP_DELEGATIONS
+            .prefix("f1")
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(f1, vec![vec![1; 32], vec![2; 32]]);
+        assert!(FP_DELEGATIONS.has(storage, ("f2", &[2; 32])));
+        assert!(LEGACY_FP_DELEGATIONS.is_empty(storage));
+
+        // Migrating again is a no-op
+        assert_eq!(migrate_fp_delegations(storage).unwrap(), 0);
+    }
+
+    #[test]
+    fn params_are_migrated() {
+        let mut deps = mock_dependencies();
+        let storage = deps.as_mut().storage;
+
+        // Legacy params, as serialized by the deployed contracts
+        storage.set(
+            b"params",
+            br#"{"covenant_pks":["02ab"],"covenant_quorum":1,"btc_network":"regtest","slashing_address":"n4cV57jePmAAue2WTTBQzH3k3R2rgWBQwY","min_slashing_tx_fee_sat":1500,"slashing_rate":"0.1"}"#,
+        );
+
+        assert!(migrate_params(storage).unwrap());
+        assert_eq!(
+            PARAMS.load(storage, 0).unwrap(),
+            Params {
+                covenant_pks: vec!["02ab".to_string()],
+                covenant_quorum: 1,
+                btc_network: Network::Regtest,
+                slashing_address: "n4cV57jePmAAue2WTTBQzH3k3R2rgWBQwY".to_string(),
+                min_slashing_tx_fee_sat: 1500,
+                slashing_rate: Decimal::percent(10),
+                ..Params::default()
+            }
+        );
+        assert_eq!(PARAMS_BTC_ACTIVATION_HEIGHTS.load(storage, 0).unwrap(), 0);
+        assert!(!LEGACY_PARAMS.exists(storage));
+
+        // Migrating again is a no-op
+        assert!(!migrate_params(storage).unwrap());
+    }
+
+    #[test]
+    fn delegation_indexes_are_migrated() {
+        use crate::state::staking::{BtcDelegation, DELEGATIONS_KEY};
+
+        let mut deps = mock_dependencies();
+        let storage = deps.as_mut().storage;
+
+        // Delegations stored before the indexes existed
+        let legacy_delegations: Map<&[u8], BtcDelegation> = Map::new(DELEGATIONS_KEY);
+        let del = BtcDelegation::from(&test_utils::get_derived_btc_delegation(1, &[1]));
+        legacy_delegations.save(storage, &[1; 32], &del).unwrap();
+        legacy_delegations.save(storage, &[2; 32], &del).unwrap();
+
+        assert_eq!(migrate_delegation_indexes(storage).unwrap(), 2);
+
+        let delegations = btc_delegations();
+        let by_staker = delegations
+            .idx
+            .staker
+            .prefix(del.staker_addr.clone())
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(by_staker, vec![vec![1; 32], vec![2; 32]]);
+        let by_staker_btc_pk = delegations
+            .idx
+            .staker_btc_pk
+            .prefix(del.btc_pk_hex.clone())
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(by_staker_btc_pk, vec![vec![1; 32], vec![2; 32]]);
+
+        // Migrating again is a no-op
+        assert_eq!(migrate_delegation_indexes(storage).unwrap(), 0);
+    }
+
+    #[test]
+    fn staking_stats_are_migrated() {
+        use crate::state::staking::{BtcDelegation, BtcTipInfo, FinalityProviderState};
+        use babylon_apis::btc_staking_api::FinalityProvider;
+
+        let mut deps = mock_dependencies();
+        let storage = deps.as_mut().storage;
+
+        // An active, an unbonded, a slashed and an expired delegation
+        let active = BtcDelegation::from(&test_utils::get_derived_btc_delegation(1, &[1]));
+        let mut unbonded = active.clone();
+        unbonded.undelegation_info.delegator_unbonding_sig = vec![1; 64];
+        let slashed = BtcDelegation {
+            slashed: true,
+            ..active.clone()
+        };
+        let expired = BtcDelegation {
+            end_height: 0,
+            ..active.clone()
+        };
+        let delegations = btc_delegations();
+        for (i, del) in [&active, &unbonded, &slashed, &expired]
+            .into_iter()
+            .enumerate()
+        {
+            delegations.save(storage, &[i as u8; 32], del).unwrap();
+        }
+        BTC_TIP
+            .save(
+                storage,
+                &BtcTipInfo {
+                    height: 1,
+                    checkpoint_finalization_timeout: 1,
+                },
+            )
+            .unwrap();
+
+        // A slashed and a non-slashed finality provider
+        let fp = FinalityProvider::from(&test_utils::create_new_finality_provider(1));
+        FPS.save(storage, "f1", &fp).unwrap();
+        FPS.save(
+            storage,
+            "f2",
+            &FinalityProvider {
+                slashed_height: 1,
+                ..fp
+            },
+        )
+        .unwrap();
+        fps()
+            .save(
+                storage,
+                "f1",
+                &FinalityProviderState {
+                    power: active.total_sat,
+                },
+                1,
+            )
+            .unwrap();
+
+        assert!(migrate_staking_stats(storage, 2).unwrap());
+        assert_eq!(
+            STAKING_STATS.load(storage).unwrap(),
+            StakingStats {
+                total_active_sat: active.total_sat,
+                total_power: active.total_sat,
+                active_delegations: 1,
+                unbonded_delegations: 2,
+                slashed_delegations: 1,
+                finality_providers: 2,
+                slashed_finality_providers: 1,
+            }
+        );
+
+        // Migrating again is a no-op
+        assert!(!migrate_staking_stats(storage, 3).unwrap());
+    }
+
+    #[test]
+    fn delegation_expiry_queue_is_migrated() {
+        use crate::state::staking::BtcDelegation;
+
+        let mut deps = mock_dependencies();
+        let storage = deps.as_mut().storage;
+
+        let active = BtcDelegation::from(&test_utils::get_derived_btc_delegation(1, &[1]));
+        let slashed = BtcDelegation {
+            slashed: true,
+            ..active.clone()
+        };
+        let delegations = btc_delegations();
+        delegations.save(storage, &[1; 32], &active).unwrap();
+        delegations.save(storage, &[2; 32], &slashed).unwrap();
+
+        assert_eq!(migrate_delegation_expiry_queue(storage).unwrap(), 1);
+        assert!(DELEGATION_EXPIRY_QUEUE.has(storage, (active.end_height, &[1; 32])));
+        assert!(!DELEGATION_EXPIRY_QUEUE.has(storage, (slashed.end_height, &[2; 32])));
+
+        // Migrating again is a no-op
+        assert_eq!(migrate_delegation_expiry_queue(storage).unwrap(), 0);
+    }
+}
@@ -1,14 +1,15 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Decimal;
 #[cfg(not(target_arch = "wasm32"))]
 use {
     crate::state::config::Config, babylon_apis::btc_staking_api::ActiveBtcDelegation,
     cw_controllers::AdminResponse,
 };
 
-use babylon_apis::btc_staking_api::FinalityProvider;
+use babylon_apis::btc_staking_api::{BTCDelegationStatus, FinalityProvider};
 
 use crate::state::config::Params;
-use crate::state::staking::BtcDelegation;
+use crate::state::staking::{BtcDelegation, StakingStats};
 
 #[cw_serde]
 #[derive(Default)]
@@ -28,6 +29,10 @@ pub enum QueryMsg {
     /// `Params` returns the current Consumer-specific parameters of the btc-staking contract
     #[returns(Params)]
     Params {},
+    /// `ParamsByVersion` returns the Consumer-specific parameters of the given version, along with
+    /// the BTC height range they apply to
+    #[returns(ParamsByVersionResponse)]
+    ParamsByVersion { version: u32 },
     /// `Admin` returns the current admin of the contract
     #[returns(AdminResponse)]
     Admin {},
@@ -61,10 +66,49 @@ pub enum QueryMsg {
     /// delegations, for a given finality provider.
     ///
     /// `btc_pk_hex` is the BTC public key of the finality provider, in hex format.
+    /// `start_after` is the staking tx hash (in hex format) of the delegation to start after,
+    /// or `None` to start from the beginning.
+    /// `limit` is the maximum number of delegations to return.
+    /// `status` is an optional filter on the status of the delegations at the current BTC tip.
+    /// `include_delegations` returns the delegations themselves along with their hashes, if true.
     /// The hashes are returned in hex format
-    //TODO?: Support pagination
     #[returns(DelegationsByFPResponse)]
-    DelegationsByFP { btc_pk_hex: String },
+    DelegationsByFP {
+        btc_pk_hex: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        status: Option<BTCDelegationStatus>,
+        include_delegations: Option<bool>,
+    },
+    /// `DelegationsByStaker` returns the list of delegations of a staker, by its staker address.
+    ///
+    /// `staker_addr` is the address of the staker.
+    /// `start_after` is the staking tx hash (in hex format) of the delegation to start after,
+    /// or `None` to start from the beginning.
+    /// `limit` is the maximum number of delegations to return.
+    /// `status` is an optional filter on the status of the delegations at the current BTC tip
+    #[returns(DelegationsByStakerResponse)]
+    DelegationsByStaker {
+        staker_addr: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        status: Option<BTCDelegationStatus>,
+    },
+    /// `DelegationsByStakerBtcPk` returns the list of delegations of a staker, by its BTC public
+    /// key.
+    ///
+    /// `btc_pk_hex` is the BTC public key of the staker, in hex format.
+    /// `start_after` is the staking tx hash (in hex format) of the delegation to start after,
+    /// or `None` to start from the beginning.
+    /// `limit` is the maximum number of delegations to return.
+    /// `status` is an optional filter on the status of the delegations at the current BTC tip
+    #[returns(DelegationsByStakerResponse)]
+    DelegationsByStakerBtcPk {
+        btc_pk_hex: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        status: Option<BTCDelegationStatus>,
+    },
     /// `FinalityProviderInfo` returns the finality provider information by its BTC public key,
     /// in hex format
     /// The information includes the aggregated power of the finality provider.
@@ -85,12 +129,47 @@ pub enum QueryMsg {
         start_after: Option<FinalityProviderInfo>,
         limit: Option<u32>,
     },
+    /// `FinalityProviderCommissionHistory` returns the commission rate changes of a finality
+    /// provider, in ascending height order.
+    ///
+    /// `btc_pk_hex` is the BTC public key of the finality provider, in hex format.
+    /// `start_after` is the Babylon height of the change to start after, or `None` to start from
+    /// the registration of the finality provider
+    #[returns(CommissionHistoryResponse)]
+    FinalityProviderCommissionHistory {
+        btc_pk_hex: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// `StakingStats` returns the aggregated staking statistics, along with the voting power
+    /// concentration among the finality providers with the largest voting power.
+    ///
+    /// `height` is the optional block height at which to get the statistics.
+    /// If `height` is not provided, the latest statistics are returned.
+    /// `top_n` is the number of finality providers to aggregate the voting power of, 10 by default,
+    /// and at most 30
+    #[returns(StakingStatsResponse)]
+    StakingStats {
+        height: Option<u64>,
+        top_n: Option<u32>,
+    },
     /// `ActivatedHeight` returns the height at which the contract gets its first delegation, if any
     ///
     #[returns(ActivatedHeightResponse)]
     ActivatedHeight {},
 }
 
+#[cw_serde]
+pub struct ParamsByVersionResponse {
+    pub version: u32,
+    /// `btc_start_height` is the BTC height from which the params apply
+    pub btc_start_height: u64,
+    /// `btc_end_height` is the last BTC height to which the params apply.
+    /// `None` for the latest version
+    pub btc_end_height: Option<u64>,
+    pub params: Params,
+}
+
 #[cw_serde]
 pub struct FinalityProvidersResponse {
     pub fps: Vec<FinalityProvider>,
@@ -104,6 +183,16 @@ pub struct BtcDelegationsResponse {
 #[cw_serde]
 pub struct DelegationsByFPResponse {
     pub hashes: Vec<String>,
+    /// `delegations` are the delegations corresponding to `hashes`, if requested
+    pub delegations: Option<Vec<BtcDelegation>>,
+}
+
+#[cw_serde]
+pub struct DelegationsByStakerResponse {
+    /// `hashes` are the staking tx hashes of the delegations, in hex format
+    pub hashes: Vec<String>,
+    /// `delegations` are the delegations corresponding to `hashes`
+    pub delegations: Vec<BtcDelegation>,
 }
 
 #[cw_serde]
@@ -121,6 +210,30 @@ pub struct FinalityProviderInfo {
     pub power: u64,
 }
 
+#[cw_serde]
+pub struct CommissionHistoryResponse {
+    pub history: Vec<CommissionChange>,
+}
+
+#[cw_serde]
+pub struct CommissionChange {
+    /// `height` is the Babylon height at which the commission rate was set
+    pub height: u64,
+    /// `commission` is the commission rate set at `height`
+    pub commission: Decimal,
+}
+
+#[cw_serde]
+pub struct StakingStatsResponse {
+    pub stats: StakingStats,
+    /// `top_n` is the number of finality providers `top_n_power` is aggregated over
+    pub top_n: u32,
+    /// `top_n_power` is the aggregated voting power of the `top_n` finality providers with the
+    /// largest voting power.
+    /// Relative to `stats.total_power`, it is the voting power concentration among them
+    pub top_n_power: u64,
+}
+
 #[cw_serde]
 pub struct ActivatedHeightResponse {
     pub height: u64,
@@ -1,3 +1,5 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::str::FromStr;
 
 use bitcoin::hashes::Hash;
@@ -5,19 +7,22 @@ use bitcoin::Txid;
 
 use cosmwasm_std::Order::Descending;
 use cosmwasm_std::{Deps, Order, StdResult};
-use cw_storage_plus::Bound;
+use cw_storage_plus::{Bound, MultiIndex};
 
-use babylon_apis::btc_staking_api::FinalityProvider;
+use babylon_apis::btc_staking_api::{BTCDelegationStatus, FinalityProvider};
 
 use crate::error::ContractError;
 use crate::msg::{
-    ActivatedHeightResponse, BtcDelegationsResponse, DelegationsByFPResponse, FinalityProviderInfo,
-    FinalityProvidersByPowerResponse, FinalityProvidersResponse,
+    ActivatedHeightResponse, BtcDelegationsResponse, CommissionChange, CommissionHistoryResponse,
+    DelegationsByFPResponse, DelegationsByStakerResponse, FinalityProviderInfo,
+    FinalityProvidersByPowerResponse, FinalityProvidersResponse, ParamsByVersionResponse,
+    StakingStatsResponse,
 };
+use crate::state::config::{get_latest_params, get_params_btc_range, CONFIG, PARAMS};
 use crate::state::config::{Config, Params};
-use crate::state::config::{CONFIG, PARAMS};
 use crate::state::staking::{
-    fps, BtcDelegation, FinalityProviderState, ACTIVATED_HEIGHT, DELEGATIONS, FPS, FP_DELEGATIONS,
+    btc_delegations, fps, BtcDelegation, BtcTipInfo, FinalityProviderState, ACTIVATED_HEIGHT,
+    BTC_TIP, FPS, FP_COMMISSION_HISTORY, FP_DELEGATIONS, STAKING_STATS,
 };
 
 pub fn config(deps: Deps) -> StdResult<Config> {
@@ -25,7 +30,26 @@ pub fn config(deps: Deps) -> StdResult<Config> {
 }
 
 pub fn params(deps: Deps) -> StdResult<Params> {
-    PARAMS.load(deps.storage)
+    Ok(get_latest_params(deps.storage)?.1)
+}
+
+/// Params by version query.
+///
+/// `version`: The version of the params
+pub fn params_by_version(
+    deps: Deps,
+    version: u32,
+) -> Result<ParamsByVersionResponse, ContractError> {
+    let params = PARAMS
+        .may_load(deps.storage, version)?
+        .ok_or(ContractError::ParamsVersionNotFound(version))?;
+    let (btc_start_height, btc_end_height) = get_params_btc_range(deps.storage, version)?;
+    Ok(ParamsByVersionResponse {
+        version,
+        btc_start_height,
+        btc_end_height,
+        params,
+    })
 }
 
 pub fn finality_provider(deps: Deps, btc_pk_hex: String) -> StdResult<FinalityProvider> {
@@ -55,12 +79,13 @@ pub fn finality_providers(
 /// `staking_tx_hash_hex`: The (reversed) staking tx hash, in hex
 pub fn delegation(deps: Deps, staking_tx_hash_hex: String) -> Result<BtcDelegation, ContractError> {
     let staking_tx_hash = Txid::from_str(&staking_tx_hash_hex)?;
-    Ok(DELEGATIONS.load(deps.storage, staking_tx_hash.as_ref())?)
+    Ok(btc_delegations().load(deps.storage, staking_tx_hash.as_ref())?)
 }
 
 /// Get list of delegations.
 /// `start_after`: The (reversed) associated staking tx hash of the delegation in hex, if provided.
-/// `active`: List only active delegations if true, otherwise list all delegations.
+/// `active`: List only active delegations at the current BTC tip if true, otherwise list all
+/// delegations.
 pub fn delegations(
     deps: Deps,
     start_after: Option<String>,
@@ -75,11 +100,12 @@ pub fn delegations(
         .transpose()?;
     let start_after = start_after.as_ref().map(|s| s.as_ref());
     let start_after = start_after.map(Bound::exclusive);
-    let delegations = DELEGATIONS
+    let btc_tip = BTC_TIP.may_load(deps.storage)?;
+    let delegations = btc_delegations()
         .range_raw(deps.storage, start_after, None, Order::Ascending)
         .filter(|item| {
             if let Ok((_, del)) = item {
-                !active || del.is_active()
+                !active || del.is_active(btc_tip.as_ref())
             } else {
                 true // don't filter errors
             }
@@ -93,43 +119,162 @@ pub fn delegations(
 /// Delegation hashes by FP query.
 ///
 /// `btc_pk_hex`: The BTC public key of the finality provider, in hex
+/// `start_after`: The (reversed) associated staking tx hash of the delegation in hex, if provided.
+/// `status`: List only delegations with this status at the current BTC tip, if provided.
+/// `include_delegations`: Return the delegations along with their hashes if true.
 pub fn delegations_by_fp(
     deps: Deps,
     btc_pk_hex: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    status: Option<BTCDelegationStatus>,
+    include_delegations: Option<bool>,
 ) -> Result<DelegationsByFPResponse, ContractError> {
-    let tx_hashes = FP_DELEGATIONS.load(deps.storage, &btc_pk_hex)?;
-    let tx_hashes = tx_hashes
-        .iter()
-        .map(|h| Ok(Txid::from_slice(h)?.to_string()))
-        .collect::<Result<_, ContractError>>()?;
-    Ok(DelegationsByFPResponse { hashes: tx_hashes })
+    // Ensure the finality provider exists
+    FPS.load(deps.storage, &btc_pk_hex)?;
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .as_ref()
+        .map(|s| Txid::from_str(s))
+        .transpose()?;
+    let start_after = start_after
+        .as_ref()
+        .map(|s| Bound::exclusive(s.as_byte_array().as_slice()));
+    let status = status.unwrap_or(BTCDelegationStatus::ANY);
+    let include_delegations = include_delegations.unwrap_or_default();
+    let btc_tip = BTC_TIP.may_load(deps.storage)?;
+
+    let mut hashes = vec![];
+    let mut delegations = vec![];
+    for staking_tx_hash in
+        FP_DELEGATIONS
+            .prefix(&btc_pk_hex)
+            .keys(deps.storage, start_after, None, Order::Ascending)
+    {
+        if hashes.len() >= limit {
+            break;
+        }
+        let staking_tx_hash = Txid::from_slice(&staking_tx_hash?)?;
+        if status == BTCDelegationStatus::ANY && !include_delegations {
+            hashes.push(staking_tx_hash.to_string());
+            continue;
+        }
+        let del = btc_delegations().load(deps.storage, staking_tx_hash.as_ref())?;
+        if !has_status(&del, btc_tip.as_ref(), &status) {
+            continue;
+        }
+        hashes.push(staking_tx_hash.to_string());
+        if include_delegations {
+            delegations.push(del);
+        }
+    }
+
+    Ok(DelegationsByFPResponse {
+        hashes,
+        delegations: include_delegations.then_some(delegations),
+    })
 }
 
-/// Active / all delegations by FP convenience query.
+/// Delegations by staker address query.
 ///
-/// This is an alternative to `delegations_by_fp` that returns the actual delegations instead of
-/// just the hashes.
+/// `staker_addr`: The address of the staker
+/// `start_after`: The (reversed) associated staking tx hash of the delegation in hex, if provided.
+/// `status`: List only delegations with this status at the current BTC tip, if provided.
+pub fn delegations_by_staker(
+    deps: Deps,
+    staker_addr: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    status: Option<BTCDelegationStatus>,
+) -> Result<DelegationsByStakerResponse, ContractError> {
+    delegations_by_index(
+        deps,
+        &btc_delegations().idx.staker,
+        staker_addr,
+        start_after,
+        limit,
+        status,
+    )
+}
+
+/// Delegations by staker BTC public key query.
 ///
-/// `btc_pk_hex`: The BTC public key of the finality provider, in hex.
-/// `active` is a filter to return only active delegations
-pub fn active_delegations_by_fp(
+/// `btc_pk_hex`: The BTC public key of the staker, in hex
+/// `start_after`: The (reversed) associated staking tx hash of the delegation in hex, if provided.
+/// `status`: List only delegations with this status at the current BTC tip, if provided.
+pub fn delegations_by_staker_btc_pk(
     deps: Deps,
     btc_pk_hex: String,
-    active: bool,
-) -> Result<BtcDelegationsResponse, ContractError> {
-    let tx_hashes = FP_DELEGATIONS.load(deps.storage, &btc_pk_hex)?;
-    let delegations = tx_hashes
-        .iter()
-        .map(|h| Ok(DELEGATIONS.load(deps.storage, Txid::from_slice(h)?.as_ref())?))
-        .filter(|item| {
-            if let Ok(del) = item {
-                !active || del.is_active()
-            } else {
-                true // don't filter errors
-            }
-        })
-        .collect::<Result<Vec<_>, ContractError>>()?;
-    Ok(BtcDelegationsResponse { delegations })
+    start_after: Option<String>,
+    limit: Option<u32>,
+    status: Option<BTCDelegationStatus>,
+) -> Result<DelegationsByStakerResponse, ContractError> {
+    delegations_by_index(
+        deps,
+        &btc_delegations().idx.staker_btc_pk,
+        btc_pk_hex,
+        start_after,
+        limit,
+        status,
+    )
+}
+
+/// delegations_by_index lists the delegations under the given key of a delegations index, in
+/// staking tx hash order
+fn delegations_by_index(
+    deps: Deps,
+    index: &MultiIndex<String, BtcDelegation, Vec<u8>>,
+    key: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    status: Option<BTCDelegationStatus>,
+) -> Result<DelegationsByStakerResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .as_ref()
+        .map(|s| Txid::from_str(s))
+        .transpose()?;
+    let start_after = start_after.map(|s| Bound::exclusive(s.as_byte_array().to_vec()));
+    let status = status.unwrap_or(BTCDelegationStatus::ANY);
+    let btc_tip = BTC_TIP.may_load(deps.storage)?;
+
+    let mut hashes = vec![];
+    let mut delegations = vec![];
+    for item in index
+        .prefix(key)
+        .range(deps.storage, start_after, None, Order::Ascending)
+    {
+        if hashes.len() >= limit {
+            break;
+        }
+        let (staking_tx_hash, del) = item?;
+        if !has_status(&del, btc_tip.as_ref(), &status) {
+            continue;
+        }
+        hashes.push(Txid::from_slice(&staking_tx_hash)?.to_string());
+        delegations.push(del);
+    }
+
+    Ok(DelegationsByStakerResponse {
+        hashes,
+        delegations,
+    })
+}
+
+/// has_status returns whether the delegation has the given status at the given BTC tip.
+/// Delegations are only received once they are active, so no delegation is pending
+fn has_status(
+    del: &BtcDelegation,
+    btc_tip: Option<&BtcTipInfo>,
+    status: &BTCDelegationStatus,
+) -> bool {
+    match status {
+        BTCDelegationStatus::PENDING => false,
+        BTCDelegationStatus::ACTIVE => del.is_active(btc_tip),
+        BTCDelegationStatus::UNBONDED => !del.is_active(btc_tip),
+        BTCDelegationStatus::ANY => true,
+    }
 }
 
 pub fn finality_provider_info(
@@ -170,6 +315,76 @@ pub fn finality_providers_by_power(
     Ok(FinalityProvidersByPowerResponse { fps })
 }
 
+pub fn finality_provider_commission_history(
+    deps: Deps,
+    btc_pk_hex: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<CommissionHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(Bound::exclusive);
+    let history = FP_COMMISSION_HISTORY
+        .prefix(&btc_pk_hex)
+        .range(deps.storage, start_after, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(height, commission)| CommissionChange { height, commission }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(CommissionHistoryResponse { history })
+}
+
+/// Staking statistics query.
+///
+/// `height`: The block height at which to get the statistics, or the latest ones if `None`.
+/// `top_n`: The number of finality providers with the largest voting power to aggregate the power
+/// of, 10 by default, and at most 30.
+///
+/// At a given height, the power of every finality provider is read, as the power index is not
+/// snapshotted. Only the `top_n` largest powers are kept meanwhile.
+pub fn staking_stats(
+    deps: Deps,
+    height: Option<u64>,
+    top_n: Option<u32>,
+) -> Result<StakingStatsResponse, ContractError> {
+    let top_n = top_n.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let stats = match height {
+        Some(h) => STAKING_STATS.may_load_at_height(deps.storage, h),
+        None => STAKING_STATS.may_load(deps.storage),
+    }?
+    .unwrap_or_default();
+
+    let top_n_power = match height {
+        // The power index is not snapshotted, so the largest powers at the given height are
+        // selected here, in a min-heap of at most `top_n` powers
+        Some(h) => {
+            let fps = fps();
+            let mut top_powers = BinaryHeap::with_capacity(top_n as usize + 1);
+            for btc_pk_hex in FPS.keys(deps.storage, None, None, Order::Ascending) {
+                let power = fps
+                    .may_load_at_height(deps.storage, &btc_pk_hex?, h)?
+                    .map_or(0, |fp_state| fp_state.power);
+                top_powers.push(Reverse(power));
+                if top_powers.len() > top_n as usize {
+                    top_powers.pop();
+                }
+            }
+            top_powers.into_iter().map(|Reverse(power)| power).sum()
+        }
+        None => fps()
+            .idx
+            .power
+            .range(deps.storage, None, None, Descending)
+            .take(top_n as usize)
+            .map(|item| item.map(|(_, fp_state)| fp_state.power))
+            .sum::<StdResult<u64>>()?,
+    };
+
+    Ok(StakingStatsResponse {
+        stats,
+        top_n,
+        top_n_power,
+    })
+}
+
 pub fn activated_height(deps: Deps) -> Result<ActivatedHeightResponse, ContractError> {
     let activated_height = ACTIVATED_HEIGHT.may_load(deps.storage)?.unwrap_or_default();
     Ok(ActivatedHeightResponse {
@@ -183,15 +398,18 @@ mod tests {
     use cosmwasm_std::testing::message_info;
     use cosmwasm_std::testing::{mock_dependencies, mock_env};
     use cosmwasm_std::StdError::NotFound;
-    use cosmwasm_std::{from_json, Env, Storage};
+    use cosmwasm_std::{from_json, Deps, Env, Storage};
 
-    use babylon_apis::btc_staking_api::{FinalityProvider, UnbondedBtcDelegation};
+    use babylon_apis::btc_staking_api::{
+        ActiveBtcDelegation, BTCDelegationStatus, FinalityProvider, UnbondedBtcDelegation,
+    };
     use test_utils::{create_new_finality_provider, get_btc_del_unbonding_sig};
 
     use crate::contract::{execute, instantiate};
     use crate::error::ContractError;
-    use crate::msg::{ExecuteMsg, FinalityProviderInfo, InstantiateMsg};
+    use crate::msg::{ExecuteMsg, FinalityProviderInfo, InstantiateMsg, ParamsByVersionResponse};
     use crate::staking::tests::staking_tx_hash;
+    use crate::state::config::add_params;
     use crate::state::staking::{BtcDelegation, FinalityProviderState, FP_STATE_KEY};
     use crate::test_utils::staking_params;
 
@@ -204,6 +422,23 @@ mod tests {
         env
     }
 
+    // Query the delegations of a finality provider with the given status
+    fn delegations_by_fp_with_status(
+        deps: Deps,
+        btc_pk_hex: &str,
+        status: BTCDelegationStatus,
+    ) -> Result<Vec<BtcDelegation>, ContractError> {
+        let res = crate::queries::delegations_by_fp(
+            deps,
+            btc_pk_hex.to_string(),
+            None,
+            None,
+            Some(status),
+            Some(true),
+        )?;
+        Ok(res.delegations.unwrap())
+    }
+
     // Sort delegations by staking tx hash
     fn sort_delegations(dels: &[BtcDelegation]) -> Vec<BtcDelegation> {
         let mut dels = dels.to_vec();
@@ -211,6 +446,62 @@ mod tests {
         dels
     }
 
+    #[test]
+    fn test_params_by_version() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        let params = staking_params();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(params.clone()),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        let mut new_params = params.clone();
+        new_params.min_slashing_tx_fee_sat += 1;
+        add_params(deps.as_mut().storage, 1, &new_params, 100).unwrap();
+        // Activation heights must increase
+        let err = add_params(deps.as_mut().storage, 2, &new_params, 100).unwrap_err();
+        assert_eq!(err, ContractError::InvalidParamsActivationHeight(100, 100));
+        // Versions must follow the latest one, without duplicates or gaps
+        let err = add_params(deps.as_mut().storage, 1, &new_params, 200).unwrap_err();
+        assert_eq!(err, ContractError::UnexpectedParamsVersion(1, 2));
+        let err = add_params(deps.as_mut().storage, 3, &new_params, 200).unwrap_err();
+        assert_eq!(err, ContractError::UnexpectedParamsVersion(3, 2));
+
+        let res = crate::queries::params_by_version(deps.as_ref(), 0).unwrap();
+        assert_eq!(
+            res,
+            ParamsByVersionResponse {
+                version: 0,
+                btc_start_height: 0,
+                btc_end_height: Some(99),
+                params,
+            }
+        );
+        let res = crate::queries::params_by_version(deps.as_ref(), 1).unwrap();
+        assert_eq!(
+            res,
+            ParamsByVersionResponse {
+                version: 1,
+                btc_start_height: 100,
+                btc_end_height: None,
+                params: new_params.clone(),
+            }
+        );
+        let err = crate::queries::params_by_version(deps.as_ref(), 2).unwrap_err();
+        assert_eq!(err, ContractError::ParamsVersionNotFound(2));
+
+        // The latest params are the current ones
+        assert_eq!(crate::queries::params(deps.as_ref()).unwrap(), new_params);
+    }
+
     #[test]
     fn test_finality_providers() {
         let mut deps = mock_dependencies();
@@ -237,6 +528,8 @@ mod tests {
             active_del: vec![],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -296,6 +589,8 @@ mod tests {
             active_del: vec![],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -309,6 +604,8 @@ mod tests {
             active_del: vec![del1.clone(), del2.clone()],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -368,6 +665,8 @@ mod tests {
             active_del: vec![],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -381,6 +680,8 @@ mod tests {
             active_del: vec![del1.clone(), del2.clone()],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -407,6 +708,8 @@ mod tests {
                 staking_tx_hash: staking_tx_hash_hex,
                 unbonding_tx_sig: unbonding_sig.to_bytes().into(),
             }],
+            edit_fp: vec![],
+            best_effort: None,
         };
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
@@ -430,6 +733,80 @@ mod tests {
         assert_eq!(dels.len(), 2);
     }
 
+    #[test]
+    fn test_active_delegations_at_btc_tip() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        let params = staking_params();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(params),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        // Add a finality provider and a delegation to it
+        let new_fp1 = create_new_finality_provider(1);
+        let del1 = test_utils::get_derived_btc_delegation(1, &[1]);
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp1.clone()],
+            active_del: vec![del1.clone()],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // Without a BTC tip, the time-lock is not considered
+        let dels = crate::queries::delegations(deps.as_ref(), None, None, Some(true))
+            .unwrap()
+            .delegations;
+        assert_eq!(dels.len(), 1);
+
+        let w = 2;
+        let active_at = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, btc_height: u64| {
+            let msg = ExecuteMsg::BtcTipUpdate {
+                height: btc_height,
+                checkpoint_finalization_timeout: w,
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+            let active = crate::queries::delegations(deps.as_ref(), None, None, Some(true))
+                .unwrap()
+                .delegations
+                .len();
+            let active_by_fp = delegations_by_fp_with_status(
+                deps.as_ref(),
+                &new_fp1.btc_pk_hex,
+                BTCDelegationStatus::ACTIVE,
+            )
+            .unwrap()
+            .len();
+            assert_eq!(active, active_by_fp);
+            active == 1
+        };
+
+        // The time-lock has not started yet
+        assert!(!active_at(&mut deps, del1.start_height - 1));
+        // The time-lock has started
+        assert!(active_at(&mut deps, del1.start_height));
+        // Exactly `w` BTC blocks are left in the time-lock
+        assert!(active_at(&mut deps, del1.end_height - w));
+        // Less than `w` BTC blocks are left in the time-lock
+        assert!(!active_at(&mut deps, del1.end_height - w + 1));
+
+        // All delegations are still listed
+        let dels = crate::queries::delegations(deps.as_ref(), None, None, None)
+            .unwrap()
+            .delegations;
+        assert_eq!(dels.len(), 1);
+    }
+
     #[test]
     fn test_delegations_by_fp() {
         let mut deps = mock_dependencies();
@@ -458,6 +835,8 @@ mod tests {
             active_del: vec![],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -471,24 +850,184 @@ mod tests {
             active_del: vec![del1.clone(), del2.clone()],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
         // Query delegations by finality provider
-        let dels1 = crate::queries::delegations_by_fp(deps.as_ref(), fp1_pk.clone())
-            .unwrap()
-            .hashes;
+        let dels1 = crate::queries::delegations_by_fp(
+            deps.as_ref(),
+            fp1_pk.clone(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .hashes;
         assert_eq!(dels1.len(), 1);
-        let dels2 = crate::queries::delegations_by_fp(deps.as_ref(), fp2_pk.clone())
-            .unwrap()
-            .hashes;
+        let dels2 = crate::queries::delegations_by_fp(
+            deps.as_ref(),
+            fp2_pk.clone(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .hashes;
         assert_eq!(dels2.len(), 1);
         assert_ne!(dels1[0], dels2[0]);
-        let err = crate::queries::delegations_by_fp(deps.as_ref(), "f3".to_string()).unwrap_err();
+        let err = crate::queries::delegations_by_fp(
+            deps.as_ref(),
+            "f3".to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
         assert!(matches!(err, ContractError::Std(NotFound { .. })));
     }
 
+    #[test]
+    fn test_delegations_by_staker() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        let params = staking_params();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(params),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        // Add a couple finality providers
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![
+                create_new_finality_provider(1),
+                create_new_finality_provider(2),
+            ],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // Add a couple delegations of the same staker address, with different staker BTC keys
+        let del1 = test_utils::get_derived_btc_delegation(1, &[1]);
+        let del2 = ActiveBtcDelegation {
+            staker_addr: del1.staker_addr.clone(),
+            ..test_utils::get_derived_btc_delegation(2, &[2])
+        };
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![],
+            active_del: vec![del1.clone(), del2.clone()],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let del1 = BtcDelegation::from(&del1);
+        let del2 = BtcDelegation::from(&del2);
+        let hash1 = "69f9744cb6a4e3c26a6820265a1a0340e9c18b75a1b618b2af2e60da737035b2".to_string();
+        let hash2 = "1212970e8b6255828e85ee98415f59e0cc84419affc8bd57d3fbbcd6864c8185".to_string();
+        assert_eq!(staking_tx_hash(&del1).to_string(), hash1);
+        assert_eq!(staking_tx_hash(&del2).to_string(), hash2);
+
+        // Query delegations by staker address
+        let res = crate::queries::delegations_by_staker(
+            deps.as_ref(),
+            del1.staker_addr.clone(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        // In staking tx hash order
+        assert_eq!(res.hashes, vec![hash2.clone(), hash1.clone()]);
+        assert_eq!(res.delegations, vec![del2.clone(), del1.clone()]);
+
+        // Paginate with a limit
+        let res = crate::queries::delegations_by_staker(
+            deps.as_ref(),
+            del1.staker_addr.clone(),
+            None,
+            Some(1),
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.hashes, vec![hash2.clone()]);
+        assert_eq!(res.delegations, vec![del2]);
+        let res = crate::queries::delegations_by_staker(
+            deps.as_ref(),
+            del1.staker_addr.clone(),
+            Some(hash2),
+            Some(1),
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.hashes, vec![hash1.clone()]);
+        assert_eq!(res.delegations, vec![del1.clone()]);
+
+        // Query delegations by staker BTC public key
+        let res = crate::queries::delegations_by_staker_btc_pk(
+            deps.as_ref(),
+            del1.btc_pk_hex.clone(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.hashes, vec![hash1.clone()]);
+        assert_eq!(res.delegations, vec![del1.clone()]);
+
+        // Paginate after the last delegation
+        let res = crate::queries::delegations_by_staker_btc_pk(
+            deps.as_ref(),
+            del1.btc_pk_hex.clone(),
+            Some(hash1),
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(res.hashes.is_empty());
+
+        // Filter by status
+        let res = crate::queries::delegations_by_staker_btc_pk(
+            deps.as_ref(),
+            del1.btc_pk_hex.clone(),
+            None,
+            None,
+            Some(BTCDelegationStatus::UNBONDED),
+        )
+        .unwrap();
+        assert!(res.hashes.is_empty());
+
+        // Unknown stakers have no delegations
+        let res = crate::queries::delegations_by_staker(
+            deps.as_ref(),
+            "unknown".to_string(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(res.hashes.is_empty());
+        assert!(res.delegations.is_empty());
+    }
+
     #[test]
     fn test_active_delegations_by_fp() {
         let mut deps = mock_dependencies();
@@ -515,6 +1054,8 @@ mod tests {
             active_del: vec![],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -532,20 +1073,21 @@ mod tests {
             active_del: vec![del1.clone(), del2.clone()],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
         // Query all delegations by finality provider
-        let dels1 = crate::queries::active_delegations_by_fp(deps.as_ref(), fp1_pk.clone(), false)
-            .unwrap()
-            .delegations;
+        let dels1 = delegations_by_fp_with_status(deps.as_ref(), &fp1_pk, BTCDelegationStatus::ANY)
+            .unwrap();
         assert_eq!(dels1.len(), 2);
 
         // Query active delegations by finality provider
-        let dels1 = crate::queries::active_delegations_by_fp(deps.as_ref(), fp1_pk.clone(), true)
-            .unwrap()
-            .delegations;
+        let dels1 =
+            delegations_by_fp_with_status(deps.as_ref(), &fp1_pk, BTCDelegationStatus::ACTIVE)
+                .unwrap();
         assert_eq!(dels1.len(), 2);
 
         // Unbond the first delegation
@@ -560,21 +1102,65 @@ mod tests {
                 staking_tx_hash: staking_tx_hash_hex,
                 unbonding_tx_sig: unbonding_sig.to_bytes().into(),
             }],
+            edit_fp: vec![],
+            best_effort: None,
         };
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
         // Query all delegations by finality provider
-        let dels1 = crate::queries::active_delegations_by_fp(deps.as_ref(), fp1_pk.clone(), false)
-            .unwrap()
-            .delegations;
+        let dels1 = delegations_by_fp_with_status(deps.as_ref(), &fp1_pk, BTCDelegationStatus::ANY)
+            .unwrap();
         assert_eq!(dels1.len(), 2);
 
         // Query active delegations by finality provider
-        let dels1 = crate::queries::active_delegations_by_fp(deps.as_ref(), fp1_pk.clone(), true)
-            .unwrap()
-            .delegations;
+        let dels1 =
+            delegations_by_fp_with_status(deps.as_ref(), &fp1_pk, BTCDelegationStatus::ACTIVE)
+                .unwrap();
         assert_eq!(dels1.len(), 1);
-        let err = crate::queries::active_delegations_by_fp(deps.as_ref(), "f2".to_string(), false)
+        assert_eq!(dels1[0].total_sat, 200);
+
+        // Query unbonded delegations by finality provider
+        let dels1 =
+            delegations_by_fp_with_status(deps.as_ref(), &fp1_pk, BTCDelegationStatus::UNBONDED)
+                .unwrap();
+        assert_eq!(dels1.len(), 1);
+        assert_eq!(dels1[0].total_sat, 100);
+
+        // Paginate delegations by finality provider
+        let page1 = crate::queries::delegations_by_fp(
+            deps.as_ref(),
+            fp1_pk.clone(),
+            None,
+            Some(1),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(page1.hashes.len(), 1);
+        assert_eq!(page1.delegations, None);
+        let page2 = crate::queries::delegations_by_fp(
+            deps.as_ref(),
+            fp1_pk.clone(),
+            Some(page1.hashes[0].clone()),
+            Some(1),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(page2.hashes.len(), 1);
+        assert_ne!(page1.hashes[0], page2.hashes[0]);
+        let page3 = crate::queries::delegations_by_fp(
+            deps.as_ref(),
+            fp1_pk.clone(),
+            Some(page2.hashes[0].clone()),
+            Some(1),
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(page3.hashes.is_empty());
+
+        let err = delegations_by_fp_with_status(deps.as_ref(), "f2", BTCDelegationStatus::ANY)
             .unwrap_err();
         assert!(matches!(err, ContractError::Std(NotFound { .. })));
     }
@@ -607,6 +1193,8 @@ mod tests {
             active_del: vec![],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         let _res = execute(deps.as_mut(), initial_env, info.clone(), msg).unwrap();
@@ -624,6 +1212,8 @@ mod tests {
             active_del: vec![del1.clone(), del2.clone()],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         execute(deps.as_mut(), mock_env_height(11), info.clone(), msg).unwrap();
@@ -710,6 +1300,8 @@ mod tests {
             active_del: vec![],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -724,6 +1316,8 @@ mod tests {
             active_del: vec![del1.clone()],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -768,6 +1362,8 @@ mod tests {
             active_del: vec![],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -787,6 +1383,8 @@ mod tests {
             active_del: vec![del1.clone(), del2.clone(), del3],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
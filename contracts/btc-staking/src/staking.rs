@@ -1,33 +1,46 @@
 use bitcoin::absolute::LockTime;
 use bitcoin::consensus::deserialize;
 use bitcoin::hashes::Hash;
-use bitcoin::{Transaction, Txid};
-use cosmwasm_std::{DepsMut, Env, Event, MessageInfo, Response, Storage};
+use bitcoin::{Transaction, TxMerkleNode, Txid};
+use cosmwasm_std::{
+    to_json_binary, Addr, Deps, DepsMut, Env, Event, MessageInfo, Order, Response, StdResult,
+    Storage,
+};
+use cw_storage_plus::PrefixBound;
 use hex::ToHex;
 
+use std::collections::HashSet;
 use std::str::FromStr;
 
 use crate::error::ContractError;
-use crate::state::config::{ADMIN, CONFIG, PARAMS};
+use crate::state::config::{get_latest_params, get_params_for_btc_height, ADMIN, CONFIG, PARAMS};
 use crate::state::staking::{
-    fps, BtcDelegation, FinalityProviderState, ACTIVATED_HEIGHT, DELEGATIONS, DELEGATION_FPS, FPS,
-    FP_DELEGATIONS,
+    btc_delegations, fps, BtcDelegation, BtcTipInfo, FinalityProviderState, StakingStats,
+    ACTIVATED_HEIGHT, BTC_TIP, DELEGATION_EXPIRY_QUEUE, DELEGATION_FPS, FPS, FP_COMMISSION_HISTORY,
+    FP_DELEGATIONS, STAKING_STATS,
 };
 use crate::validation::{
-    verify_active_delegation, verify_new_fp, verify_slashed_delegation, verify_undelegation,
+    verify_active_delegation, verify_commission, verify_new_fp, verify_slashed_delegation,
+    verify_undelegation,
 };
 use babylon_apis::btc_staking_api::{
-    ActiveBtcDelegation, FinalityProvider, NewFinalityProvider, SlashedBtcDelegation,
-    UnbondedBtcDelegation,
+    ActiveBtcDelegation, BtcStakingItemKind, BtcStakingItemResult, BtcStakingResponse,
+    EditFinalityProvider, FinalityProvider, NewFinalityProvider, SlashedBtcDelegation,
+    UnbondedBtcDelegation, HASH_SIZE,
 };
 
 use babylon_apis::Validate;
 use babylon_bindings::BabylonMsg;
+use babylon_bitcoin::merkle::verify_merkle_proof;
 use babylon_contract::msg::btc_header::BtcHeaderResponse;
 
 use babylon_contract::msg::contract::QueryMsg as BabylonQueryMsg;
+use babylon_contract::state::config::Config as BabylonConfig;
 
-/// handle_btc_staking handles the BTC staking operations
+/// handle_btc_staking handles the BTC staking operations.
+/// In best-effort mode, invalid items are skipped and reported in the response data, instead of
+/// failing the whole message
+#[allow(clippy::too_many_arguments)]
 pub fn handle_btc_staking(
     deps: DepsMut,
     env: Env,
@@ -36,38 +49,113 @@ pub fn handle_btc_staking(
     active_delegations: &[ActiveBtcDelegation],
     slashed_delegations: &[SlashedBtcDelegation],
     unbonded_delegations: &[UnbondedBtcDelegation],
+    edited_fps: &[EditFinalityProvider],
+    best_effort: bool,
 ) -> Result<Response<BabylonMsg>, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     if info.sender != config.babylon && !ADMIN.is_admin(deps.as_ref(), &info.sender)? {
         return Err(ContractError::Unauthorized);
     }
 
-    let mut res = Response::new();
+    let mut items = ItemResults::new(best_effort);
 
     for fp in new_fps {
-        handle_new_fp(deps.storage, fp, env.block.height)?;
+        let result = handle_new_fp(deps.storage, fp, env.block.height).map(|_| None);
         // TODO: Add event
+        items.record(BtcStakingItemKind::NewFp, fp.btc_pk_hex.clone(), result)?;
+    }
+
+    // Process finality provider edits
+    for fp in edited_fps {
+        let result = handle_edit_fp(deps.storage, fp, env.block.height).map(Some);
+        items.record(BtcStakingItemKind::EditFp, fp.btc_pk_hex.clone(), result)?;
     }
 
     // Process active delegations
     for del in active_delegations {
-        handle_active_delegation(deps.storage, env.block.height, del)?;
+        let result = verify_staking_tx_inclusion(deps.as_ref(), &config.babylon, del)
+            .and_then(|_| handle_active_delegation(deps.storage, env.block.height, del))
+            .map(|_| None);
         // TODO: Add event
+        let id = deserialize::<Transaction>(&del.staking_tx)
+            .map(|tx| tx.txid().to_string())
+            .unwrap_or_else(|_| del.staking_tx.encode_hex());
+        items.record(BtcStakingItemKind::ActiveDel, id, result)?;
     }
 
     // Process slashed delegations
     for del in slashed_delegations {
-        let ev = handle_slashed_delegation(deps.storage, env.block.height, del)?;
-        res = res.add_event(ev);
+        let result = handle_slashed_delegation(deps.storage, env.block.height, del).map(Some);
+        items.record(
+            BtcStakingItemKind::SlashedDel,
+            del.staking_tx_hash.clone(),
+            result,
+        )?;
     }
 
     // Process undelegations
     for undel in unbonded_delegations {
-        let ev = handle_undelegation(deps.storage, env.block.height, undel)?;
-        res = res.add_event(ev);
+        let result = handle_undelegation(deps.storage, env.block.height, undel).map(Some);
+        items.record(
+            BtcStakingItemKind::UnbondedDel,
+            undel.staking_tx_hash.clone(),
+            result,
+        )?;
+    }
+
+    let data = BtcStakingResponse {
+        results: items.results,
+    };
+    Ok(Response::new()
+        .add_events(items.events)
+        .set_data(to_json_binary(&data)?))
+}
+
+/// ItemResults collects the processing results of the items of a `BtcStaking` message
+struct ItemResults {
+    best_effort: bool,
+    results: Vec<BtcStakingItemResult>,
+    events: Vec<Event>,
+}
+
+impl ItemResults {
+    fn new(best_effort: bool) -> Self {
+        Self {
+            best_effort,
+            results: vec![],
+            events: vec![],
+        }
     }
 
-    Ok(res)
+    /// record records the result of an item. Failures are only recorded in best-effort mode,
+    /// otherwise they are returned as errors.
+    ///
+    /// NOTE: The item handlers do all their checks before writing to storage, so that failed
+    /// items leave no partial state behind
+    fn record(
+        &mut self,
+        kind: BtcStakingItemKind,
+        id: String,
+        result: Result<Option<Event>, ContractError>,
+    ) -> Result<(), ContractError> {
+        let error = match result {
+            Ok(event) => {
+                self.events.extend(event);
+                None
+            }
+            Err(err) if self.best_effort => {
+                let event = Event::new("btc_staking_item_failed")
+                    .add_attribute("kind", kind.as_str())
+                    .add_attribute("id", &id)
+                    .add_attribute("error", err.to_string());
+                self.events.push(event);
+                Some(err.to_string())
+            }
+            Err(err) => return Err(err),
+        };
+        self.results.push(BtcStakingItemResult { kind, id, error });
+        Ok(())
+    }
 }
 
 /// handle_bew_fp handles registering a new finality provider
@@ -85,32 +173,73 @@ pub fn handle_new_fp(
     // basic validations on the finality provider data
     new_fp.validate()?;
 
-    // verify the finality provider registration request (full or lite)
-    verify_new_fp(new_fp)?;
+    // verify the finality provider registration request
+    let (_, params) = get_latest_params(storage)?;
+    verify_new_fp(new_fp, &params)?;
+    verify_commission(&params, new_fp.commission, None)?;
 
     // get DB object
     let fp = FinalityProvider::from(new_fp);
 
     // save to DB
     FPS.save(storage, &fp.btc_pk_hex, &fp)?;
+    FP_COMMISSION_HISTORY.save(storage, (&fp.btc_pk_hex, height), &fp.commission)?;
     // Set its voting power to zero
     let fp_state = FinalityProviderState::default();
     fps().save(storage, &fp.btc_pk_hex, &fp_state, height)?;
+    update_stats(storage, height, |stats| {
+        stats.finality_providers += 1;
+    })?;
 
     Ok(())
 }
 
+/// handle_edit_fp handles editing the description and / or commission of an existing finality
+/// provider
+pub fn handle_edit_fp(
+    storage: &mut dyn Storage,
+    edit_fp: &EditFinalityProvider,
+    height: u64,
+) -> Result<Event, ContractError> {
+    // basic validations on the finality provider data
+    edit_fp.validate()?;
+
+    let mut fp = FPS
+        .may_load(storage, &edit_fp.btc_pk_hex)?
+        .ok_or_else(|| ContractError::FinalityProviderNotFound(edit_fp.btc_pk_hex.clone()))?;
+
+    let mut event = Event::new("finality_provider_edited")
+        .add_attribute("btc_pk_hex", &edit_fp.btc_pk_hex)
+        .add_attribute("height", height.to_string());
+    if let Some(description) = &edit_fp.description {
+        fp.description = Some(description.clone());
+        event = event.add_attribute("moniker", &description.moniker);
+    }
+    if let Some(commission) = edit_fp.commission {
+        // verify the commission rate and its change against the latest params
+        let (_, params) = get_latest_params(storage)?;
+        verify_commission(&params, commission, Some(fp.commission))?;
+        fp.commission = commission;
+        FP_COMMISSION_HISTORY.save(storage, (&edit_fp.btc_pk_hex, height), &commission)?;
+        event = event.add_attribute("commission", commission.to_string());
+    }
+
+    FPS.save(storage, &edit_fp.btc_pk_hex, &fp)?;
+
+    Ok(event)
+}
+
 pub fn handle_active_delegation(
     storage: &mut dyn Storage,
     height: u64,
     active_delegation: &ActiveBtcDelegation,
 ) -> Result<(), ContractError> {
-    // TODO: Get params
-    // btc_confirmation_depth
-    // checkpoint_finalization_timeout
-    // minimum_unbonding_time
-
-    let params = PARAMS.load(storage)?;
+    // Get the params version the delegation declares, which must apply to its start height
+    let params = get_params_for_btc_height(
+        storage,
+        active_delegation.params_version,
+        active_delegation.start_height,
+    )?;
 
     // Basic stateless checks
     active_delegation.validate()?;
@@ -121,8 +250,38 @@ pub fn handle_active_delegation(
     // - are not slashed, and
     // - their registered epochs are finalised
     // and then check whether the BTC stake is restaked to FPs of consumers
-    // TODO: ensure the BTC delegation does not restake to too many finality providers
-    // (pending concrete design)
+
+    // Ensure the BTC delegation does not restake to too many finality providers
+    if active_delegation.fp_btc_pk_list.len() > params.max_fps_per_delegation as usize {
+        return Err(ContractError::TooManyFinalityProviders(
+            active_delegation.fp_btc_pk_list.len(),
+            params.max_fps_per_delegation,
+        ));
+    }
+
+    // Get the registered, non-slashed finality providers.
+    // Unregistered finality providers are skipped, as they can belong to another Consumer, or
+    // Babylon.
+    // A delegation can restake to at most one finality provider per Consumer that gets its
+    // voting power. Slashed finality providers get no voting power, so they don't count
+    let mut registered_fps = vec![];
+    let mut consumer_ids = HashSet::new();
+    for fp_btc_pk_hex in &active_delegation.fp_btc_pk_list {
+        if let Some(fp) = FPS.may_load(storage, fp_btc_pk_hex)? {
+            if fp.slashed_height != 0 {
+                continue;
+            }
+            if !consumer_ids.insert(fp.consumer_id.clone()) {
+                return Err(ContractError::DuplicatedConsumerFinalityProvider(
+                    fp.consumer_id,
+                ));
+            }
+            registered_fps.push(fp_btc_pk_hex);
+        }
+    }
+    if registered_fps.is_empty() {
+        return Err(ContractError::FinalityProviderNotRegistered);
+    }
 
     // Parse staking tx
     let staking_tx: Transaction = deserialize(&active_delegation.staking_tx)
@@ -144,14 +303,44 @@ pub fn handle_active_delegation(
     let staking_tx_hash = staking_tx.txid();
 
     // Check staking tx is not duplicated
-    if DELEGATIONS.has(storage, staking_tx_hash.as_ref()) {
+    if btc_delegations().has(storage, staking_tx_hash.as_ref()) {
         return Err(ContractError::DelegationAlreadyExists(
             staking_tx_hash.to_string(),
         ));
     }
 
-    // verify the active delegation (full or lite)
-    verify_active_delegation(&params, active_delegation, &staking_tx)?;
+    // verify the active delegation (signatures, plus the BTC txs with full validation)
+    let btc_tip = BTC_TIP.may_load(storage)?;
+    let checkpoint_finalization_timeout = btc_tip
+        .as_ref()
+        .map_or(0, |tip| tip.checkpoint_finalization_timeout);
+    verify_active_delegation(
+        &params,
+        active_delegation,
+        &staking_tx,
+        checkpoint_finalization_timeout,
+    )?;
+
+    // Ensure the time-lock of the staking tx has begun, and has not expired at the current BTC
+    // tip. Otherwise, the delegation would not be active, and its power would not be accounted
+    // consistently with its status
+    if let Some(tip) = btc_tip {
+        if tip.height < active_delegation.start_height {
+            return Err(ContractError::DelegationNotStarted(
+                staking_tx_hash.to_string(),
+                active_delegation.start_height,
+                tip.height,
+            ));
+        }
+        if BtcDelegation::from(active_delegation)
+            .is_expired(tip.height, tip.checkpoint_finalization_timeout)
+        {
+            return Err(ContractError::DelegationExpired(
+                staking_tx_hash.to_string(),
+                tip.height,
+            ));
+        }
+    }
 
     // All good, construct BTCDelegation and insert BTC delegation
     // NOTE: the BTC delegation does not have voting power yet.
@@ -161,25 +350,10 @@ pub fn handle_active_delegation(
 
     // Update delegations by registered finality provider
     let fps = fps();
-    let mut registered_fp = false;
-    for fp_btc_pk_hex in &active_delegation.fp_btc_pk_list {
-        // Skip if finality provider is not registered, as it can belong to another Consumer, or Babylon
-        if !FPS.has(storage, fp_btc_pk_hex) {
-            continue;
-        }
-
-        // Skip slashed FPs
-        let fp = FPS.load(storage, fp_btc_pk_hex)?;
-        if fp.slashed_height > 0 {
-            continue;
-        }
-
+    let mut added_power = 0;
+    for fp_btc_pk_hex in registered_fps {
         // Update staking tx hash by finality provider map
-        let mut fp_delegations = FP_DELEGATIONS
-            .may_load(storage, fp_btc_pk_hex)?
-            .unwrap_or(vec![]);
-        fp_delegations.push(staking_tx_hash.as_byte_array().to_vec());
-        FP_DELEGATIONS.save(storage, fp_btc_pk_hex, &fp_delegations)?;
+        FP_DELEGATIONS.save(storage, (fp_btc_pk_hex, staking_tx_hash.as_ref()), &())?;
 
         // Update finality provider by staking tx hash reverse map
         let mut delegation_fps = DELEGATION_FPS
@@ -188,22 +362,34 @@ pub fn handle_active_delegation(
         delegation_fps.push(fp_btc_pk_hex.clone());
         DELEGATION_FPS.save(storage, staking_tx_hash.as_ref(), &delegation_fps)?;
 
-        // Update aggregated voting power by FP
+        // Update aggregated voting power by FP.
+        // The stake is restaked, so every finality provider gets the full staked amount
         fps.update(storage, fp_btc_pk_hex, height, |fp_state| {
             let mut fp_state = fp_state.unwrap_or_default();
-            fp_state.power = fp_state.power.saturating_add(active_delegation.total_sat);
+            let power = fp_state.power.saturating_add(active_delegation.total_sat);
+            added_power += power - fp_state.power;
+            fp_state.power = power;
             Ok::<_, ContractError>(fp_state)
         })?;
-
-        registered_fp = true;
     }
+    update_stats(storage, height, |stats| {
+        stats.active_delegations += 1;
+        stats.total_active_sat = stats
+            .total_active_sat
+            .saturating_add(active_delegation.total_sat);
+        stats.total_power = stats.total_power.saturating_add(added_power);
+    })?;
 
-    if !registered_fp {
-        return Err(ContractError::FinalityProviderNotRegistered);
-    }
     // Add this BTC delegation
     let delegation = BtcDelegation::from(active_delegation);
-    DELEGATIONS.save(storage, staking_tx_hash.as_ref(), &delegation)?;
+    btc_delegations().save(storage, staking_tx_hash.as_ref(), &delegation)?;
+
+    // Queue the delegation for expiry when its time-lock ends
+    DELEGATION_EXPIRY_QUEUE.save(
+        storage,
+        (delegation.end_height, staking_tx_hash.as_ref()),
+        &(),
+    )?;
 
     // Store activated height, if first delegation
     if ACTIVATED_HEIGHT.may_load(storage)?.is_none() {
@@ -225,19 +411,25 @@ fn handle_undelegation(
     undelegation.validate()?;
 
     let staking_tx_hash = Txid::from_str(&undelegation.staking_tx_hash)?;
-    let mut btc_del = DELEGATIONS.load(storage, staking_tx_hash.as_ref())?;
+    let mut btc_del = btc_delegations().load(storage, staking_tx_hash.as_ref())?;
 
     // Ensure the BTC delegation is active
-    if !btc_del.is_active() {
+    let btc_tip = BTC_TIP.may_load(storage)?;
+    if !btc_del.is_active(btc_tip.as_ref()) {
         return Err(ContractError::DelegationIsNotActive(
             staking_tx_hash.to_string(),
         ));
     }
 
-    // verify the early unbonded delegation (full or lite)
-    let params = PARAMS.load(storage)?;
+    // verify the early unbonded delegation, against the params version of the
+    // delegation
+    let params = PARAMS
+        .may_load(storage, btc_del.params_version)?
+        .ok_or(ContractError::ParamsVersionNotFound(btc_del.params_version))?;
     verify_undelegation(&params, &btc_del, &undelegation.unbonding_tx_sig)?;
 
+    let affected_fps = DELEGATION_FPS.load(storage, staking_tx_hash.as_ref())?;
+
     // Add the signature to the BTC delegation's undelegation and set back
     btc_undelegate(
         storage,
@@ -247,16 +439,14 @@ fn handle_undelegation(
     )?;
 
     // Discount the voting power from the affected finality providers
-    let affected_fps = DELEGATION_FPS.load(storage, staking_tx_hash.as_ref())?;
-    let fps = fps();
-    for fp in affected_fps {
-        fps.update(storage, &fp, height, |fp_state| {
-            let mut fp_state =
-                fp_state.ok_or(ContractError::FinalityProviderNotFound(fp.clone()))?; // should never happen
-            fp_state.power = fp_state.power.saturating_sub(btc_del.total_sat);
-            Ok::<_, ContractError>(fp_state)
-        })?;
-    }
+    let discounted = discount_power(storage, height, &affected_fps, btc_del.total_sat)?;
+    update_stats(storage, height, |stats| {
+        stats.active_delegations = stats.active_delegations.saturating_sub(1);
+        stats.unbonded_delegations += 1;
+        stats.total_active_sat = stats.total_active_sat.saturating_sub(btc_del.total_sat);
+        stats.total_power = stats.total_power.saturating_sub(discounted);
+    })?;
+
     // Record event that the BTC delegation becomes unbonded
     let unbonding_event = Event::new("btc_undelegation")
         .add_attribute("staking_tx_hash", staking_tx_hash.to_string())
@@ -276,34 +466,33 @@ fn handle_slashed_delegation(
     delegation.validate()?;
 
     let staking_tx_hash = Txid::from_str(&delegation.staking_tx_hash)?;
-    let mut btc_del = DELEGATIONS.load(storage, staking_tx_hash.as_ref())?;
+    let mut btc_del = btc_delegations().load(storage, staking_tx_hash.as_ref())?;
 
     // Ensure the BTC delegation is active
-    if !btc_del.is_active() {
+    let btc_tip = BTC_TIP.may_load(storage)?;
+    if !btc_del.is_active(btc_tip.as_ref()) {
         return Err(ContractError::DelegationIsNotActive(
             staking_tx_hash.to_string(),
         ));
     }
 
-    // verify the slashed delegation (full or lite)
+    // verify the slashed delegation
     let recovered_fp_sk_hex = delegation.recovered_fp_btc_sk.clone();
     verify_slashed_delegation(&btc_del, &recovered_fp_sk_hex)?;
 
     // Discount the voting power from the affected finality providers
     let affected_fps = DELEGATION_FPS.load(storage, staking_tx_hash.as_ref())?;
-    let fps = fps();
-    for fp in affected_fps {
-        fps.update(storage, &fp, height, |fp_state| {
-            let mut fp_state =
-                fp_state.ok_or(ContractError::FinalityProviderNotFound(fp.clone()))?; // should never happen
-            fp_state.power = fp_state.power.saturating_sub(btc_del.total_sat);
-            Ok::<_, ContractError>(fp_state)
-        })?;
-    }
+    let discounted = discount_power(storage, height, &affected_fps, btc_del.total_sat)?;
+    update_stats(storage, height, |stats| {
+        stats.active_delegations = stats.active_delegations.saturating_sub(1);
+        stats.slashed_delegations += 1;
+        stats.total_active_sat = stats.total_active_sat.saturating_sub(btc_del.total_sat);
+        stats.total_power = stats.total_power.saturating_sub(discounted);
+    })?;
 
     // Mark the delegation as slashed
     btc_del.slashed = true;
-    DELEGATIONS.save(storage, staking_tx_hash.as_ref(), &btc_del)?;
+    btc_delegations().save(storage, staking_tx_hash.as_ref(), &btc_del)?;
 
     // Record event that the BTC delegation becomes unbonded due to slashing at this height
     let slashing_event = Event::new("btc_undelegation_slashed")
@@ -327,6 +516,144 @@ pub fn handle_slash_fp(
     slash_finality_provider(deps, env, fp_btc_pk_hex)
 }
 
+/// handle_btc_tip_update records the BTC tip notified by the babylon contract, which the status of
+/// the delegations is computed against, and expires the delegations whose time-lock has ended
+pub fn handle_btc_tip_update(
+    deps: DepsMut,
+    env: Env,
+    info: &MessageInfo,
+    height: u64,
+    checkpoint_finalization_timeout: u64,
+) -> Result<Response<BabylonMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.babylon && !ADMIN.is_admin(deps.as_ref(), &info.sender)? {
+        return Err(ContractError::Unauthorized);
+    }
+    let btc_tip = BtcTipInfo {
+        height,
+        checkpoint_finalization_timeout,
+    };
+    BTC_TIP.save(deps.storage, &btc_tip)?;
+
+    let events = expire_delegations(
+        deps.storage,
+        env.block.height,
+        &btc_tip,
+        MAX_EXPIRIES_PER_BTC_TIP_UPDATE,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "btc_tip_update")
+        .add_attribute("btc_height", height.to_string())
+        .add_events(events))
+}
+
+/// Maximum number of expiry queue entries processed per BTC tip update, so that BTC tip updates
+/// (sent along with BTC headers and timestamps by the babylon contract) stay within gas limits
+const MAX_EXPIRIES_PER_BTC_TIP_UPDATE: usize = 100;
+
+/// expire_delegations removes the voting power of the delegations that have less than `w` BTC
+/// blocks left in their time-lock at the given BTC tip, and marks them as expired.
+/// Delegations that were unbonded early or slashed in the meantime have no power left, and are just
+/// removed from the expiry queue.
+/// At most `limit` queue entries are processed; the rest are carried over to the next BTC tip
+/// update
+fn expire_delegations(
+    storage: &mut dyn Storage,
+    height: u64,
+    btc_tip: &BtcTipInfo,
+    limit: usize,
+) -> Result<Vec<Event>, ContractError> {
+    // A delegation is expired if `btc_height + w > end_height`
+    let expiry_bound = btc_tip.height + btc_tip.checkpoint_finalization_timeout;
+    let expired = DELEGATION_EXPIRY_QUEUE
+        .prefix_range(
+            storage,
+            None,
+            Some(PrefixBound::exclusive(expiry_bound)),
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(key, _)| key))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut events = vec![];
+    for (end_height, staking_tx_hash) in expired {
+        DELEGATION_EXPIRY_QUEUE.remove(storage, (end_height, &staking_tx_hash));
+
+        let mut btc_del = btc_delegations().load(storage, &staking_tx_hash)?;
+        // Unbonded early or slashed delegations have already been discounted
+        if !btc_del.is_active(None) {
+            continue;
+        }
+
+        // Mark the delegation as expired, so that it cannot become active again
+        btc_del.expired = true;
+        btc_delegations().save(storage, &staking_tx_hash, &btc_del)?;
+
+        // Discount the voting power from the affected finality providers
+        let affected_fps = DELEGATION_FPS.load(storage, &staking_tx_hash)?;
+        let discounted = discount_power(storage, height, &affected_fps, btc_del.total_sat)?;
+        update_stats(storage, height, |stats| {
+            stats.active_delegations = stats.active_delegations.saturating_sub(1);
+            stats.unbonded_delegations += 1;
+            stats.total_active_sat = stats.total_active_sat.saturating_sub(btc_del.total_sat);
+            stats.total_power = stats.total_power.saturating_sub(discounted);
+        })?;
+
+        // Record event that the BTC delegation expired at this height
+        let expiry_event = Event::new("btc_delegation_expired")
+            .add_attribute(
+                "staking_tx_hash",
+                Txid::from_byte_array(staking_tx_hash).to_string(),
+            )
+            .add_attribute("end_height", end_height.to_string())
+            .add_attribute("btc_height", btc_tip.height.to_string())
+            .add_attribute("height", height.to_string());
+        events.push(expiry_event);
+    }
+
+    Ok(events)
+}
+
+/// discount_power discounts the given staked amount from the voting power of the affected
+/// finality providers.
+/// Returns the total voting power discounted
+fn discount_power(
+    storage: &mut dyn Storage,
+    height: u64,
+    affected_fps: &[String],
+    total_sat: u64,
+) -> Result<u64, ContractError> {
+    let fps = fps();
+    let mut discounted = 0;
+    for fp in affected_fps {
+        fps.update(storage, fp, height, |fp_state| {
+            let mut fp_state =
+                fp_state.ok_or(ContractError::FinalityProviderNotFound(fp.clone()))?; // should never happen
+            let power = fp_state.power.saturating_sub(total_sat);
+            discounted += fp_state.power - power;
+            fp_state.power = power;
+            Ok::<_, ContractError>(fp_state)
+        })?;
+    }
+    Ok(discounted)
+}
+
+/// update_stats applies the given change to the staking statistics at the given height
+fn update_stats(
+    storage: &mut dyn Storage,
+    height: u64,
+    change: impl FnOnce(&mut StakingStats),
+) -> Result<(), ContractError> {
+    STAKING_STATS.update(storage, height, |stats| {
+        let mut stats = stats.unwrap_or_default();
+        change(&mut stats);
+        Ok::<_, ContractError>(stats)
+    })?;
+    Ok(())
+}
+
 /// btc_undelegate adds the signature of the unbonding tx signed by the staker to the given BTC
 /// delegation
 fn btc_undelegate(
@@ -338,7 +665,7 @@ fn btc_undelegate(
     btc_del.undelegation_info.delegator_unbonding_sig = unbonding_tx_sig.to_vec();
 
     // Set BTC delegation back to KV store
-    DELEGATIONS.save(storage, staking_tx_hash.as_ref(), btc_del)?;
+    btc_delegations().save(storage, staking_tx_hash.as_ref(), btc_del)?;
 
     // TODO? Notify subscriber about this unbonded BTC delegation
     //  - Who are subscribers in this context?
@@ -378,11 +705,17 @@ pub(crate) fn slash_finality_provider(
     // FP set.
     // We simply set the FP voting power to zero from the next *processing* height (See NOTE in
     // `handle_finality_signature`)
+    let mut slashed_power = 0;
     fps().update(deps.storage, fp_btc_pk_hex, env.block.height + 1, |fp| {
         let mut fp = fp.unwrap_or_default();
+        slashed_power = fp.power;
         fp.power = 0;
         Ok::<_, ContractError>(fp)
     })?;
+    update_stats(deps.storage, env.block.height + 1, |stats| {
+        stats.slashed_finality_providers += 1;
+        stats.total_power = stats.total_power.saturating_sub(slashed_power);
+    })?;
 
     // Save the finality provider back
     FPS.save(deps.storage, fp_btc_pk_hex, &fp)?;
@@ -391,6 +724,98 @@ pub(crate) fn slash_finality_provider(
     Ok(Response::new())
 }
 
+/// verify_staking_tx_inclusion verifies the inclusion proof of the staking tx of an active
+/// delegation against the BTC headers of the babylon contract, and ensures the staking tx is k-deep,
+/// with k the BTC confirmation depth of the babylon contract.
+///
+/// The block including the staking tx must be at the start height of the delegation.
+/// The inclusion proof is only mandatory if the params version of the delegation enables
+/// `require_staking_tx_inclusion_proof`
+fn verify_staking_tx_inclusion(
+    deps: Deps,
+    babylon: &Addr,
+    active_delegation: &ActiveBtcDelegation,
+) -> Result<(), ContractError> {
+    let staking_tx: Transaction = deserialize(&active_delegation.staking_tx)
+        .map_err(|_| ContractError::InvalidBtcTx(active_delegation.staking_tx.encode_hex()))?;
+    let staking_tx_hash = staking_tx.txid();
+
+    let Some(inclusion_proof) = &active_delegation.staking_tx_inclusion_proof else {
+        let params = PARAMS
+            .may_load(deps.storage, active_delegation.params_version)?
+            .ok_or(ContractError::ParamsVersionNotFound(
+                active_delegation.params_version,
+            ))?;
+        if params.require_staking_tx_inclusion_proof {
+            return Err(ContractError::MissingStakingTxInclusionProof(
+                staking_tx_hash.to_string(),
+            ));
+        }
+        return Ok(());
+    };
+
+    // Get the BTC header of the block including the staking tx
+    let query_msg = BabylonQueryMsg::BtcHeaderByHash {
+        hash: inclusion_proof.block_hash_hex.clone(),
+    };
+    let header: BtcHeaderResponse = deps.querier.query_wasm_smart(babylon, &query_msg)?;
+    if header.height != active_delegation.start_height {
+        return Err(ContractError::StakingTxHeightMismatch(
+            staking_tx_hash.to_string(),
+            header.height,
+            active_delegation.start_height,
+        ));
+    }
+
+    // Verify the inclusion proof against the Merkle root of the block
+    if inclusion_proof.proof.len() % HASH_SIZE != 0 {
+        return Err(ContractError::InvalidStakingTxInclusionProof(
+            staking_tx_hash.to_string(),
+        ));
+    }
+    let proof: Vec<&[u8]> = inclusion_proof.proof.chunks(HASH_SIZE).collect();
+    // The tx index cannot have more bits than the proof has levels
+    if inclusion_proof
+        .index
+        .checked_shr(proof.len() as u32)
+        .unwrap_or_default()
+        != 0
+    {
+        return Err(ContractError::InvalidStakingTxInclusionProof(
+            staking_tx_hash.to_string(),
+        ));
+    }
+    let merkle_root = TxMerkleNode::from_str(&header.header.merkle_root)?;
+    if !verify_merkle_proof(
+        &staking_tx,
+        &proof,
+        inclusion_proof.index as usize,
+        merkle_root.as_raw_hash(),
+    ) {
+        return Err(ContractError::InvalidStakingTxInclusionProof(
+            staking_tx_hash.to_string(),
+        ));
+    }
+
+    // Ensure the staking tx is k-deep
+    let tip: BtcHeaderResponse = deps
+        .querier
+        .query_wasm_smart(babylon, &BabylonQueryMsg::BtcTipHeader {})?;
+    let babylon_config: BabylonConfig = deps
+        .querier
+        .query_wasm_smart(babylon, &BabylonQueryMsg::Config {})?;
+    let depth = tip.height.saturating_sub(header.height);
+    if depth < babylon_config.btc_confirmation_depth {
+        return Err(ContractError::StakingTxNotKDeep(
+            staking_tx_hash.to_string(),
+            depth,
+            babylon_config.btc_confirmation_depth,
+        ));
+    }
+
+    Ok(())
+}
+
 /// get_btc_tip_height queries the Babylon contract for the latest BTC tip height
 fn get_btc_tip_height(deps: &DepsMut) -> Result<u64, ContractError> {
     // Get the BTC tip from the babylon contract through a raw query
@@ -408,7 +833,13 @@ fn get_btc_tip_height(deps: &DepsMut) -> Result<u64, ContractError> {
 pub(crate) mod tests {
     use super::*;
 
-    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::testing::{
+        message_info, mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_json, ContractResult, OwnedDeps, SystemResult, WasmQuery};
+
+    use babylon_apis::btc_staking_api::InclusionProof;
+    use babylon_contract::msg::btc_header::BtcHeader;
 
     use test_utils::{
         create_new_finality_provider, create_new_fp_sk, get_active_btc_delegation,
@@ -417,9 +848,10 @@ pub(crate) mod tests {
 
     use crate::contract::tests::{CREATOR, INIT_ADMIN};
     use crate::contract::{execute, instantiate};
-    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use crate::msg::{CommissionChange, ExecuteMsg, InstantiateMsg};
     use crate::queries;
-    use crate::state::staking::BtcUndelegationInfo;
+    use crate::state::config::add_params;
+    use crate::state::staking::{BtcUndelegationInfo, StakingStats};
     use crate::test_utils::staking_params;
 
     // Compute staking tx hash of a delegation
@@ -452,6 +884,8 @@ pub(crate) mod tests {
             active_del: vec![],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         // Only the Creator or Admin can call this
@@ -485,6 +919,8 @@ pub(crate) mod tests {
             active_del: vec![],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         // Use admin_info to execute the message
@@ -507,124 +943,1217 @@ pub(crate) mod tests {
     }
 
     #[test]
-    fn active_delegation_happy_path() {
+    fn test_edit_fp() {
+        use babylon_apis::btc_staking_api::FinalityProviderDescription;
+        use babylon_apis::error::StakingApiError;
+        use cosmwasm_std::Decimal;
+
         let mut deps = mock_dependencies();
         let info = message_info(&deps.api.addr_make(CREATOR), &[]);
 
-        let params = staking_params();
         instantiate(
             deps.as_mut(),
             mock_env(),
             info.clone(),
             InstantiateMsg {
-                params: Some(params),
+                params: None,
                 admin: None,
             },
         )
         .unwrap();
 
-        // Build valid active delegation
-        let active_delegation = get_active_btc_delegation();
-
-        // Register one FP first
-        let mut new_fp = create_new_finality_provider(1);
-        new_fp
-            .btc_pk_hex
-            .clone_from(&active_delegation.fp_btc_pk_list[0]);
-
-        // Check that the finality provider has no power yet
-        let res = queries::finality_provider_info(deps.as_ref(), new_fp.btc_pk_hex.clone(), None);
-        assert!(matches!(
-            res,
-            Err(ContractError::FinalityProviderNotFound(pk)) if pk == new_fp.btc_pk_hex
-        ));
-
+        let new_fp = create_new_finality_provider(1);
         let msg = ExecuteMsg::BtcStaking {
             new_fp: vec![new_fp.clone()],
             active_del: vec![],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
-
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        // Now add the active delegation
-        let msg = ExecuteMsg::BtcStaking {
+        let edit_msg = |edit_fp: EditFinalityProvider| ExecuteMsg::BtcStaking {
             new_fp: vec![],
-            active_del: vec![active_delegation.clone()],
+            active_del: vec![],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![edit_fp],
+            best_effort: None,
         };
 
+        // Editing the commission only leaves the description unchanged
+        let msg = edit_msg(EditFinalityProvider {
+            btc_pk_hex: new_fp.btc_pk_hex.clone(),
+            description: None,
+            commission: Some(Decimal::percent(20)),
+        });
         let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        assert_eq!(
+            res.events,
+            vec![Event::new("finality_provider_edited")
+                .add_attribute("btc_pk_hex", &new_fp.btc_pk_hex)
+                .add_attribute("height", mock_env().block.height.to_string())
+                .add_attribute("commission", "0.2")]
+        );
+        let fp = queries::finality_provider(deps.as_ref(), new_fp.btc_pk_hex.clone()).unwrap();
+        assert_eq!(fp.commission, Decimal::percent(20));
+        assert_eq!(fp.description, new_fp.description);
+
+        // Editing the description
+        let description = FinalityProviderDescription {
+            moniker: "new moniker".to_string(),
+            identity: "".to_string(),
+            website: "".to_string(),
+            security_contact: "".to_string(),
+            details: "".to_string(),
+        };
+        let msg = edit_msg(EditFinalityProvider {
+            btc_pk_hex: new_fp.btc_pk_hex.clone(),
+            description: Some(description.clone()),
+            commission: None,
+        });
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let fp = queries::finality_provider(deps.as_ref(), new_fp.btc_pk_hex.clone()).unwrap();
+        assert_eq!(fp.commission, Decimal::percent(20));
+        assert_eq!(fp.description, Some(description));
+
+        // An invalid description is rejected
+        let msg = edit_msg(EditFinalityProvider {
+            btc_pk_hex: new_fp.btc_pk_hex.clone(),
+            description: Some(FinalityProviderDescription {
+                moniker: "".to_string(),
+                identity: "".to_string(),
+                website: "".to_string(),
+                security_contact: "".to_string(),
+                details: "".to_string(),
+            }),
+            commission: None,
+        });
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::StakingError(StakingApiError::DescriptionErr(_))
+        ));
 
-        // Check the active delegation is being stored
-        let delegation = BtcDelegation::from(&active_delegation);
-        let staking_tx_hash_hex = staking_tx_hash(&delegation).to_string();
-        let query_res = queries::delegation(deps.as_ref(), staking_tx_hash_hex).unwrap();
-        assert_eq!(query_res, delegation);
+        // A commission above 100% is rejected
+        let msg = edit_msg(EditFinalityProvider {
+            btc_pk_hex: new_fp.btc_pk_hex.clone(),
+            description: None,
+            commission: Some(Decimal::percent(101)),
+        });
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::StakingError(StakingApiError::InvalidCommission(Decimal::percent(101)))
+        );
 
-        // Check that the finality provider power has been updated
-        let fp = queries::finality_provider_info(deps.as_ref(), new_fp.btc_pk_hex.clone(), None)
-            .unwrap();
-        assert_eq!(fp.power, active_delegation.total_sat);
+        // Unknown finality providers cannot be edited
+        let unknown_fp = create_new_finality_provider(2);
+        let msg = edit_msg(EditFinalityProvider {
+            btc_pk_hex: unknown_fp.btc_pk_hex.clone(),
+            description: None,
+            commission: Some(Decimal::percent(20)),
+        });
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::FinalityProviderNotFound(unknown_fp.btc_pk_hex)
+        );
     }
 
     #[test]
-    fn undelegation_works() {
-        let mut deps = mock_dependencies();
-        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+    fn commission_limits_are_enforced() {
+        use babylon_apis::btc_staking_api::Params;
+        use cosmwasm_std::Decimal;
 
-        let params = staking_params();
-        instantiate(
+        let new_fp = create_new_finality_provider(1);
+        let commission = new_fp.commission;
+
+        let setup = |params: Params| {
+            let mut deps = mock_dependencies();
+            let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+            instantiate(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                InstantiateMsg {
+                    params: Some(params),
+                    admin: None,
+                },
+            )
+            .unwrap();
+            deps
+        };
+        let msg = |new_fp: Vec<NewFinalityProvider>, edit_fp: Vec<EditFinalityProvider>| {
+            ExecuteMsg::BtcStaking {
+                new_fp,
+                active_del: vec![],
+                slashed_del: vec![],
+                unbonded_del: vec![],
+                edit_fp,
+                best_effort: None,
+            }
+        };
+        let edit = |commission: Decimal| EditFinalityProvider {
+            btc_pk_hex: new_fp.btc_pk_hex.clone(),
+            description: None,
+            commission: Some(commission),
+        };
+
+        // A finality provider below the min commission rate cannot register
+        let min_commission_rate = commission + Decimal::percent(1);
+        let mut deps = setup(Params {
+            min_commission_rate,
+            ..staking_params()
+        });
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+        let err = execute(
             deps.as_mut(),
             mock_env(),
+            info,
+            msg(vec![new_fp.clone()], vec![]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::CommissionTooLow(commission, min_commission_rate)
+        );
+
+        let mut deps = setup(Params {
+            min_commission_rate: commission,
+            max_commission_change_rate: Decimal::percent(5),
+            ..staking_params()
+        });
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+        let mut env = mock_env();
+        execute(
+            deps.as_mut(),
+            env.clone(),
             info.clone(),
-            InstantiateMsg {
-                params: Some(params),
-                admin: None,
-            },
+            msg(vec![new_fp.clone()], vec![]),
         )
         .unwrap();
 
-        // Register one FP first
-        let new_fp = create_new_finality_provider(1);
+        // The commission rate cannot drop below the min commission rate
+        if !commission.is_zero() {
+            let lower = commission - Decimal::permille(1);
+            let err = execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                msg(vec![], vec![edit(lower)]),
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::CommissionTooLow(lower, commission));
+        }
 
-        // Build valid active delegation
-        let active_delegation = get_derived_btc_delegation(1, &[1]);
+        // The commission rate cannot change more than the max commission change rate
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            msg(vec![], vec![edit(commission + Decimal::percent(6))]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::CommissionChangeTooLarge(Decimal::percent(6), Decimal::percent(5))
+        );
 
-        let msg = ExecuteMsg::BtcStaking {
-            new_fp: vec![new_fp.clone()],
-            active_del: vec![active_delegation.clone()],
+        // A change within the limits is accepted, and recorded in the history
+        env.block.height += 1;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            msg(vec![], vec![edit(commission + Decimal::percent(5))]),
+        )
+        .unwrap();
+
+        let history = queries::finality_provider_commission_history(
+            deps.as_ref(),
+            new_fp.btc_pk_hex.clone(),
+            None,
+            None,
+        )
+        .unwrap()
+        .history;
+        assert_eq!(
+            history,
+            vec![
+                CommissionChange {
+                    height: env.block.height - 1,
+                    commission,
+                },
+                CommissionChange {
+                    height: env.block.height,
+                    commission: commission + Decimal::percent(5),
+                },
+            ]
+        );
+
+        // Pagination by height
+        let history = queries::finality_provider_commission_history(
+            deps.as_ref(),
+            new_fp.btc_pk_hex.clone(),
+            Some(env.block.height - 1),
+            None,
+        )
+        .unwrap()
+        .history;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].height, env.block.height);
+    }
+
+    #[test]
+    fn active_delegation_happy_path() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        let params = staking_params();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(params),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        // Build valid active delegation
+        let active_delegation = get_active_btc_delegation();
+
+        // Register one FP first
+        let mut new_fp = create_new_finality_provider(1);
+        new_fp
+            .btc_pk_hex
+            .clone_from(&active_delegation.fp_btc_pk_list[0]);
+
+        // Check that the finality provider has no power yet
+        let res = queries::finality_provider_info(deps.as_ref(), new_fp.btc_pk_hex.clone(), None);
+        assert!(matches!(
+            res,
+            Err(ContractError::FinalityProviderNotFound(pk)) if pk == new_fp.btc_pk_hex
+        ));
+
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp.clone()],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // Now add the active delegation
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![],
+            active_del: vec![active_delegation.clone()],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // Check the active delegation is being stored
+        let delegation = BtcDelegation::from(&active_delegation);
+        let staking_tx_hash_hex = staking_tx_hash(&delegation).to_string();
+        let query_res = queries::delegation(deps.as_ref(), staking_tx_hash_hex).unwrap();
+        assert_eq!(query_res, delegation);
+
+        // Check that the finality provider power has been updated
+        let fp = queries::finality_provider_info(deps.as_ref(), new_fp.btc_pk_hex.clone(), None)
+            .unwrap();
+        assert_eq!(fp.power, active_delegation.total_sat);
+    }
+
+    #[test]
+    fn expired_delegation_is_rejected() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(staking_params()),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        let active_delegation = get_derived_btc_delegation(1, &[1]);
+
+        // Only the babylon contract or the admin can update the BTC tip
+        let msg = ExecuteMsg::BtcTipUpdate {
+            height: active_delegation.end_height,
+            checkpoint_finalization_timeout: 1,
+        };
+        let other_info = message_info(&deps.api.addr_make("other"), &[]);
+        let err = execute(deps.as_mut(), mock_env(), other_info, msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized);
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // The delegation has less than `w` BTC blocks left in its time-lock
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![create_new_finality_provider(1)],
+            active_del: vec![active_delegation.clone()],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        let staking_tx_hash_hex =
+            staking_tx_hash(&BtcDelegation::from(&active_delegation)).to_string();
+        assert_eq!(
+            err,
+            ContractError::DelegationExpired(staking_tx_hash_hex, active_delegation.end_height)
+        );
+    }
+
+    #[test]
+    fn not_started_delegation_is_rejected() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(staking_params()),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        let active_delegation = get_derived_btc_delegation(1, &[1]);
+        let msg = ExecuteMsg::BtcTipUpdate {
+            height: active_delegation.start_height - 1,
+            checkpoint_finalization_timeout: 1,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // The time-lock of the delegation has not begun at the BTC tip
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![create_new_finality_provider(1)],
+            active_del: vec![active_delegation.clone()],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        let staking_tx_hash_hex =
+            staking_tx_hash(&BtcDelegation::from(&active_delegation)).to_string();
+        assert_eq!(
+            err,
+            ContractError::DelegationNotStarted(
+                staking_tx_hash_hex,
+                active_delegation.start_height,
+                active_delegation.start_height - 1
+            )
+        );
+
+        // No power or stats have been added
+        let stats = queries::staking_stats(deps.as_ref(), None, None).unwrap();
+        assert_eq!(stats.stats.active_delegations, 0);
+        assert_eq!(stats.stats.total_power, 0);
+
+        // Once the BTC tip reaches the start height, the delegation is accepted
+        let tip_msg = ExecuteMsg::BtcTipUpdate {
+            height: active_delegation.start_height,
+            checkpoint_finalization_timeout: 1,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), tip_msg).unwrap();
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![],
+            active_del: vec![active_delegation],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn delegation_fps_are_limited() {
+        use babylon_apis::btc_staking_api::Params;
+
+        let active_delegation = get_derived_btc_delegation(1, &[1, 3]);
+        let add_delegation =
+            |params: Params, new_fp: Vec<NewFinalityProvider>, slashed: &[&str]| {
+                let mut deps = mock_dependencies();
+                let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+                instantiate(
+                    deps.as_mut(),
+                    mock_env(),
+                    info.clone(),
+                    InstantiateMsg {
+                        params: Some(params),
+                        admin: None,
+                    },
+                )
+                .unwrap();
+                let msg = ExecuteMsg::BtcStaking {
+                    new_fp,
+                    active_del: vec![],
+                    slashed_del: vec![],
+                    unbonded_del: vec![],
+                    edit_fp: vec![],
+                    best_effort: None,
+                };
+                execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+                for fp_btc_pk_hex in slashed {
+                    let msg = ExecuteMsg::Slash {
+                        fp_btc_pk_hex: fp_btc_pk_hex.to_string(),
+                    };
+                    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+                }
+                let msg = ExecuteMsg::BtcStaking {
+                    new_fp: vec![],
+                    active_del: vec![active_delegation.clone()],
+                    slashed_del: vec![],
+                    unbonded_del: vec![],
+                    edit_fp: vec![],
+                    best_effort: None,
+                };
+                execute(deps.as_mut(), mock_env(), info, msg).map(|_| deps)
+            };
+
+        // The delegation restakes to more finality providers than allowed
+        let params = Params {
+            max_fps_per_delegation: 1,
+            ..staking_params()
+        };
+        let err = add_delegation(params, vec![create_new_finality_provider(1)], &[])
+            .err()
+            .unwrap();
+        assert_eq!(err, ContractError::TooManyFinalityProviders(2, 1));
+
+        // The delegation restakes to two finality providers of the same Consumer
+        let new_fp1 = create_new_finality_provider(1);
+        let new_fp3 = create_new_finality_provider(3);
+        let same_consumer_fp3 = NewFinalityProvider {
+            consumer_id: new_fp1.consumer_id.clone(),
+            ..new_fp3.clone()
+        };
+        let err = add_delegation(
+            staking_params(),
+            vec![new_fp1.clone(), same_consumer_fp3.clone()],
+            &[],
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            err,
+            ContractError::DuplicatedConsumerFinalityProvider(new_fp1.consumer_id.clone())
+        );
+
+        // A slashed finality provider gets no voting power, so it doesn't count towards its
+        // Consumer
+        let deps = add_delegation(
+            staking_params(),
+            vec![new_fp1.clone(), same_consumer_fp3],
+            &[&new_fp1.btc_pk_hex],
+        )
+        .ok()
+        .unwrap();
+        let fp = queries::finality_provider_info(deps.as_ref(), new_fp1.btc_pk_hex.clone(), None)
+            .unwrap();
+        assert_eq!(fp.power, 0);
+        let fp = queries::finality_provider_info(deps.as_ref(), new_fp3.btc_pk_hex.clone(), None)
+            .unwrap();
+        assert_eq!(fp.power, active_delegation.total_sat);
+
+        // The delegation restakes to finality providers of different Consumers, each of which
+        // gets the full staked amount as voting power
+        let deps = add_delegation(
+            staking_params(),
+            vec![new_fp1.clone(), new_fp3.clone()],
+            &[],
+        )
+        .ok()
+        .unwrap();
+        for btc_pk_hex in [new_fp1.btc_pk_hex, new_fp3.btc_pk_hex] {
+            let fp = queries::finality_provider_info(deps.as_ref(), btc_pk_hex, None).unwrap();
+            assert_eq!(fp.power, active_delegation.total_sat);
+        }
+    }
+
+    #[test]
+    fn staking_stats_are_tracked() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(staking_params()),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        // Register a couple finality providers, of different Consumers
+        let new_fp1 = create_new_finality_provider(1);
+        let new_fp3 = create_new_finality_provider(3);
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp1.clone(), new_fp3.clone()],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // Add a delegation restaked to both finality providers
+        let active_delegation = get_derived_btc_delegation(1, &[1, 3]);
+        let total_sat = active_delegation.total_sat;
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![],
+            active_del: vec![active_delegation],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // The delegation is counted once, and its power once per finality provider
+        let res = queries::staking_stats(deps.as_ref(), None, Some(1)).unwrap();
+        assert_eq!(
+            res.stats,
+            StakingStats {
+                total_active_sat: total_sat,
+                total_power: 2 * total_sat,
+                active_delegations: 1,
+                finality_providers: 2,
+                ..Default::default()
+            }
+        );
+        assert_eq!(res.top_n, 1);
+        assert_eq!(res.top_n_power, total_sat);
+        // The number of aggregated finality providers is capped
+        let res = queries::staking_stats(deps.as_ref(), None, Some(u32::MAX)).unwrap();
+        assert_eq!(res.top_n, 30);
+        assert_eq!(res.top_n_power, 2 * total_sat);
+
+        // Slash a finality provider. Its power is discounted from the next height onwards
+        let env = mock_env();
+        slash_finality_provider(deps.as_mut(), env.clone(), &new_fp1.btc_pk_hex).unwrap();
+
+        let res = queries::staking_stats(deps.as_ref(), None, None).unwrap();
+        assert_eq!(res.stats.slashed_finality_providers, 1);
+        assert_eq!(res.stats.total_power, total_sat);
+        assert_eq!(res.stats.active_delegations, 1);
+        assert_eq!(res.top_n_power, total_sat);
+
+        // The statistics before the slashing are kept
+        let res = queries::staking_stats(deps.as_ref(), Some(env.block.height + 1), None).unwrap();
+        assert_eq!(res.stats.slashed_finality_providers, 0);
+        assert_eq!(res.stats.total_power, 2 * total_sat);
+        assert_eq!(res.top_n_power, 2 * total_sat);
+        let res =
+            queries::staking_stats(deps.as_ref(), Some(env.block.height + 1), Some(1)).unwrap();
+        assert_eq!(res.top_n_power, total_sat);
+        let res = queries::staking_stats(deps.as_ref(), Some(env.block.height + 2), None).unwrap();
+        assert_eq!(res.stats.slashed_finality_providers, 1);
+        assert_eq!(res.stats.total_power, total_sat);
+        assert_eq!(res.top_n_power, total_sat);
+    }
+
+    #[test]
+    fn delegations_expire_with_btc_tip() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(staking_params()),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        let new_fp = create_new_finality_provider(1);
+        let active_delegation = get_derived_btc_delegation(1, &[1]);
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp.clone()],
+            active_del: vec![active_delegation.clone()],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let w = 2;
+        let tip_update = |btc_height| ExecuteMsg::BtcTipUpdate {
+            height: btc_height,
+            checkpoint_finalization_timeout: w,
+        };
+
+        // The delegation still has `w` BTC blocks left in its time-lock
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            tip_update(active_delegation.end_height - w),
+        )
+        .unwrap();
+        assert!(res.events.is_empty());
+        let fp = queries::finality_provider_info(deps.as_ref(), new_fp.btc_pk_hex.clone(), None)
+            .unwrap();
+        assert_eq!(fp.power, active_delegation.total_sat);
+
+        // The delegation expires, and its power is discounted from the next height onwards
+        let mut env = mock_env();
+        env.block.height += 1;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            tip_update(active_delegation.end_height),
+        )
+        .unwrap();
+        let staking_tx_hash_hex =
+            staking_tx_hash(&BtcDelegation::from(&active_delegation)).to_string();
+        assert_eq!(
+            res.events,
+            vec![Event::new("btc_delegation_expired")
+                .add_attribute("staking_tx_hash", staking_tx_hash_hex.clone())
+                .add_attribute("end_height", active_delegation.end_height.to_string())
+                .add_attribute("btc_height", active_delegation.end_height.to_string())
+                .add_attribute("height", env.block.height.to_string())]
+        );
+        let fp = queries::finality_provider_info(
+            deps.as_ref(),
+            new_fp.btc_pk_hex.clone(),
+            Some(env.block.height + 1),
+        )
+        .unwrap();
+        assert_eq!(fp.power, 0);
+        // Power before expiry is kept
+        let fp = queries::finality_provider_info(
+            deps.as_ref(),
+            new_fp.btc_pk_hex.clone(),
+            Some(env.block.height),
+        )
+        .unwrap();
+        assert_eq!(fp.power, active_delegation.total_sat);
+
+        // The expired delegation is counted as unbonded
+        let stats = queries::staking_stats(deps.as_ref(), None, None)
+            .unwrap()
+            .stats;
+        assert_eq!(stats.active_delegations, 0);
+        assert_eq!(stats.unbonded_delegations, 1);
+        assert_eq!(stats.total_active_sat, 0);
+        assert_eq!(stats.total_power, 0);
+
+        // Delegations are expired only once
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            tip_update(active_delegation.end_height + 1),
+        )
+        .unwrap();
+        assert!(res.events.is_empty());
+
+        // Expired delegations stay expired if the BTC tip goes back (e.g. due to a reorg)
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            tip_update(active_delegation.end_height - w),
+        )
+        .unwrap();
+        let btc_del = queries::delegation(deps.as_ref(), staking_tx_hash_hex.clone()).unwrap();
+        assert!(btc_del.expired);
+        let btc_tip = BTC_TIP.load(&deps.storage).unwrap();
+        assert!(!btc_del.is_active(Some(&btc_tip)));
+
+        // Expired delegations cannot be unbonded
+        let unbonding_sig = get_btc_del_unbonding_sig(1, &[1]);
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![UnbondedBtcDelegation {
+                staking_tx_hash: staking_tx_hash_hex.clone(),
+                unbonding_tx_sig: unbonding_sig.to_bytes().into(),
+            }],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::DelegationIsNotActive(staking_tx_hash_hex)
+        );
+    }
+
+    #[test]
+    fn delegation_expiries_are_bounded() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(staking_params()),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        let del1 = get_derived_btc_delegation(1, &[1]);
+        let del2 = get_derived_btc_delegation(2, &[1]);
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![create_new_finality_provider(1)],
+            active_del: vec![del1.clone(), del2.clone()],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Both delegations are expired at this BTC tip, but only one is processed per call
+        let btc_tip = BtcTipInfo {
+            height: del1.end_height.max(del2.end_height),
+            checkpoint_finalization_timeout: 1,
+        };
+        let height = mock_env().block.height;
+        let events = expire_delegations(deps.as_mut().storage, height, &btc_tip, 1).unwrap();
+        assert_eq!(events.len(), 1);
+        // The rest is carried over to the next call
+        let events = expire_delegations(deps.as_mut().storage, height, &btc_tip, 1).unwrap();
+        assert_eq!(events.len(), 1);
+        let events = expire_delegations(deps.as_mut().storage, height, &btc_tip, 1).unwrap();
+        assert!(events.is_empty());
+
+        for del in [del1, del2] {
+            let staking_tx_hash_hex = staking_tx_hash(&BtcDelegation::from(&del)).to_string();
+            let btc_del = queries::delegation(deps.as_ref(), staking_tx_hash_hex).unwrap();
+            assert!(btc_del.expired);
+        }
+    }
+
+    // Mock the BTC header queries of the babylon contract, with a block including the staking tx
+    // at `block_height`, and the BTC tip at `tip_height`
+    fn mock_babylon_btc_headers(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        merkle_root: String,
+        block_height: u64,
+        tip_height: u64,
+        btc_confirmation_depth: u64,
+    ) {
+        let header = |height| BtcHeaderResponse {
+            header: BtcHeader {
+                version: 4,
+                prev_blockhash: "00".repeat(32),
+                merkle_root: merkle_root.clone(),
+                time: 0,
+                bits: 0,
+                nonce: 0,
+            },
+            hash: "11".repeat(32),
+            height,
+            cum_work: Default::default(),
+        };
+        let block = header(block_height);
+        let tip = header(tip_height);
+        let config = BabylonConfig {
+            network: babylon_bitcoin::chain_params::Network::Regtest,
+            babylon_tag: vec![0x1, 0x2, 0x3, 0x4],
+            btc_confirmation_depth,
+            checkpoint_finalization_timeout: 1,
+            notify_cosmos_zone: false,
+            btc_staking: None,
+            btc_finality: None,
+            consumer_name: None,
+            consumer_description: None,
+            signet_challenge: None,
+        };
+        deps.querier.update_wasm(move |query| {
+            let WasmQuery::Smart { msg, .. } = query else {
+                unreachable!("unexpected query")
+            };
+            let res = match from_json(msg).unwrap() {
+                BabylonQueryMsg::BtcHeaderByHash { .. } => to_json_binary(&block),
+                BabylonQueryMsg::BtcTipHeader {} => to_json_binary(&tip),
+                BabylonQueryMsg::Config {} => to_json_binary(&config),
+                _ => unreachable!("unexpected query"),
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        });
+    }
+
+    #[test]
+    fn staking_tx_inclusion_is_verified() {
+        use babylon_apis::btc_staking_api::Params;
+
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(Params {
+                    require_staking_tx_inclusion_proof: true,
+                    ..staking_params()
+                }),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        let new_fp = create_new_finality_provider(1);
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // The staking tx is the second tx of a two-tx block
+        let mut active_delegation = get_derived_btc_delegation(1, &[1]);
+        let staking_tx: Transaction = deserialize(&active_delegation.staking_tx).unwrap();
+        let sibling = [7u8; HASH_SIZE];
+        let merkle_root = babylon_bitcoin::merkle::compute_merkle_root(&staking_tx, &[&sibling], 1);
+        let merkle_root = TxMerkleNode::from_raw_hash(merkle_root).to_string();
+        active_delegation.staking_tx_inclusion_proof = Some(InclusionProof {
+            block_hash_hex: "11".repeat(32),
+            index: 1,
+            proof: sibling.to_vec().into(),
+        });
+        let msg = |del: &ActiveBtcDelegation| ExecuteMsg::BtcStaking {
+            new_fp: vec![],
+            active_del: vec![del.clone()],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        let staking_tx_hash_hex = staking_tx.txid().to_string();
+
+        // The inclusion proof is required
+        let mut unproven_delegation = active_delegation.clone();
+        unproven_delegation.staking_tx_inclusion_proof = None;
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            msg(&unproven_delegation),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MissingStakingTxInclusionProof(staking_tx_hash_hex.clone())
+        );
+
+        // The block must be at the start height of the delegation
+        let start_height = active_delegation.start_height;
+        mock_babylon_btc_headers(
+            &mut deps,
+            merkle_root.clone(),
+            start_height - 1,
+            start_height + 6,
+            6,
+        );
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            msg(&active_delegation),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::StakingTxHeightMismatch(
+                staking_tx_hash_hex.clone(),
+                start_height - 1,
+                start_height
+            )
+        );
+
+        // The staking tx is not k-deep yet
+        mock_babylon_btc_headers(
+            &mut deps,
+            merkle_root.clone(),
+            start_height,
+            start_height + 5,
+            6,
+        );
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            msg(&active_delegation),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::StakingTxNotKDeep(staking_tx_hash_hex.clone(), 5, 6)
+        );
+
+        // The inclusion proof does not match the block
+        mock_babylon_btc_headers(&mut deps, merkle_root, start_height, start_height + 6, 6);
+        let mut invalid_delegation = active_delegation.clone();
+        invalid_delegation
+            .staking_tx_inclusion_proof
+            .as_mut()
+            .unwrap()
+            .index = 0;
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            msg(&invalid_delegation),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidStakingTxInclusionProof(staking_tx_hash_hex.clone())
+        );
+
+        // The staking tx is included and k-deep
+        execute(deps.as_mut(), mock_env(), info, msg(&active_delegation)).unwrap();
+        queries::delegation(deps.as_ref(), staking_tx_hash_hex).unwrap();
+    }
+
+    #[test]
+    fn delegation_params_version_is_checked() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        let params = staking_params();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(params.clone()),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        // Add a second params version, applying after the start of the delegation
+        let mut active_delegation = get_derived_btc_delegation(1, &[1]);
+        let activation_height = active_delegation.start_height + 1;
+        add_params(deps.as_mut().storage, 1, &params, activation_height).unwrap();
+
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![create_new_finality_provider(1)],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = |del: &ActiveBtcDelegation| ExecuteMsg::BtcStaking {
+            new_fp: vec![],
+            active_del: vec![del.clone()],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+
+        // Unknown params version
+        active_delegation.params_version = 2;
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            msg(&active_delegation),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ParamsVersionNotFound(2));
+
+        // The params version does not apply to the start height of the delegation yet
+        active_delegation.params_version = 1;
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            msg(&active_delegation),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidParamsVersion(1, active_delegation.start_height)
+        );
+
+        // The params version applying to the start height of the delegation
+        active_delegation.params_version = 0;
+        execute(deps.as_mut(), mock_env(), info, msg(&active_delegation)).unwrap();
+    }
+
+    #[test]
+    fn best_effort_skips_invalid_items() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        let params = staking_params();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(params),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        // The same FP twice, and the same delegation twice
+        let active_delegation = get_active_btc_delegation();
+        let mut new_fp = create_new_finality_provider(1);
+        new_fp
+            .btc_pk_hex
+            .clone_from(&active_delegation.fp_btc_pk_list[0]);
+        let msg = |best_effort| ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp.clone(), new_fp.clone()],
+            active_del: vec![active_delegation.clone(), active_delegation.clone()],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort,
+        };
+
+        // In best-effort mode, invalid items are skipped and reported
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg(Some(true))).unwrap();
+        let data: BtcStakingResponse = from_json(res.data.unwrap()).unwrap();
+        let staking_tx_hash_hex =
+            staking_tx_hash(&BtcDelegation::from(&active_delegation)).to_string();
+        assert_eq!(
+            data.results,
+            vec![
+                BtcStakingItemResult {
+                    kind: BtcStakingItemKind::NewFp,
+                    id: new_fp.btc_pk_hex.clone(),
+                    error: None,
+                },
+                BtcStakingItemResult {
+                    kind: BtcStakingItemKind::NewFp,
+                    id: new_fp.btc_pk_hex.clone(),
+                    error: Some(
+                        ContractError::FinalityProviderAlreadyExists(new_fp.btc_pk_hex.clone())
+                            .to_string()
+                    ),
+                },
+                BtcStakingItemResult {
+                    kind: BtcStakingItemKind::ActiveDel,
+                    id: staking_tx_hash_hex.clone(),
+                    error: None,
+                },
+                BtcStakingItemResult {
+                    kind: BtcStakingItemKind::ActiveDel,
+                    id: staking_tx_hash_hex.clone(),
+                    error: Some(
+                        ContractError::DelegationAlreadyExists(staking_tx_hash_hex.clone())
+                            .to_string()
+                    ),
+                },
+            ]
+        );
+        assert_eq!(
+            2,
+            res.events
+                .iter()
+                .filter(|ev| ev.ty == "btc_staking_item_failed")
+                .count()
+        );
+
+        // Valid items are applied, invalid ones leave no state behind
+        queries::delegation(deps.as_ref(), staking_tx_hash_hex).unwrap();
+        let fp = queries::finality_provider_info(deps.as_ref(), new_fp.btc_pk_hex.clone(), None)
+            .unwrap();
+        assert_eq!(fp.power, active_delegation.total_sat);
+
+        // By default, invalid items fail the whole message
+        let err = execute(deps.as_mut(), mock_env(), info, msg(None)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::FinalityProviderAlreadyExists(new_fp.btc_pk_hex.clone())
+        );
+    }
+
+    #[test]
+    fn undelegation_works() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        let params = staking_params();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(params),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        // Register one FP first
+        let new_fp = create_new_finality_provider(1);
+
+        // Build valid active delegation
+        let active_delegation = get_derived_btc_delegation(1, &[1]);
+
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp.clone()],
+            active_del: vec![active_delegation.clone()],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
         assert_eq!(0, res.messages.len());
 
-        // Check the delegation is active (it has no unbonding or slashing tx signature)
-        let active_delegation_undelegation = active_delegation.undelegation_info.clone();
+        // Check the delegation is active (it has no unbonding tx signature)
         // Compute the staking tx hash
         let delegation = BtcDelegation::from(&active_delegation);
         let staking_tx_hash_hex = staking_tx_hash(&delegation).to_string();
 
         let btc_del = queries::delegation(deps.as_ref(), staking_tx_hash_hex.clone()).unwrap();
         let btc_undelegation = btc_del.undelegation_info;
-        assert_eq!(
-            btc_undelegation,
-            BtcUndelegationInfo {
-                unbonding_tx: active_delegation_undelegation.unbonding_tx.to_vec(),
-                slashing_tx: active_delegation_undelegation.slashing_tx.to_vec(),
-                delegator_unbonding_sig: vec![],
-                delegator_slashing_sig: active_delegation_undelegation
-                    .delegator_slashing_sig
-                    .to_vec(),
-                covenant_unbonding_sig_list: vec![],
-                covenant_slashing_sigs: vec![],
-            }
-        );
+        assert!(btc_undelegation.delegator_unbonding_sig.is_empty());
+        assert_eq!(btc_undelegation, delegation.undelegation_info);
 
         let unbonding_sig = get_btc_del_unbonding_sig(1, &[1]);
 
@@ -639,26 +2168,21 @@ pub(crate) mod tests {
             active_del: vec![],
             slashed_del: vec![],
             unbonded_del: vec![undelegation.clone()],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
         assert_eq!(0, res.messages.len());
 
         // Check the delegation is not active any more (updated with the unbonding tx signature)
-        let active_delegation_undelegation = active_delegation.undelegation_info;
         let btc_del = queries::delegation(deps.as_ref(), staking_tx_hash_hex).unwrap();
         let btc_undelegation = btc_del.undelegation_info;
         assert_eq!(
             btc_undelegation,
             BtcUndelegationInfo {
-                unbonding_tx: active_delegation_undelegation.unbonding_tx.into(),
-                slashing_tx: active_delegation_undelegation.slashing_tx.into(),
                 delegator_unbonding_sig: unbonding_sig.to_bytes().into(),
-                delegator_slashing_sig: active_delegation_undelegation
-                    .delegator_slashing_sig
-                    .into(),
-                covenant_unbonding_sig_list: vec![],
-                covenant_slashing_sigs: vec![],
+                ..delegation.undelegation_info
             }
         );
 
@@ -666,6 +2190,19 @@ pub(crate) mod tests {
         let fp = queries::finality_provider_info(deps.as_ref(), new_fp.btc_pk_hex.clone(), None)
             .unwrap();
         assert_eq!(fp.power, 0);
+
+        // Check the staking statistics have been updated
+        let stats = queries::staking_stats(deps.as_ref(), None, None)
+            .unwrap()
+            .stats;
+        assert_eq!(
+            stats,
+            StakingStats {
+                unbonded_delegations: 1,
+                finality_providers: 1,
+                ..Default::default()
+            }
+        );
     }
 
     #[test]
@@ -696,6 +2233,8 @@ pub(crate) mod tests {
             active_del: vec![active_delegation.clone()],
             slashed_del: vec![],
             unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -728,6 +2267,8 @@ pub(crate) mod tests {
             active_del: vec![],
             unbonded_del: vec![],
             slashed_del: vec![slashed.clone()],
+            edit_fp: vec![],
+            best_effort: None,
         };
 
         let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -754,5 +2295,547 @@ pub(crate) mod tests {
         let fp = queries::finality_provider_info(deps.as_ref(), new_fp.btc_pk_hex.clone(), None)
             .unwrap();
         assert_eq!(fp.power, 0);
+
+        // Check the staking statistics have been updated
+        let stats = queries::staking_stats(deps.as_ref(), None, None)
+            .unwrap()
+            .stats;
+        assert_eq!(
+            stats,
+            StakingStats {
+                slashed_delegations: 1,
+                finality_providers: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    /// Build a BIP-322 PoP over the given FP address, signed by a P2WPKH address of the key
+    fn bip322_pop(
+        fp_addr: &str,
+        sk: &k256::schnorr::SigningKey,
+    ) -> babylon_apis::btc_staking_api::ProofOfPossessionBtc {
+        use babylon_bitcoin::bip322;
+        use bitcoin::consensus::serialize;
+        use bitcoin::hashes::hash160;
+        use bitcoin::sighash::{EcdsaSighashType, SighashCache};
+        use bitcoin::{Address, Amount, Network, ScriptBuf, WPubkeyHash, Witness};
+        use k256::ecdsa::signature::hazmat::PrehashSigner;
+        use k256::sha2::{Digest, Sha256};
+        use prost::Message;
+
+        let address = babylon_apis::new_canonical_addr(fp_addr, "bbn").unwrap();
+        let msg_hash: [u8; 32] = Sha256::new_with_prefix(address.as_slice())
+            .finalize()
+            .into();
+
+        let ecdsa_sk = k256::ecdsa::SigningKey::from_bytes(&sk.to_bytes()).unwrap();
+        let pk = ecdsa_sk.verifying_key().to_encoded_point(true);
+        let pk_hash = WPubkeyHash::from_raw_hash(hash160::Hash::hash(pk.as_bytes()));
+        let script_pubkey = ScriptBuf::new_p2wpkh(&pk_hash);
+        let btc_address = Address::from_script(&script_pubkey, Network::Regtest).unwrap();
+
+        let to_spend = bip322::to_spend(&msg_hash, &script_pubkey);
+        let to_sign = bip322::to_sign(&to_spend, Witness::new());
+        let sighash = SighashCache::new(&to_sign)
+            .p2wpkh_signature_hash(0, &script_pubkey, Amount::ZERO, EcdsaSighashType::All)
+            .unwrap();
+        let sig: k256::ecdsa::Signature = ecdsa_sk.sign_prehash(sighash.as_byte_array()).unwrap();
+        let mut sig_bytes = sig.to_der().as_bytes().to_vec();
+        sig_bytes.push(EcdsaSighashType::All as u8);
+        let witness = Witness::from_slice(&[sig_bytes, pk.as_bytes().to_vec()]);
+
+        let bip322_sig = babylon_proto::babylon::btcstaking::v1::Bip322Sig {
+            address: btc_address.to_string(),
+            sig: serialize(&witness).into(),
+        };
+        babylon_apis::btc_staking_api::ProofOfPossessionBtc {
+            btc_sig_type: babylon_apis::btc_staking_api::BTCSigType::BIP322 as i32,
+            btc_sig: bip322_sig.encode_to_vec().into(),
+        }
+    }
+
+    #[test]
+    fn test_add_fp_with_bip322_pop() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(staking_params()),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        let mut new_fp = create_new_finality_provider(1);
+        let fp_sk = create_new_fp_sk(1);
+
+        // A PoP signed by another key is rejected
+        let other_fp = create_new_finality_provider(2);
+        new_fp.pop = Some(bip322_pop(&other_fp.addr, &create_new_fp_sk(2)));
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp.clone()],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Bitcoin(_)));
+
+        // A PoP over another address is rejected
+        new_fp.pop = Some(bip322_pop(&other_fp.addr, &fp_sk));
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp.clone()],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Bitcoin(_)));
+
+        // A valid BIP-322 PoP is accepted
+        new_fp.pop = Some(bip322_pop(&new_fp.addr, &fp_sk));
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp.clone()],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let fp = queries::finality_provider(deps.as_ref(), new_fp.btc_pk_hex.clone()).unwrap();
+        assert_eq!(fp.pop, new_fp.pop);
+    }
+
+    /// Build an ECDSA (Bitcoin signed message) PoP over the given FP address
+    fn ecdsa_pop(
+        fp_addr: &str,
+        sk: &k256::schnorr::SigningKey,
+    ) -> babylon_apis::btc_staking_api::ProofOfPossessionBtc {
+        use bitcoin::sign_message::signed_msg_hash;
+        use k256::sha2::{Digest, Sha256};
+
+        let address = babylon_apis::new_canonical_addr(fp_addr, "bbn").unwrap();
+        let msg_hash: [u8; 32] = Sha256::new_with_prefix(address.as_slice())
+            .finalize()
+            .into();
+
+        let ecdsa_sk = k256::ecdsa::SigningKey::from_bytes(&sk.to_bytes()).unwrap();
+        let (sig, recovery_id) = ecdsa_sk
+            .sign_prehash_recoverable(signed_msg_hash(&hex::encode(msg_hash)).as_byte_array())
+            .unwrap();
+        // compressed P2PKH header
+        let mut sig_bytes = vec![31 + recovery_id.to_byte()];
+        sig_bytes.extend_from_slice(&sig.to_bytes());
+
+        babylon_apis::btc_staking_api::ProofOfPossessionBtc {
+            btc_sig_type: babylon_apis::btc_staking_api::BTCSigType::ECDSA as i32,
+            btc_sig: sig_bytes.into(),
+        }
+    }
+
+    #[test]
+    fn test_add_fp_with_ecdsa_pop() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: None,
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        let mut new_fp = create_new_finality_provider(1);
+        let fp_sk = create_new_fp_sk(1);
+        let other_fp = create_new_finality_provider(2);
+
+        // A PoP signed by another key is rejected
+        new_fp.pop = Some(ecdsa_pop(&new_fp.addr, &create_new_fp_sk(2)));
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp.clone()],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Bitcoin(_)));
+
+        // A PoP over another address is rejected
+        new_fp.pop = Some(ecdsa_pop(&other_fp.addr, &fp_sk));
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp.clone()],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Bitcoin(_)));
+
+        // A valid ECDSA PoP is accepted
+        new_fp.pop = Some(ecdsa_pop(&new_fp.addr, &fp_sk));
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp.clone()],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn staking_output_is_verified() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(staking_params()),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        let active_delegation = get_active_btc_delegation();
+        let mut new_fp = create_new_finality_provider(1);
+        new_fp
+            .btc_pk_hex
+            .clone_from(&active_delegation.fp_btc_pk_list[0]);
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // The staking output index must point to the staking output
+        let mut invalid_del = active_delegation.clone();
+        invalid_del.staking_output_idx = 1;
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![],
+            active_del: vec![invalid_del],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidBtcTx(_)));
+
+        // The staking output must commit to the staking time
+        let mut invalid_del = active_delegation.clone();
+        invalid_del.end_height += 1;
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![],
+            active_del: vec![invalid_del],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidBtcTx(_)));
+
+        // The valid delegation is accepted
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![],
+            active_del: vec![active_delegation],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn covenant_quorum_is_enforced() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+
+        let params = staking_params();
+        let quorum = params.covenant_quorum;
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                params: Some(params),
+                admin: None,
+            },
+        )
+        .unwrap();
+
+        let active_delegation = get_active_btc_delegation();
+        let mut new_fp = create_new_finality_provider(1);
+        new_fp
+            .btc_pk_hex
+            .clone_from(&active_delegation.fp_btc_pk_list[0]);
+        let msg = ExecuteMsg::BtcStaking {
+            new_fp: vec![new_fp],
+            active_del: vec![],
+            slashed_del: vec![],
+            unbonded_del: vec![],
+            edit_fp: vec![],
+            best_effort: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let add_delegation = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                              del: ActiveBtcDelegation| {
+            let msg = ExecuteMsg::BtcStaking {
+                new_fp: vec![],
+                active_del: vec![del],
+                slashed_del: vec![],
+                unbonded_del: vec![],
+                edit_fp: vec![],
+                best_effort: None,
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), msg)
+        };
+
+        // Not enough covenant signatures over the slashing tx
+        let mut del = active_delegation.clone();
+        del.covenant_sigs.pop();
+        let err = add_delegation(&mut deps, del).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientCovenantSigs(quorum as usize - 1, quorum)
+        );
+
+        // Duplicated covenant signatures are rejected
+        let mut del = active_delegation.clone();
+        let dup_sig = del.covenant_sigs[0].clone();
+        del.covenant_sigs.push(dup_sig.clone());
+        let err = add_delegation(&mut deps, del).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::DuplicateCovenantSig(hex::encode(dup_sig.cov_pk.as_slice()))
+        );
+
+        // Not enough covenant signatures over the unbonding tx
+        let mut del = active_delegation.clone();
+        del.undelegation_info.covenant_unbonding_sig_list.pop();
+        let err = add_delegation(&mut deps, del).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientCovenantUnbondingSigs(quorum as usize - 1, quorum)
+        );
+
+        // Not enough covenant signatures over the unbonding slashing tx
+        let mut del = active_delegation.clone();
+        del.undelegation_info.covenant_slashing_sigs.pop();
+        let err = add_delegation(&mut deps, del).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientCovenantUnbondingSlashingSigs(quorum as usize - 1, quorum)
+        );
+
+        // More adaptor signatures than finality providers are rejected (without panicking)
+        let mut del = active_delegation.clone();
+        let extra_sig = del.covenant_sigs[0].adaptor_sigs[0].clone();
+        del.covenant_sigs[0].adaptor_sigs.push(extra_sig);
+        let err = add_delegation(&mut deps, del).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidCovenantSig(_)));
+        let mut del = active_delegation.clone();
+        let extra_sig = del.undelegation_info.covenant_slashing_sigs[0].adaptor_sigs[0].clone();
+        del.undelegation_info.covenant_slashing_sigs[0]
+            .adaptor_sigs
+            .push(extra_sig);
+        let err = add_delegation(&mut deps, del).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidCovenantSig(_)));
+
+        // An end height lower than the start height is rejected (without overflowing)
+        let mut del = active_delegation.clone();
+        del.end_height = del.start_height - 1;
+        let err = add_delegation(&mut deps, del.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidStakingTime(del.start_height, del.end_height)
+        );
+
+        // The delegation with a quorum of covenant signatures is accepted
+        add_delegation(&mut deps, active_delegation).unwrap();
+    }
+
+    #[test]
+    fn slashing_policy_is_enforced() {
+        use babylon_apis::btc_staking_api::Params;
+        use babylon_btcstaking::error::Error as BTCStakingError;
+
+        let active_delegation = get_active_btc_delegation();
+        let add_delegation = |params: Params| {
+            let mut deps = mock_dependencies();
+            let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+            instantiate(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                InstantiateMsg {
+                    params: Some(params),
+                    admin: None,
+                },
+            )
+            .unwrap();
+
+            let mut new_fp = create_new_finality_provider(1);
+            new_fp
+                .btc_pk_hex
+                .clone_from(&active_delegation.fp_btc_pk_list[0]);
+            let msg = ExecuteMsg::BtcStaking {
+                new_fp: vec![new_fp],
+                active_del: vec![active_delegation.clone()],
+                slashed_del: vec![],
+                unbonded_del: vec![],
+                edit_fp: vec![],
+                best_effort: None,
+            };
+            execute(deps.as_mut(), mock_env(), info, msg)
+        };
+
+        // The slashing tx slashes less than the slashing rate
+        let params = Params {
+            slashing_rate: cosmwasm_std::Decimal::percent(50),
+            ..staking_params()
+        };
+        let staking_tx: Transaction = deserialize(&active_delegation.staking_tx).unwrap();
+        let staking_value = staking_tx.output[active_delegation.staking_output_idx as usize]
+            .value
+            .to_sat();
+        let err = add_delegation(params.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BTCStaking(BTCStakingError::InsufficientSlashingAmount(
+                babylon_btcstaking::tx_verify::slashing_amount(staking_value, params.slashing_rate)
+            ))
+        );
+
+        // The slashing tx pays a lower fee than the min slashing tx fee
+        let params = Params {
+            min_slashing_tx_fee_sat: staking_value,
+            ..staking_params()
+        };
+        let err = add_delegation(params).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BTCStaking(BTCStakingError::InsufficientSlashingFee(staking_value))
+        );
+
+        // The delegation satisfying the slashing policy is accepted
+        add_delegation(staking_params()).unwrap();
+    }
+
+    #[test]
+    fn unbonding_policy_is_enforced() {
+        use babylon_apis::btc_staking_api::Params;
+        use babylon_btcstaking::error::Error as BTCStakingError;
+
+        let active_delegation = get_active_btc_delegation();
+        let add_delegation = |params: Params| {
+            let mut deps = mock_dependencies();
+            let info = message_info(&deps.api.addr_make(CREATOR), &[]);
+            instantiate(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                InstantiateMsg {
+                    params: Some(params),
+                    admin: None,
+                },
+            )
+            .unwrap();
+
+            let mut new_fp = create_new_finality_provider(1);
+            new_fp
+                .btc_pk_hex
+                .clone_from(&active_delegation.fp_btc_pk_list[0]);
+            let msg = ExecuteMsg::BtcStaking {
+                new_fp: vec![new_fp],
+                active_del: vec![],
+                slashed_del: vec![],
+                unbonded_del: vec![],
+                edit_fp: vec![],
+                best_effort: None,
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+            let msg = ExecuteMsg::BtcStaking {
+                new_fp: vec![],
+                active_del: vec![active_delegation.clone()],
+                slashed_del: vec![],
+                unbonded_del: vec![],
+                edit_fp: vec![],
+                best_effort: None,
+            };
+            execute(deps.as_mut(), mock_env(), info, msg)
+        };
+
+        // The unbonding time is lower than the min unbonding time
+        let params = Params {
+            min_unbonding_time: active_delegation.unbonding_time + 1,
+            ..staking_params()
+        };
+        let err = add_delegation(params).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BTCStaking(BTCStakingError::InsufficientUnbondingTime(
+                active_delegation.unbonding_time as u16,
+                active_delegation.unbonding_time as u64 + 1
+            ))
+        );
+
+        // The unbonding time must be strictly larger than the min unbonding time
+        let params = Params {
+            min_unbonding_time: active_delegation.unbonding_time,
+            ..staking_params()
+        };
+        let err = add_delegation(params).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BTCStaking(BTCStakingError::InsufficientUnbondingTime(
+                active_delegation.unbonding_time as u16,
+                active_delegation.unbonding_time as u64
+            ))
+        );
+
+        // The unbonding tx fee is lower than the required unbonding fee
+        let params = Params {
+            unbonding_fee_sat: 2000,
+            ..staking_params()
+        };
+        let err = add_delegation(params).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BTCStaking(BTCStakingError::InsufficientUnbondingFee(2000))
+        );
+
+        // The delegation satisfying the unbonding policy is accepted
+        add_delegation(staking_params()).unwrap();
     }
 }
@@ -1,13 +1,21 @@
-use babylon_bitcoin::chain_params::Network;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Order, StdError, StdResult, Storage};
 
 use cw_controllers::Admin;
-use cw_storage_plus::Item;
-use derivative::Derivative;
+use cw_storage_plus::{Bound, Item, Map};
+
+pub use babylon_apis::btc_staking_api::Params;
+
+use crate::error::ContractError;
 
 pub(crate) const CONFIG: Item<Config> = Item::new("config");
-pub(crate) const PARAMS: Item<Params> = Item::new("params");
+/// Params history, by version.
+/// Each version applies to the BTC heights from its activation height onwards, until the
+/// activation height of the next version
+pub(crate) const PARAMS: Map<u32, Params> = Map::new("params_history");
+/// BTC activation height of the params, by version
+pub(crate) const PARAMS_BTC_ACTIVATION_HEIGHTS: Map<u32, u64> =
+    Map::new("params_btc_activation_heights");
 /// Storage for admin
 pub(crate) const ADMIN: Admin = Admin::new("admin");
 
@@ -18,33 +26,81 @@ pub struct Config {
     pub babylon: Addr,
 }
 
-/// Params define Consumer-selectable BTC staking parameters
-// TODO: Add / enable param entries as needed
-#[cw_serde]
-#[derive(Derivative)]
-#[derivative(Default)]
-pub struct Params {
-    // covenant_pks is the list of public keys held by the covenant committee each PK
-    // follows encoding in BIP-340 spec on Bitcoin
-    pub covenant_pks: Vec<String>,
-    // covenant_quorum is the minimum number of signatures needed for the covenant multi-signature
-    pub covenant_quorum: u32,
-    #[derivative(Default(value = "Network::Regtest"))]
-    // ntc_network is the network the BTC staking protocol is running on
-    pub btc_network: Network,
-    // `min_commission_rate` is the chain-wide minimum commission rate that a finality provider
-    // can charge their delegators
-    // pub min_commission_rate: Decimal,
-    /// `slashing_address` is the address that the slashed BTC goes to.
-    /// The address is in string format on Bitcoin.
-    #[derivative(Default(value = "String::from(\"n4cV57jePmAAue2WTTBQzH3k3R2rgWBQwY\")"))]
-    pub slashing_address: String,
-    /// `min_slashing_tx_fee_sat` is the minimum amount of tx fee (quantified in Satoshi) needed for
-    /// the pre-signed slashing tx
-    #[derivative(Default(value = "1000"))]
-    pub min_slashing_tx_fee_sat: u64,
-    /// `slashing_rate` determines the portion of the staked amount to be slashed,
-    /// expressed as a decimal (e.g. 0.5 for 50%).
-    #[derivative(Default(value = "String::from(\"0.1\")"))]
-    pub slashing_rate: String,
+/// get_latest_params returns the latest params, along with their version
+pub fn get_latest_params(storage: &dyn Storage) -> StdResult<(u32, Params)> {
+    PARAMS
+        .range(storage, None, None, Order::Descending)
+        .next()
+        .ok_or_else(|| StdError::not_found("params"))?
+}
+
+/// add_params adds the given params version, applying from the given BTC height onwards.
+/// The version is the one Babylon assigns to the params, as delegations refer to it. It must
+/// follow the latest version, without gaps, and its activation height must be higher than the one
+/// of the latest version
+pub(crate) fn add_params(
+    storage: &mut dyn Storage,
+    version: u32,
+    params: &Params,
+    btc_activation_height: u64,
+) -> Result<(), ContractError> {
+    let expected_version = match PARAMS_BTC_ACTIVATION_HEIGHTS
+        .range(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+    {
+        Some((latest_version, latest_height)) => {
+            if btc_activation_height <= latest_height {
+                return Err(ContractError::InvalidParamsActivationHeight(
+                    btc_activation_height,
+                    latest_height,
+                ));
+            }
+            latest_version + 1
+        }
+        None => 0,
+    };
+    if version != expected_version {
+        return Err(ContractError::UnexpectedParamsVersion(
+            version,
+            expected_version,
+        ));
+    }
+    PARAMS.save(storage, version, params)?;
+    PARAMS_BTC_ACTIVATION_HEIGHTS.save(storage, version, &btc_activation_height)?;
+    Ok(())
+}
+
+/// get_params_btc_range returns the BTC height range the params version applies to.
+/// The end height is inclusive, and `None` for the latest version
+pub fn get_params_btc_range(storage: &dyn Storage, version: u32) -> StdResult<(u64, Option<u64>)> {
+    let start_height = PARAMS_BTC_ACTIVATION_HEIGHTS.load(storage, version)?;
+    let end_height = PARAMS_BTC_ACTIVATION_HEIGHTS
+        .range(
+            storage,
+            Some(Bound::exclusive(version)),
+            None,
+            Order::Ascending,
+        )
+        .next()
+        .transpose()?
+        .map(|(_, next_height)| next_height - 1);
+    Ok((start_height, end_height))
+}
+
+/// get_params_for_btc_height returns the params of the given version, ensuring they apply to the
+/// given BTC height
+pub(crate) fn get_params_for_btc_height(
+    storage: &dyn Storage,
+    version: u32,
+    btc_height: u64,
+) -> Result<Params, ContractError> {
+    let params = PARAMS
+        .may_load(storage, version)?
+        .ok_or(ContractError::ParamsVersionNotFound(version))?;
+    let (start_height, end_height) = get_params_btc_range(storage, version)?;
+    if btc_height < start_height || end_height.is_some_and(|end| btc_height > end) {
+        return Err(ContractError::InvalidParamsVersion(version, btc_height));
+    }
+    Ok(params)
 }
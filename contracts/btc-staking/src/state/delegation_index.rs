@@ -0,0 +1,17 @@
+use cw_storage_plus::{Index, IndexList, MultiIndex};
+
+use crate::state::staking::BtcDelegation;
+
+pub struct DelegationIndexes<'a> {
+    // Staker address (multi-)index (deserializing the (hidden) staking tx hash to bytes)
+    pub staker: MultiIndex<'a, String, BtcDelegation, Vec<u8>>,
+    // Staker BTC PK (multi-)index (deserializing the (hidden) staking tx hash to bytes)
+    pub staker_btc_pk: MultiIndex<'a, String, BtcDelegation, Vec<u8>>,
+}
+
+impl<'a> IndexList<BtcDelegation> for DelegationIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<BtcDelegation>> + '_> {
+        let v: Vec<&dyn Index<BtcDelegation>> = vec![&self.staker, &self.staker_btc_pk];
+        Box::new(v.into_iter())
+    }
+}
@@ -1,4 +1,5 @@
 pub mod config;
 pub mod staking;
 
+mod delegation_index;
 mod fp_index;
@@ -1,6 +1,10 @@
 use cosmwasm_schema::cw_serde;
-use cw_storage_plus::{IndexedSnapshotMap, Item, Map, MultiIndex, Strategy};
+use cosmwasm_std::Decimal;
+use cw_storage_plus::{
+    IndexedMap, IndexedSnapshotMap, Item, Map, MultiIndex, SnapshotItem, Strategy,
+};
 
+use crate::state::delegation_index::DelegationIndexes;
 use crate::state::fp_index::FinalityProviderIndexes;
 use babylon_apis::btc_staking_api::{BTCDelegationStatus, FinalityProvider, HASH_SIZE};
 use babylon_apis::{btc_staking_api, Bytes};
@@ -46,13 +50,30 @@ pub struct BtcDelegation {
     pub params_version: u32,
     /// slashed is used to indicate whether a given delegation is related to a slashed FP
     pub slashed: bool,
+    /// expired is used to indicate whether the voting power of the delegation has been removed
+    /// because its time-lock ended.
+    /// It is kept even if the BTC tip goes back (e.g. due to a reorg)
+    #[serde(default)]
+    pub expired: bool,
 }
 
 impl BtcDelegation {
-    pub fn is_active(&self) -> bool {
-        // TODO: Implement full delegation status checks (needs BTC height)
-        // self.get_status(btc_height, w) == BTCDelegationStatus::ACTIVE
-        !self.is_unbonded_early() && !self.is_slashed()
+    /// is_active returns whether the delegation is active at the given BTC tip.
+    /// If the BTC tip is not known yet, the time-lock of the delegation is not considered
+    pub fn is_active(&self, btc_tip: Option<&BtcTipInfo>) -> bool {
+        match btc_tip {
+            Some(tip) => {
+                self.get_status(tip.height, tip.checkpoint_finalization_timeout)
+                    == BTCDelegationStatus::ACTIVE
+            }
+            None => !self.is_unbonded_early() && !self.is_slashed() && !self.expired,
+        }
+    }
+
+    /// is_expired returns whether the delegation has less than `w` BTC blocks left in its
+    /// time-lock at the given BTC height
+    pub fn is_expired(&self, btc_height: u64, w: u64) -> bool {
+        btc_height + w > self.end_height
     }
 
     fn is_unbonded_early(&self) -> bool {
@@ -65,10 +86,11 @@ impl BtcDelegation {
 
     pub fn get_status(&self, btc_height: u64, w: u64) -> BTCDelegationStatus {
         // Manually unbonded, staking tx time-lock has not begun, is less than w BTC blocks left, or
-        // has expired
+        // has expired (at this or a previous BTC tip)
         if self.is_unbonded_early()
             || btc_height < self.start_height
-            || btc_height + w > self.end_height
+            || self.is_expired(btc_height, w)
+            || self.expired
             || self.is_slashed()
         {
             BTCDelegationStatus::UNBONDED
@@ -102,6 +124,7 @@ impl From<btc_staking_api::ActiveBtcDelegation> for BtcDelegation {
             undelegation_info: active_delegation.undelegation_info.into(),
             params_version: active_delegation.params_version,
             slashed: false,
+            expired: false,
         }
     }
 }
@@ -199,13 +222,23 @@ impl From<btc_staking_api::SignatureInfo> for SignatureInfo {
 /// Finality providers by their BTC public key
 pub(crate) const FPS: Map<&str, FinalityProvider> = Map::new("fps");
 
-/// Delegations by staking tx hash
-/// TODO: create a new DB object for BTC delegation
-pub(crate) const DELEGATIONS: Map<&[u8; HASH_SIZE], BtcDelegation> = Map::new("delegations");
-/// Map of staking hashes by finality provider
-pub(crate) const FP_DELEGATIONS: Map<&str, Vec<Vec<u8>>> = Map::new("fp_delegations");
+/// Commission rate history of finality providers.
+/// Keyed by finality provider and the Babylon height at which the commission rate was set
+pub(crate) const FP_COMMISSION_HISTORY: Map<(&str, u64), Decimal> =
+    Map::new("fp_commission_history");
+
+/// Staking hashes by finality provider.
+/// Keyed by finality provider and staking hash, so that they can be paginated
+pub(crate) const FP_DELEGATIONS: Map<(&str, &[u8]), ()> = Map::new("fp_dels");
 /// Reverse map of finality providers by staking hash
 pub(crate) const DELEGATION_FPS: Map<&[u8; HASH_SIZE], Vec<String>> = Map::new("delegation_fps");
+/// Expiry queue of delegations, by the end BTC height of their time-lock and staking hash
+pub(crate) const DELEGATION_EXPIRY_QUEUE: Map<(u64, &[u8; HASH_SIZE]), ()> =
+    Map::new("delegation_expiry_queue");
+
+pub const DELEGATIONS_KEY: &str = "delegations";
+pub const DELEGATIONS_STAKER_KEY: &str = "delegations__staker";
+pub const DELEGATIONS_STAKER_BTC_PK_KEY: &str = "delegations__staker_btc_pk";
 
 pub const FP_STATE_KEY: &str = "fp_state";
 const FP_STATE_CHECKPOINTS: &str = "fp_state__checkpoints";
@@ -215,6 +248,71 @@ pub const FP_POWER_KEY: &str = "fp_state__power";
 /// The height at which the contract gets its first delegation
 pub const ACTIVATED_HEIGHT: Item<u64> = Item::new("activated_height");
 
+/// The BTC tip, as notified by the babylon contract
+#[cw_serde]
+pub struct BtcTipInfo {
+    /// `height` is the height of the BTC tip
+    pub height: u64,
+    /// `checkpoint_finalization_timeout` is the `w` parameter of the delegation status
+    pub checkpoint_finalization_timeout: u64,
+}
+
+/// The BTC tip known to the contract.
+/// Not set until the babylon contract sends its first BTC tip update
+pub const BTC_TIP: Item<BtcTipInfo> = Item::new("btc_tip");
+
+/// Aggregated staking statistics, kept up to date as delegations and finality providers change
+#[cw_serde]
+#[derive(Default)]
+pub struct StakingStats {
+    /// `total_active_sat` is the total amount of BTC in active delegations, in satoshis.
+    /// Restaked delegations are counted once
+    pub total_active_sat: u64,
+    /// `total_power` is the aggregated voting power of all finality providers, in satoshis.
+    /// Restaked delegations are counted once per (non-slashed) finality provider
+    pub total_power: u64,
+    /// `active_delegations` is the number of active delegations
+    pub active_delegations: u64,
+    /// `unbonded_delegations` is the number of delegations unbonded early, or expired
+    pub unbonded_delegations: u64,
+    /// `slashed_delegations` is the number of slashed delegations
+    pub slashed_delegations: u64,
+    /// `finality_providers` is the number of registered finality providers, slashed or not
+    pub finality_providers: u64,
+    /// `slashed_finality_providers` is the number of slashed finality providers
+    pub slashed_finality_providers: u64,
+}
+
+/// The staking statistics, snapshotted by height
+pub const STAKING_STATS: SnapshotItem<StakingStats> = SnapshotItem::new(
+    "staking_stats",
+    "staking_stats__checkpoints",
+    "staking_stats__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Indexed map for delegations, by staking tx hash.
+///
+/// This allows querying the delegations of a staker, by its staker address or BTC public key.
+/// Both indexes are `MultiIndex`es, as a staker can have multiple delegations.
+/// TODO: create a new DB object for BTC delegation
+pub fn btc_delegations<'a>() -> IndexedMap<&'a [u8; HASH_SIZE], BtcDelegation, DelegationIndexes<'a>>
+{
+    let indexes = DelegationIndexes {
+        staker: MultiIndex::new(
+            |_, del| del.staker_addr.clone(),
+            DELEGATIONS_KEY,
+            DELEGATIONS_STAKER_KEY,
+        ),
+        staker_btc_pk: MultiIndex::new(
+            |_, del| del.btc_pk_hex.clone(),
+            DELEGATIONS_KEY,
+            DELEGATIONS_STAKER_BTC_PK_KEY,
+        ),
+    };
+    IndexedMap::new(DELEGATIONS_KEY, indexes)
+}
+
 /// Indexed snapshot map for finality providers.
 ///
 /// This allows querying the map finality providers, sorted by their (aggregated) power.
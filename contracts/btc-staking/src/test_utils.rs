@@ -1,4 +1,5 @@
 use babylon_bitcoin::chain_params::Network;
+use cosmwasm_std::Decimal;
 use test_utils::get_params;
 
 use crate::state::config::Params;
@@ -11,6 +12,13 @@ pub fn staking_params() -> Params {
         btc_network: Network::Regtest, // TODO: fix this
         slashing_address: proto_params.slashing_address,
         min_slashing_tx_fee_sat: proto_params.min_slashing_tx_fee_sat as u64,
-        slashing_rate: "0.01".to_string(), // TODO: fix this
+        slashing_rate: Decimal::percent(1), // TODO: fix this
+        min_unbonding_time: proto_params.min_unbonding_time,
+        min_unbonding_rate: Decimal::percent(80), // TODO: fix this
+        unbonding_fee_sat: 1000,
+        min_commission_rate: Decimal::zero(),
+        max_commission_change_rate: Decimal::one(),
+        max_fps_per_delegation: 5,
+        require_staking_tx_inclusion_proof: false,
     }
 }
@@ -1,29 +1,29 @@
 use crate::state::config::Params;
 use crate::{error::ContractError, state::staking::BtcDelegation};
-use babylon_apis::btc_staking_api::{ActiveBtcDelegation, NewFinalityProvider};
-use bitcoin::Transaction;
-use cosmwasm_std::Binary;
-
-#[cfg(feature = "full-validation")]
-use {
-    babylon_apis::btc_staking_api::{BTCSigType, ProofOfPossessionBtc},
-    babylon_bitcoin::schnorr::verify_digest,
-    babylon_btcstaking::adaptor_sig::AdaptorSignature,
-    babylon_btcstaking::sig_verify::enc_verify_transaction_sig_with_output,
-    bitcoin::{consensus::deserialize, Address},
-    cosmwasm_std::CanonicalAddr,
-    hex::ToHex,
-    k256::schnorr::{Signature, SigningKey, VerifyingKey},
-    k256::sha2::{Digest, Sha256},
-    std::str::FromStr,
+use babylon_apis::btc_staking_api::{
+    ActiveBtcDelegation, BTCSigType, NewFinalityProvider, ProofOfPossessionBtc,
 };
+use babylon_apis::new_canonical_addr;
+use babylon_bitcoin::chain_params::get_bitcoin_network;
+use babylon_bitcoin::{bip322, ecdsa, schnorr::verify_digest};
+use babylon_btcstaking::adaptor_sig::AdaptorSignature;
+use babylon_btcstaking::sig_verify::{enc_verify_transaction_sig_with_output, get_output_idx};
+use babylon_btcstaking::tx_verify::is_rate_valid;
+use babylon_proto::babylon::btcstaking::v1::Bip322Sig;
+use bitcoin::{consensus::deserialize, Address, Transaction, Witness};
+use cosmwasm_std::{Binary, CanonicalAddr, Decimal};
+use hex::ToHex;
+use k256::schnorr::{Signature, SigningKey, VerifyingKey};
+use k256::sha2::{Digest, Sha256};
+use prost::Message;
+use std::str::FromStr;
 
 /// verify_pop verifies the proof of possession of the given address.
-#[cfg(feature = "full-validation")]
 fn verify_pop(
     btc_pk: &VerifyingKey,
     address: CanonicalAddr,
     pop: &ProofOfPossessionBtc,
+    network: bitcoin::Network,
 ) -> Result<(), ContractError> {
     // get signed msg, i.e., the hash of the canonicalised address
     let address_bytes = address.as_slice();
@@ -40,19 +40,33 @@ fn verify_pop(
                 .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
         }
         BTCSigType::BIP322 => {
-            // TODO: implement BIP322 verification
-            return Ok(());
+            // the BIP-322 signature comes together with the signer's BTC address
+            let bip322_sig = Bip322Sig::decode(pop.btc_sig.as_slice())
+                .map_err(|e| ContractError::FinalityProviderVerificationError(e.to_string()))?;
+            let btc_address = Address::from_str(&bip322_sig.address)
+                .map_err(|e| ContractError::FinalityProviderVerificationError(e.to_string()))?
+                .require_network(network)
+                .map_err(|e| ContractError::FinalityProviderVerificationError(e.to_string()))?;
+            let witness: Witness = deserialize(&bip322_sig.sig)
+                .map_err(|e| ContractError::FinalityProviderVerificationError(e.to_string()))?;
+
+            // the signer must be the FP's BTC key, and the signature must be valid
+            bip322::verify_signer(&btc_address, &witness, &btc_pk.to_bytes().into())?;
+            bip322::verify(&msg_hash, &btc_address, &witness)?;
         }
         BTCSigType::ECDSA => {
-            // TODO: implement ECDSA verification
-            return Ok(());
+            // the signed message is the hex-encoded msg hash, as wallets sign text messages
+            ecdsa::verify(
+                &hex::encode(msg_hash),
+                &pop.btc_sig,
+                &btc_pk.to_bytes().into(),
+            )?;
         }
     }
 
     Ok(())
 }
 
-#[cfg(feature = "full-validation")]
 fn decode_pks(
     staker_pk_hex: &str,
     fp_pk_hex_list: &[String],
@@ -60,7 +74,7 @@ fn decode_pks(
 ) -> Result<(VerifyingKey, Vec<VerifyingKey>, Vec<VerifyingKey>), ContractError> {
     // get staker's public key
     let staker_pk_bytes =
-        hex::decode(&staker_pk_hex).map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
+        hex::decode(staker_pk_hex).map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
     let staker_pk = VerifyingKey::from_bytes(&staker_pk_bytes)
         .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
 
@@ -88,438 +102,568 @@ fn decode_pks(
     Ok((staker_pk, fp_pks, cov_pks))
 }
 
-/// verify_new_fp verifies the new finality provider data (full validation version)
-pub fn verify_new_fp(new_fp: &NewFinalityProvider) -> Result<(), ContractError> {
-    // The following code is marked with `#[cfg(feature = "full-validation")]`
-    // so that it is included in the build if the `full-validation` feature is
-    // enabled.
-    // TODO: fix contract size when full-validation is enabled
-    #[cfg(feature = "full-validation")]
-    {
-        // get FP's PK
-        use babylon_apis::new_canonical_addr;
-        let fp_pk_bytes = hex::decode(&new_fp.btc_pk_hex)
-            .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-        let fp_pk = VerifyingKey::from_bytes(&fp_pk_bytes)
-            .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
+/// verify_params verifies the consistency of the BTC staking params
+pub fn verify_params(params: &Params) -> Result<(), ContractError> {
+    params.btc_network.validate()?;
 
-        // get canonicalised FP address
-        // TODO: parameterise `bbn` prefix
-        let addr = new_fp.addr.clone();
-        let address = new_canonical_addr(&addr, "bbn")?;
-
-        // get FP's PoP
-        let pop = new_fp
-            .pop
-            .clone()
-            .ok_or(ContractError::FinalityProviderVerificationError(
-                "proof of possession is missing".to_string(),
-            ))?;
-
-        // verify PoP
-        verify_pop(&fp_pk, address, &pop)?;
+    // Covenant keys are BIP-340 public keys
+    for cov_pk_hex in &params.covenant_pks {
+        let cov_pk = hex::decode(cov_pk_hex)?;
+        k256::schnorr::VerifyingKey::from_bytes(&cov_pk).map_err(|e| {
+            ContractError::InvalidParams(format!("invalid covenant key {cov_pk_hex}: {e}"))
+        })?;
+    }
+    if params.covenant_quorum as usize > params.covenant_pks.len() {
+        return Err(ContractError::InvalidParams(format!(
+            "covenant quorum {} is larger than the number of covenant keys {}",
+            params.covenant_quorum,
+            params.covenant_pks.len()
+        )));
     }
 
-    // make static analyser happy with unused parameters
-    #[cfg(not(feature = "full-validation"))]
-    let _ = new_fp;
+    // The slashing address must be valid for the BTC network
+    let network = get_bitcoin_network(params.btc_network.clone());
+    bitcoin::Address::from_str(&params.slashing_address)
+        .map_err(|e| ContractError::InvalidParams(format!("invalid slashing address: {e}")))?
+        .require_network(network)
+        .map_err(|e| ContractError::InvalidParams(format!("invalid slashing address: {e}")))?;
+
+    // The slashing rate must be a proper fraction, with at most 2 decimal places
+    if !is_rate_valid(params.slashing_rate) {
+        return Err(ContractError::InvalidParams(format!(
+            "slashing rate {} is not in (0, 1) or has more than 2 decimal places",
+            params.slashing_rate
+        )));
+    }
 
-    Ok(())
-}
+    // The min unbonding rate must be a proper fraction, with at most 2 decimal places
+    if !is_rate_valid(params.min_unbonding_rate) {
+        return Err(ContractError::InvalidParams(format!(
+            "min unbonding rate {} is not in (0, 1) or has more than 2 decimal places",
+            params.min_unbonding_rate
+        )));
+    }
 
-/// verify_active_delegation verifies the active delegation data
-pub fn verify_active_delegation(
-    params: &Params,
-    active_delegation: &ActiveBtcDelegation,
-    staking_tx: &Transaction,
-) -> Result<(), ContractError> {
-    // The following code is marked with `#[cfg(feature = "full-validation")]`
-    // so that it is included in the build if the `full-validation` feature is
-    // enabled.
-    // TODO: fix contract size when full-validation is enabled
-    #[cfg(feature = "full-validation")]
-    {
-        let (staker_pk, fp_pks, cov_pks) = decode_pks(
-            &active_delegation.btc_pk_hex,
-            &active_delegation.fp_btc_pk_list,
-            &params.covenant_pks,
-        )?;
+    // The commission rates must be proper rates
+    if params.min_commission_rate > Decimal::one() {
+        return Err(ContractError::InvalidParams(format!(
+            "min commission rate {} is larger than 1",
+            params.min_commission_rate
+        )));
+    }
+    if params.max_commission_change_rate > Decimal::one() {
+        return Err(ContractError::InvalidParams(format!(
+            "max commission change rate {} is larger than 1",
+            params.max_commission_change_rate
+        )));
+    }
 
-        // Check if data provided in request, matches data to which staking tx is
-        // committed
+    // The unbonding tx must pay some fee
+    if params.unbonding_fee_sat == 0 {
+        return Err(ContractError::InvalidParams(
+            "unbonding fee must be positive".to_string(),
+        ));
+    }
 
-        // TODO: Check staking tx time-lock has correct values
-        // get start_height and end_height of the time-lock
+    // A delegation must be able to restake to at least one finality provider
+    if params.max_fps_per_delegation == 0 {
+        return Err(ContractError::InvalidParams(
+            "max finality providers per delegation must be positive".to_string(),
+        ));
+    }
 
-        // TODO: Ensure staking tx is k-deep
+    Ok(())
+}
 
-        // TODO: Ensure staking tx time-lock has more than w BTC blocks left
+/// verify_new_fp verifies the new finality provider data, i.e. its proof of possession
+pub fn verify_new_fp(new_fp: &NewFinalityProvider, params: &Params) -> Result<(), ContractError> {
+    // get FP's PK
+    let fp_pk_bytes = hex::decode(&new_fp.btc_pk_hex)
+        .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
+    let fp_pk = VerifyingKey::from_bytes(&fp_pk_bytes)
+        .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
 
-        // TODO: Verify staking tx info, i.e. inclusion proof
+    // get canonicalised FP address
+    // TODO: parameterise `bbn` prefix
+    let addr = new_fp.addr.clone();
+    let address = new_canonical_addr(&addr, "bbn")?;
 
-        // Check slashing tx and its consistency with staking tx
-        let slashing_tx: Transaction = deserialize(&active_delegation.slashing_tx)
-            .map_err(|_| ContractError::InvalidBtcTx(active_delegation.slashing_tx.encode_hex()))?;
+    // get FP's PoP
+    let pop = new_fp
+        .pop
+        .clone()
+        .ok_or(ContractError::FinalityProviderVerificationError(
+            "proof of possession is missing".to_string(),
+        ))?;
 
-        // decode slashing address
-        let slashing_address: Address = Address::from_str(&params.slashing_address)
-            .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?
-            .assume_checked();
+    // verify PoP
+    let network = get_bitcoin_network(params.btc_network.clone());
+    verify_pop(&fp_pk, address, &pop, network)?;
 
-        // Check slashing tx and staking tx are valid and consistent
-        let slashing_rate = params
-            .slashing_rate
-            .parse::<f64>()
-            .map_err(|_| ContractError::InvalidBtcTx("invalid slashing rate".to_string()))?;
-        babylon_btcstaking::tx_verify::check_transactions(
-            &slashing_tx,
-            staking_tx,
-            active_delegation.staking_output_idx,
-            params.min_slashing_tx_fee_sat,
-            slashing_rate,
-            &slashing_address,
-            &staker_pk,
-            active_delegation.unbonding_time as u16,
-        )?;
+    Ok(())
+}
 
-        // TODO: Verify proof of possession
+/// verify_commission verifies the commission rate of a finality provider is not lower than the
+/// min commission rate and, when it changes, that it does not change more than the max commission
+/// change rate
+pub fn verify_commission(
+    params: &Params,
+    commission: Decimal,
+    prev_commission: Option<Decimal>,
+) -> Result<(), ContractError> {
+    if commission < params.min_commission_rate {
+        return Err(ContractError::CommissionTooLow(
+            commission,
+            params.min_commission_rate,
+        ));
+    }
+    if let Some(prev_commission) = prev_commission {
+        let change = commission.abs_diff(prev_commission);
+        if change > params.max_commission_change_rate {
+            return Err(ContractError::CommissionChangeTooLarge(
+                change,
+                params.max_commission_change_rate,
+            ));
+        }
+    }
+    Ok(())
+}
 
-        /*
-            verify staker signature against slashing path of the staking tx script
-        */
+/// check_covenant_quorum ensures the given covenant signers are at least `quorum` distinct
+/// covenant members, rejecting duplicated signers.
+/// `insufficient` builds the error to return when the quorum is not met
+fn check_covenant_quorum<'a>(
+    cov_pks: impl Iterator<Item = &'a [u8]>,
+    quorum: u32,
+    insufficient: fn(usize, u32) -> ContractError,
+) -> Result<(), ContractError> {
+    let mut signers = std::collections::BTreeSet::new();
+    for cov_pk in cov_pks {
+        if !signers.insert(cov_pk) {
+            return Err(ContractError::DuplicateCovenantSig(hex::encode(cov_pk)));
+        }
+    }
+    if signers.len() < quorum as usize {
+        return Err(insufficient(signers.len(), quorum));
+    }
+    Ok(())
+}
 
-        // get the slashing path script
-        let staking_output = &staking_tx.output[active_delegation.staking_output_idx as usize];
-        let staking_time = (active_delegation.end_height - active_delegation.start_height) as u16;
-        let babylon_script_paths = babylon_btcstaking::scripts_utils::BabylonScriptPaths::new(
-            &staker_pk,
-            &fp_pks,
-            &cov_pks,
-            params.covenant_quorum as usize,
-            staking_time,
-        )?;
-        let slashing_path_script = babylon_script_paths.slashing_path_script;
+/// verify_active_delegation verifies the active delegation data, i.e. the staker's and covenant
+/// members' signatures, and the BTC txs against the staking params (staking output, slashing and
+/// unbonding tx policies)
+pub fn verify_active_delegation(
+    params: &Params,
+    active_delegation: &ActiveBtcDelegation,
+    staking_tx: &Transaction,
+    checkpoint_finalization_timeout: u64,
+) -> Result<(), ContractError> {
+    let (staker_pk, fp_pks, cov_pks) = decode_pks(
+        &active_delegation.btc_pk_hex,
+        &active_delegation.fp_btc_pk_list,
+        &params.covenant_pks,
+    )?;
+
+    // get the staking output and the staking script paths
+    let staking_output = staking_tx
+        .output
+        .get(active_delegation.staking_output_idx as usize)
+        .ok_or(ContractError::InvalidBtcTx(format!(
+            "staking output index {} out of range",
+            active_delegation.staking_output_idx
+        )))?;
+    let staking_time = active_delegation
+        .end_height
+        .checked_sub(active_delegation.start_height)
+        .and_then(|staking_time| u16::try_from(staking_time).ok())
+        .ok_or(ContractError::InvalidStakingTime(
+            active_delegation.start_height,
+            active_delegation.end_height,
+        ))?;
+    let babylon_script_paths = babylon_btcstaking::scripts_utils::BabylonScriptPaths::new(
+        &staker_pk,
+        &fp_pks,
+        &cov_pks,
+        params.covenant_quorum as usize,
+        staking_time,
+    )?;
+
+    // Check the staking output pays to the taproot output committing to the
+    // staking script paths
+    // TODO: Check staking tx time-lock has correct values
+    // TODO: Ensure staking tx time-lock has more than w BTC blocks left
+    // NOTE: The staking tx inclusion proof and k-deepness are verified against the babylon
+    // contract BTC headers before this (see `verify_staking_tx_inclusion`)
+    if staking_output.script_pubkey != babylon_script_paths.staking_pk_script() {
+        return Err(ContractError::InvalidBtcTx(
+            "staking output does not commit to the staking script paths".to_string(),
+        ));
+    }
 
-        // get the staker's signature on the slashing tx
-        let staker_sig =
-            k256::schnorr::Signature::try_from(active_delegation.delegator_slashing_sig.as_slice())
-                .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-        // Verify the staker's signature
-        babylon_btcstaking::sig_verify::verify_transaction_sig_with_output(
-            &slashing_tx,
-            staking_output,
-            slashing_path_script.as_script(),
-            &staker_pk,
-            &staker_sig,
-        )
+    // decode slashing tx
+    let slashing_tx: Transaction = deserialize(&active_delegation.slashing_tx)
+        .map_err(|_| ContractError::InvalidBtcTx(active_delegation.slashing_tx.encode_hex()))?;
+
+    // decode slashing address
+    let slashing_address: Address = Address::from_str(&params.slashing_address)
+        .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?
+        .assume_checked();
+
+    // Check slashing tx and staking tx are valid and consistent
+    babylon_btcstaking::tx_verify::check_transactions(
+        &slashing_tx,
+        staking_tx,
+        active_delegation.staking_output_idx,
+        params.min_slashing_tx_fee_sat,
+        params.slashing_rate,
+        &slashing_address,
+        &staker_pk,
+        active_delegation.unbonding_time as u16,
+    )?;
+
+    /*
+        verify staker signature against slashing path of the staking tx script
+    */
+
+    // get the slashing path script
+    let slashing_path_script = babylon_script_paths.slashing_path_script;
+
+    // get the staker's signature on the slashing tx
+    let staker_sig = Signature::try_from(active_delegation.delegator_slashing_sig.as_slice())
         .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-
-        /*
-            Verify covenant signatures over slashing tx
-        */
-        for cov_sig in active_delegation.covenant_sigs.iter() {
-            let cov_pk = VerifyingKey::from_bytes(&cov_sig.cov_pk)
-                .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-            // Check if the covenant public key is in the params.covenant_pks
-            if !params
-                .covenant_pks
-                .contains(&hex::encode(cov_sig.cov_pk.as_slice()))
-            {
-                return Err(ContractError::InvalidCovenantSig(
-                    "Covenant public key not found in params".to_string(),
-                ));
-            }
-            let sigs = cov_sig
-                .adaptor_sigs
-                .iter()
-                .map(|sig| {
-                    AdaptorSignature::new(sig.as_slice())
-                        .map_err(|e| ContractError::SecP256K1Error(e.to_string()))
-                })
-                .collect::<Result<Vec<AdaptorSignature>, ContractError>>()?;
-            for (idx, sig) in sigs.iter().enumerate() {
-                enc_verify_transaction_sig_with_output(
-                    &slashing_tx,
-                    staking_output,
-                    slashing_path_script.as_script(),
-                    &cov_pk,
-                    &fp_pks[idx],
-                    &sig,
-                )
-                .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-            }
-        }
-
-        // TODO: Check unbonding time (staking time from unbonding tx) is larger than min unbonding time
-        // which is larger value from:
-        // - MinUnbondingTime
-        // - CheckpointFinalizationTimeout
-
-        // At this point, we know that unbonding time in request:
-        // - is larger than min unbonding time
-        // - is smaller than math.MaxUint16 (due to check in req.ValidateBasic())
-
-        /*
-            Early unbonding logic
-        */
-
-        // decode unbonding tx
-        let unbonding_tx = &active_delegation.undelegation_info.unbonding_tx;
-        let unbonding_tx: Transaction = deserialize(unbonding_tx)
-            .map_err(|_| ContractError::InvalidBtcTx(unbonding_tx.encode_hex()))?;
-        // decode unbonding slashing tx
-        let unbonding_slashing_tx = &active_delegation.undelegation_info.slashing_tx;
-        let unbonding_slashing_tx: Transaction = deserialize(unbonding_slashing_tx)
-            .map_err(|_| ContractError::InvalidBtcTx(unbonding_slashing_tx.encode_hex()))?;
-
-        // Check that the unbonding tx input is pointing to staking tx
-        if unbonding_tx.input[0].previous_output.txid != staking_tx.txid()
-            || unbonding_tx.input[0].previous_output.vout != active_delegation.staking_output_idx
+    // Verify the staker's signature
+    babylon_btcstaking::sig_verify::verify_transaction_sig_with_output(
+        &slashing_tx,
+        staking_output,
+        slashing_path_script.as_script(),
+        &staker_pk,
+        &staker_sig,
+    )
+    .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
+
+    /*
+        Verify covenant signatures over slashing tx
+    */
+    check_covenant_quorum(
+        active_delegation
+            .covenant_sigs
+            .iter()
+            .map(|cov_sig| cov_sig.cov_pk.as_slice()),
+        params.covenant_quorum,
+        ContractError::InsufficientCovenantSigs,
+    )?;
+    for cov_sig in active_delegation.covenant_sigs.iter() {
+        let cov_pk = VerifyingKey::from_bytes(&cov_sig.cov_pk)
+            .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
+        // Check if the covenant public key is in the params.covenant_pks
+        if !params
+            .covenant_pks
+            .contains(&hex::encode(cov_sig.cov_pk.as_slice()))
         {
-            return Err(ContractError::InvalidBtcTx(
-                "Unbonding transaction must spend staking output".to_string(),
+            return Err(ContractError::InvalidCovenantSig(
+                "Covenant public key not found in params".to_string(),
             ));
         }
+        // There must be one adaptor signature per finality provider
+        if cov_sig.adaptor_sigs.len() != fp_pks.len() {
+            return Err(ContractError::InvalidCovenantSig(format!(
+                "{} adaptor signatures for {} finality providers",
+                cov_sig.adaptor_sigs.len(),
+                fp_pks.len()
+            )));
+        }
+        let sigs = cov_sig
+            .adaptor_sigs
+            .iter()
+            .map(|sig| {
+                AdaptorSignature::new(sig.as_slice())
+                    .map_err(|e| ContractError::SecP256K1Error(e.to_string()))
+            })
+            .collect::<Result<Vec<AdaptorSignature>, ContractError>>()?;
+        for (idx, sig) in sigs.iter().enumerate() {
+            enc_verify_transaction_sig_with_output(
+                &slashing_tx,
+                staking_output,
+                slashing_path_script.as_script(),
+                &cov_pk,
+                &fp_pks[idx],
+                sig,
+            )
+            .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
+        }
+    }
 
-        // TODO: Check unbonding tx fees against staking tx
-        // - Fee is greater than 0.
-        // - Unbonding output value is at least `MinUnbondingValue` percentage of staking output value.
-
-        let babylon_unbonding_script_paths =
-            babylon_btcstaking::scripts_utils::BabylonScriptPaths::new(
-                &staker_pk,
-                &fp_pks,
-                &cov_pks,
-                params.covenant_quorum as usize,
-                staking_time,
-            )?;
-
-        // TODO: Ensure the unbonding tx has valid unbonding output, and
-        // get the unbonding output index
-        let unbonding_output_idx = 0;
-        let unbonding_output = &unbonding_tx.output[unbonding_output_idx as usize];
-
-        let unbonding_time = active_delegation.unbonding_time as u16;
-
-        // Check that unbonding tx and unbonding slashing tx are consistent
-        babylon_btcstaking::tx_verify::check_transactions(
-            &unbonding_slashing_tx,
-            &unbonding_tx,
-            unbonding_output_idx,
-            params.min_slashing_tx_fee_sat,
-            slashing_rate,
-            &slashing_address,
+    /*
+        Early unbonding logic
+    */
+
+    // decode unbonding tx
+    let unbonding_tx = &active_delegation.undelegation_info.unbonding_tx;
+    let unbonding_tx: Transaction = deserialize(unbonding_tx)
+        .map_err(|_| ContractError::InvalidBtcTx(unbonding_tx.encode_hex()))?;
+    // decode unbonding slashing tx
+    let unbonding_slashing_tx = &active_delegation.undelegation_info.slashing_tx;
+    let unbonding_slashing_tx: Transaction = deserialize(unbonding_slashing_tx)
+        .map_err(|_| ContractError::InvalidBtcTx(unbonding_slashing_tx.encode_hex()))?;
+
+    // Check the unbonding tx spends the staking output, and its unbonding time, fee and
+    // output value against the unbonding policy.
+    // The unbonding time must be larger than both the min unbonding time and the
+    // checkpoint finalization timeout
+    let unbonding_time = active_delegation.unbonding_time as u16;
+    babylon_btcstaking::tx_verify::check_unbonding_tx(
+        &unbonding_tx,
+        staking_tx,
+        active_delegation.staking_output_idx,
+        unbonding_time,
+        (params.min_unbonding_time as u64).max(checkpoint_finalization_timeout),
+        params.unbonding_fee_sat,
+        params.min_unbonding_rate,
+    )?;
+
+    let babylon_unbonding_script_paths =
+        babylon_btcstaking::scripts_utils::BabylonScriptPaths::new(
             &staker_pk,
+            &fp_pks,
+            &cov_pks,
+            params.covenant_quorum as usize,
             unbonding_time,
         )?;
 
-        /*
-            Check staker signature against slashing path of the unbonding tx
-        */
-        // get unbonding slashing path script
-        let unbonding_slashing_path_script = babylon_unbonding_script_paths.slashing_path_script;
-        // get the staker's signature on the unbonding slashing tx
-        let unbonding_slashing_sig = active_delegation
-            .undelegation_info
-            .delegator_slashing_sig
-            .as_slice();
-        let unbonding_slashing_sig = k256::schnorr::Signature::try_from(unbonding_slashing_sig)
-            .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-        // Verify the staker's signature
-        babylon_btcstaking::sig_verify::verify_transaction_sig_with_output(
-            &unbonding_slashing_tx,
-            &unbonding_tx.output[unbonding_output_idx as usize],
-            unbonding_slashing_path_script.as_script(),
-            &staker_pk,
-            &unbonding_slashing_sig,
+    // Ensure the unbonding tx has a valid unbonding output, i.e. paying to the taproot
+    // output committing to the unbonding script paths, and get its index
+    let unbonding_output_idx = get_output_idx(
+        &unbonding_tx,
+        babylon_unbonding_script_paths.unbonding_pk_script(),
+    )
+    .map_err(|_| {
+        ContractError::InvalidBtcTx(
+            "unbonding tx does not have a valid unbonding output".to_string(),
         )
+    })?;
+    let unbonding_output = &unbonding_tx.output[unbonding_output_idx as usize];
+
+    // Check that unbonding tx and unbonding slashing tx are consistent
+    babylon_btcstaking::tx_verify::check_transactions(
+        &unbonding_slashing_tx,
+        &unbonding_tx,
+        unbonding_output_idx,
+        params.min_slashing_tx_fee_sat,
+        params.slashing_rate,
+        &slashing_address,
+        &staker_pk,
+        unbonding_time,
+    )?;
+
+    /*
+        Check staker signature against slashing path of the unbonding tx
+    */
+    // get unbonding slashing path script
+    let unbonding_slashing_path_script = babylon_unbonding_script_paths.slashing_path_script;
+    // get the staker's signature on the unbonding slashing tx
+    let unbonding_slashing_sig = active_delegation
+        .undelegation_info
+        .delegator_slashing_sig
+        .as_slice();
+    let unbonding_slashing_sig = Signature::try_from(unbonding_slashing_sig)
         .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-
-        /*
-            verify covenant signatures over unbonding tx
-        */
-        let unbonding_path_script = babylon_script_paths.unbonding_path_script;
-        for cov_sig in active_delegation
+    // Verify the staker's signature
+    babylon_btcstaking::sig_verify::verify_transaction_sig_with_output(
+        &unbonding_slashing_tx,
+        unbonding_output,
+        unbonding_slashing_path_script.as_script(),
+        &staker_pk,
+        &unbonding_slashing_sig,
+    )
+    .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
+
+    /*
+        verify covenant signatures over unbonding tx
+    */
+    let unbonding_path_script = babylon_script_paths.unbonding_path_script;
+    check_covenant_quorum(
+        active_delegation
             .undelegation_info
             .covenant_unbonding_sig_list
             .iter()
-        {
-            // get covenant public key
-            let cov_pk = VerifyingKey::from_bytes(&cov_sig.pk)
-                .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-            // ensure covenant public key is in the params
-            if !params
-                .covenant_pks
-                .contains(&hex::encode(cov_pk.to_bytes()))
-            {
-                return Err(ContractError::InvalidCovenantSig(
-                    "Covenant public key not found in params".to_string(),
-                ));
-            }
-            // get covenant signature
-            let sig = Signature::try_from(cov_sig.sig.as_slice())
-                .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-            // Verify the covenant member's signature
-            babylon_btcstaking::sig_verify::verify_transaction_sig_with_output(
-                &staking_tx,
-                &staking_output,
-                unbonding_path_script.as_script(),
-                &cov_pk,
-                &sig,
-            )
+            .map(|cov_sig| cov_sig.pk.as_slice()),
+        params.covenant_quorum,
+        ContractError::InsufficientCovenantUnbondingSigs,
+    )?;
+    for cov_sig in active_delegation
+        .undelegation_info
+        .covenant_unbonding_sig_list
+        .iter()
+    {
+        // get covenant public key
+        let cov_pk = VerifyingKey::from_bytes(&cov_sig.pk)
             .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
+        // ensure covenant public key is in the params
+        if !params
+            .covenant_pks
+            .contains(&hex::encode(cov_pk.to_bytes()))
+        {
+            return Err(ContractError::InvalidCovenantSig(
+                "Covenant public key not found in params".to_string(),
+            ));
         }
+        // get covenant signature
+        let sig = Signature::try_from(cov_sig.sig.as_slice())
+            .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
+        // Verify the covenant member's signature
+        babylon_btcstaking::sig_verify::verify_transaction_sig_with_output(
+            &unbonding_tx,
+            staking_output,
+            unbonding_path_script.as_script(),
+            &cov_pk,
+            &sig,
+        )
+        .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
+    }
 
-        /*
-            Verify covenant signatures over unbonding slashing tx
-        */
-        for cov_sig in active_delegation
+    /*
+        Verify covenant signatures over unbonding slashing tx
+    */
+    check_covenant_quorum(
+        active_delegation
             .undelegation_info
             .covenant_slashing_sigs
             .iter()
+            .map(|cov_sig| cov_sig.cov_pk.as_slice()),
+        params.covenant_quorum,
+        ContractError::InsufficientCovenantUnbondingSlashingSigs,
+    )?;
+    for cov_sig in active_delegation
+        .undelegation_info
+        .covenant_slashing_sigs
+        .iter()
+    {
+        let cov_pk = VerifyingKey::from_bytes(&cov_sig.cov_pk)
+            .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
+        // Check if the covenant public key is in the params.covenant_pks
+        if !params
+            .covenant_pks
+            .contains(&hex::encode(cov_sig.cov_pk.as_slice()))
         {
-            let cov_pk = VerifyingKey::from_bytes(&cov_sig.cov_pk)
-                .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-            // Check if the covenant public key is in the params.covenant_pks
-            if !params
-                .covenant_pks
-                .contains(&hex::encode(cov_sig.cov_pk.as_slice()))
-            {
-                return Err(ContractError::InvalidCovenantSig(
-                    "Covenant public key not found in params".to_string(),
-                ));
-            }
-            let sigs = cov_sig
-                .adaptor_sigs
-                .iter()
-                .map(|sig| {
-                    AdaptorSignature::new(sig.as_slice())
-                        .map_err(|e| ContractError::SecP256K1Error(e.to_string()))
-                })
-                .collect::<Result<Vec<AdaptorSignature>, ContractError>>()?;
-            for (idx, sig) in sigs.iter().enumerate() {
-                enc_verify_transaction_sig_with_output(
-                    &unbonding_slashing_tx,
-                    unbonding_output,
-                    &unbonding_slashing_path_script.as_script(),
-                    &cov_pk,
-                    &fp_pks[idx],
-                    &sig,
-                )
-                .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-            }
+            return Err(ContractError::InvalidCovenantSig(
+                "Covenant public key not found in params".to_string(),
+            ));
+        }
+        // There must be one adaptor signature per finality provider
+        if cov_sig.adaptor_sigs.len() != fp_pks.len() {
+            return Err(ContractError::InvalidCovenantSig(format!(
+                "{} adaptor signatures for {} finality providers",
+                cov_sig.adaptor_sigs.len(),
+                fp_pks.len()
+            )));
+        }
+        let sigs = cov_sig
+            .adaptor_sigs
+            .iter()
+            .map(|sig| {
+                AdaptorSignature::new(sig.as_slice())
+                    .map_err(|e| ContractError::SecP256K1Error(e.to_string()))
+            })
+            .collect::<Result<Vec<AdaptorSignature>, ContractError>>()?;
+        for (idx, sig) in sigs.iter().enumerate() {
+            enc_verify_transaction_sig_with_output(
+                &unbonding_slashing_tx,
+                unbonding_output,
+                unbonding_slashing_path_script.as_script(),
+                &cov_pk,
+                &fp_pks[idx],
+                sig,
+            )
+            .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
         }
     }
 
-    // make static analyser happy with unused parameters
-    #[cfg(not(feature = "full-validation"))]
-    let _ = (params, active_delegation, staking_tx);
-
     Ok(())
 }
 
+/// verify_undelegation verifies the staker's signature on the unbonding tx
 pub fn verify_undelegation(
     params: &Params,
     btc_del: &BtcDelegation,
     sig: &Binary,
 ) -> Result<(), ContractError> {
-    // The following code is marked with `#[cfg(feature = "full-validation")]`
-    // so that it is included in the build if the `full-validation` feature is
-    // enabled.
-    // TODO: fix contract size when full-validation is enabled
-    #[cfg(feature = "full-validation")]
-    {
-        /*
-            Verify the signature on the unbonding tx is from the delegator
-        */
-
-        // get keys
-        let (staker_pk, fp_pks, cov_pks) = decode_pks(
-            &btc_del.btc_pk_hex,
-            &btc_del.fp_btc_pk_list,
-            &params.covenant_pks,
-        )?;
-
-        // get the unbonding path script
-        let staking_tx: Transaction = deserialize(&btc_del.staking_tx)
-            .map_err(|_| ContractError::InvalidBtcTx(btc_del.staking_tx.encode_hex()))?;
-        let staking_output = &staking_tx.output[btc_del.staking_output_idx as usize];
-        let staking_time = (btc_del.end_height - btc_del.start_height) as u16;
-        let babylon_script_paths = babylon_btcstaking::scripts_utils::BabylonScriptPaths::new(
-            &staker_pk,
-            &fp_pks,
-            &cov_pks,
-            params.covenant_quorum as usize,
-            staking_time,
-        )?;
-        let unbonding_path_script = babylon_script_paths.unbonding_path_script;
-
-        // get unbonding tx
-        let unbonding_tx: Transaction = deserialize(&btc_del.undelegation_info.unbonding_tx)
-            .map_err(|_| {
-                ContractError::InvalidBtcTx(btc_del.undelegation_info.unbonding_tx.encode_hex())
-            })?;
-
-        // get the staker's signature on the unbonding tx
-        let staker_sig = k256::schnorr::Signature::try_from(sig.as_slice())
-            .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-
-        // Verify the signature
-        babylon_btcstaking::sig_verify::verify_transaction_sig_with_output(
-            &unbonding_tx,
-            staking_output,
-            unbonding_path_script.as_script(),
-            &staker_pk,
-            &staker_sig,
-        )
+    /*
+        Verify the signature on the unbonding tx is from the delegator
+    */
+
+    // get keys
+    let (staker_pk, fp_pks, cov_pks) = decode_pks(
+        &btc_del.btc_pk_hex,
+        &btc_del.fp_btc_pk_list,
+        &params.covenant_pks,
+    )?;
+
+    // get the unbonding path script
+    let staking_tx: Transaction = deserialize(&btc_del.staking_tx)
+        .map_err(|_| ContractError::InvalidBtcTx(btc_del.staking_tx.encode_hex()))?;
+    let staking_output = &staking_tx.output[btc_del.staking_output_idx as usize];
+    let staking_time = (btc_del.end_height - btc_del.start_height) as u16;
+    let babylon_script_paths = babylon_btcstaking::scripts_utils::BabylonScriptPaths::new(
+        &staker_pk,
+        &fp_pks,
+        &cov_pks,
+        params.covenant_quorum as usize,
+        staking_time,
+    )?;
+    let unbonding_path_script = babylon_script_paths.unbonding_path_script;
+
+    // get unbonding tx
+    let unbonding_tx: Transaction =
+        deserialize(&btc_del.undelegation_info.unbonding_tx).map_err(|_| {
+            ContractError::InvalidBtcTx(btc_del.undelegation_info.unbonding_tx.encode_hex())
+        })?;
+
+    // get the staker's signature on the unbonding tx
+    let staker_sig = Signature::try_from(sig.as_slice())
         .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-    }
 
-    // make static analyser happy with unused parameters
-    #[cfg(not(feature = "full-validation"))]
-    let _ = (params, btc_del, sig);
+    // Verify the signature
+    babylon_btcstaking::sig_verify::verify_transaction_sig_with_output(
+        &unbonding_tx,
+        staking_output,
+        unbonding_path_script.as_script(),
+        &staker_pk,
+        &staker_sig,
+    )
+    .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
 
     Ok(())
 }
 
+/// verify_slashed_delegation verifies the slashed FP's SK corresponds to a FP PK that the
+/// delegation restakes to
 pub fn verify_slashed_delegation(
     active_delegation: &BtcDelegation,
     slashed_fp_sk_hex: &str,
 ) -> Result<(), ContractError> {
-    // The following code is marked with `#[cfg(feature = "full-validation")]`
-    // so that it is included in the build if the `full-validation` feature is
-    // enabled.
-    // TODO: fix contract size when full-validation is enabled
-    #[cfg(feature = "full-validation")]
-    {
-        /*
-            check if the SK corresponds to a FP PK that the delegation restakes to
-        */
-
-        // get the slashed FP's SK
-        let slashed_fp_sk = hex::decode(&slashed_fp_sk_hex)
-            .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
-        let slashed_fp_sk = SigningKey::from_bytes(&slashed_fp_sk)
-            .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
+    /*
+        check if the SK corresponds to a FP PK that the delegation restakes to
+    */
+
+    // get the slashed FP's SK
+    let slashed_fp_sk =
+        hex::decode(slashed_fp_sk_hex).map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
+    let slashed_fp_sk = SigningKey::from_bytes(&slashed_fp_sk)
+        .map_err(|e| ContractError::SecP256K1Error(e.to_string()))?;
 
-        // calculate the corresponding VerifyingKey
-        let slashed_fp_pk = slashed_fp_sk.verifying_key();
-        let slashed_fp_pk_hex = hex::encode(slashed_fp_pk.to_bytes());
+    // calculate the corresponding VerifyingKey
+    let slashed_fp_pk = slashed_fp_sk.verifying_key();
+    let slashed_fp_pk_hex = hex::encode(slashed_fp_pk.to_bytes());
 
-        // check if the PK corresponds to a FP PK that the delegation restakes to
-        if !active_delegation
-            .fp_btc_pk_list
-            .contains(&slashed_fp_pk_hex)
-        {
-            return Err(ContractError::FinalityProviderNotFound(
-                slashed_fp_pk_hex.to_string(),
-            ));
-        }
+    // check if the PK corresponds to a FP PK that the delegation restakes to
+    if !active_delegation
+        .fp_btc_pk_list
+        .contains(&slashed_fp_pk_hex)
+    {
+        return Err(ContractError::FinalityProviderNotFound(
+            slashed_fp_pk_hex.to_string(),
+        ));
     }
 
-    // make static analyser happy with unused parameters
-    #[cfg(not(feature = "full-validation"))]
-    let _ = (active_delegation, slashed_fp_sk_hex);
-
     Ok(())
 }
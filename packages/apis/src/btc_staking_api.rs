@@ -1,8 +1,10 @@
 /// BTC staking messages / API
 /// The definitions here follow the same structure as the equivalent IBC protobuf message types,
 /// defined in `packages/proto/src/gen/babylon.btcstaking.v1.rs`
+use babylon_bitcoin::chain_params::Network;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Binary, Decimal};
+use derivative::Derivative;
 
 /// Hash size in bytes
 pub const HASH_SIZE: usize = 32;
@@ -18,12 +20,99 @@ pub enum ExecuteMsg {
         active_del: Vec<ActiveBtcDelegation>,
         slashed_del: Vec<SlashedBtcDelegation>,
         unbonded_del: Vec<UnbondedBtcDelegation>,
+        /// `edit_fp` is optional, so that messages from senders unaware of it are still accepted
+        #[serde(default)]
+        edit_fp: Vec<EditFinalityProvider>,
+        /// `best_effort` processes the items in best-effort mode, i.e. invalid items are skipped
+        /// and reported in the response, while the rest are still applied.
+        /// By default, any invalid item fails the whole message
+        best_effort: Option<bool>,
     },
     /// Slash finality provider staking power.
     /// Used by the babylon-contract only.
     /// The Babylon contract will call this message to set the finality provider's staking power to
     /// zero when the finality provider is found to be malicious by the finality contract.
     Slash { fp_btc_pk_hex: String },
+    /// Update the BTC staking params.
+    /// Used by the admin or the babylon-contract only.
+    /// The new params are added as a new version, applying to the delegations starting from
+    /// `btc_activation_height` onwards.
+    /// `version` is the version Babylon assigns to the params, as delegations refer to it. It must
+    /// be the one following the latest version
+    UpdateParams {
+        version: u32,
+        params: Params,
+        btc_activation_height: u64,
+    },
+    /// BTC tip update.
+    /// Used by the babylon-contract only.
+    /// The Babylon contract will call this message whenever its BTC light client tip changes, so
+    /// that delegation status can be computed from the time-lock of the staking tx.
+    BtcTipUpdate {
+        /// `height` is the height of the new BTC tip
+        height: u64,
+        /// `checkpoint_finalization_timeout` is the number of BTC blocks a BTC delegation needs to
+        /// have left in its time-lock for it to be active (`w`)
+        checkpoint_finalization_timeout: u64,
+    },
+}
+
+/// Params define Consumer-selectable BTC staking parameters
+// TODO: Add / enable param entries as needed
+#[cw_serde]
+#[derive(Derivative)]
+#[derivative(Default)]
+pub struct Params {
+    // covenant_pks is the list of public keys held by the covenant committee each PK
+    // follows encoding in BIP-340 spec on Bitcoin
+    pub covenant_pks: Vec<String>,
+    // covenant_quorum is the minimum number of signatures needed for the covenant multi-signature
+    pub covenant_quorum: u32,
+    #[derivative(Default(value = "Network::Regtest"))]
+    // ntc_network is the network the BTC staking protocol is running on
+    pub btc_network: Network,
+    /// `min_commission_rate` is the chain-wide minimum commission rate that a finality provider
+    /// can charge their delegators
+    pub min_commission_rate: Decimal,
+    /// `max_commission_change_rate` is the maximum change of the commission rate of a finality
+    /// provider in a single edit
+    #[derivative(Default(value = "Decimal::one()"))]
+    pub max_commission_change_rate: Decimal,
+    /// `slashing_address` is the address that the slashed BTC goes to.
+    /// The address is in string format on Bitcoin.
+    #[derivative(Default(value = "String::from(\"n4cV57jePmAAue2WTTBQzH3k3R2rgWBQwY\")"))]
+    pub slashing_address: String,
+    /// `min_slashing_tx_fee_sat` is the minimum amount of tx fee (quantified in Satoshi) needed for
+    /// the pre-signed slashing tx
+    #[derivative(Default(value = "1000"))]
+    pub min_slashing_tx_fee_sat: u64,
+    /// `slashing_rate` determines the portion of the staked amount to be slashed,
+    /// expressed as a decimal (e.g. 0.5 for 50%).
+    #[derivative(Default(value = "Decimal::percent(10)"))]
+    pub slashing_rate: Decimal,
+    /// `min_unbonding_time` is the minimum time-lock of the unbonding tx, in BTC blocks.
+    /// The effective minimum is the larger of this and the checkpoint finalization timeout
+    pub min_unbonding_time: u32,
+    /// `min_unbonding_rate` is the minimum portion of the staked amount that the unbonding
+    /// output must carry, expressed as a decimal (e.g. 0.8 for 80%).
+    #[derivative(Default(value = "Decimal::percent(80)"))]
+    pub min_unbonding_rate: Decimal,
+    /// `unbonding_fee_sat` is the minimum amount of tx fee (quantified in Satoshi) needed for
+    /// the unbonding tx
+    #[derivative(Default(value = "1000"))]
+    pub unbonding_fee_sat: u64,
+    /// `max_fps_per_delegation` is the maximum number of finality providers (across Babylon and
+    /// all Consumers) that a BTC delegation can restake to.
+    /// The delegation is restaked, not split: each of its finality providers gets the full
+    /// staked amount as voting power
+    #[derivative(Default(value = "5"))]
+    pub max_fps_per_delegation: u32,
+    /// `require_staking_tx_inclusion_proof` requires active delegations to carry the inclusion
+    /// proof of their staking tx, which is verified against the BTC headers of the babylon
+    /// contract.
+    /// Off by default, as Babylon does not send the proof yet (see the README), and only relays
+    /// delegations whose staking tx is k-deep
+    pub require_staking_tx_inclusion_proof: bool,
 }
 
 #[cw_serde]
@@ -79,6 +168,20 @@ impl From<&NewFinalityProvider> for FinalityProvider {
     }
 }
 
+/// EditFinalityProvider edits the description and / or commission of an existing finality
+/// provider.
+/// The fields that are not set are left unchanged
+#[cw_serde]
+pub struct EditFinalityProvider {
+    /// btc_pk_hex is the Bitcoin secp256k1 PK of the finality provider to edit
+    /// the PK follows encoding in BIP-340 spec in hex format
+    pub btc_pk_hex: String,
+    /// description defines the new description terms for the finality provider
+    pub description: Option<FinalityProviderDescription>,
+    /// commission defines the new commission rate of the finality provider
+    pub commission: Option<Decimal>,
+}
+
 #[cw_serde]
 pub struct FinalityProviderDescription {
     /// moniker is the name of the finality provider
@@ -199,6 +302,24 @@ pub struct ActiveBtcDelegation {
     pub undelegation_info: BtcUndelegationInfo,
     /// params version used to validate the delegation
     pub params_version: u32,
+    /// staking_tx_inclusion_proof is the inclusion proof of the staking tx in a BTC block.
+    /// If set, it is verified against the BTC headers of the babylon contract, the block is
+    /// required to be at the delegation's start height, and the staking tx to be k-deep.
+    /// It is mandatory if the params version enables `require_staking_tx_inclusion_proof`
+    pub staking_tx_inclusion_proof: Option<InclusionProof>,
+}
+
+/// InclusionProof proves the inclusion of a tx in a BTC block
+#[cw_serde]
+pub struct InclusionProof {
+    /// block_hash_hex is the hash of the BTC block including the tx.
+    /// Encoded as a (byte-reversed) hex string
+    pub block_hash_hex: String,
+    /// index is the index of the tx in the block
+    pub index: u32,
+    /// proof is the Merkle proof of the tx in the block, i.e. the concatenation of the 32-byte
+    /// sibling hashes from the tx up to the Merkle root
+    pub proof: Binary,
 }
 
 /// CovenantAdaptorSignatures is a list adaptor signatures signed by the
@@ -266,3 +387,43 @@ pub struct UnbondedBtcDelegation {
     /// It proves that the BTC delegator wants to unbond
     pub unbonding_tx_sig: Binary,
 }
+
+/// BtcStakingResponse is the response data of the `BtcStaking` message.
+/// It contains the processing result of each item, in processing order
+#[cw_serde]
+pub struct BtcStakingResponse {
+    pub results: Vec<BtcStakingItemResult>,
+}
+
+/// BtcStakingItemKind is the kind of an item of a `BtcStaking` message
+#[cw_serde]
+pub enum BtcStakingItemKind {
+    NewFp,
+    ActiveDel,
+    SlashedDel,
+    UnbondedDel,
+    EditFp,
+}
+
+impl BtcStakingItemKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BtcStakingItemKind::NewFp => "new_fp",
+            BtcStakingItemKind::ActiveDel => "active_del",
+            BtcStakingItemKind::SlashedDel => "slashed_del",
+            BtcStakingItemKind::UnbondedDel => "unbonded_del",
+            BtcStakingItemKind::EditFp => "edit_fp",
+        }
+    }
+}
+
+/// BtcStakingItemResult is the processing result of an item of a `BtcStaking` message
+#[cw_serde]
+pub struct BtcStakingItemResult {
+    pub kind: BtcStakingItemKind,
+    /// `id` identifies the item, i.e. the BTC PK (hex) of a new or edited finality provider, or
+    /// the staking tx hash of a delegation
+    pub id: String,
+    /// `error` is the reason the item was rejected, if any
+    pub error: Option<String>,
+}
@@ -1,7 +1,7 @@
 use hex::FromHexError;
 use thiserror::Error;
 
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError};
 
 #[derive(Error, Debug, PartialEq)]
 pub enum StakingApiError {
@@ -39,6 +39,8 @@ pub enum StakingApiError {
     EmptySignature,
     #[error("Description error: {0}")]
     DescriptionErr(String),
+    #[error("Commission rate {0} is not in [0, 1]")]
+    InvalidCommission(Decimal),
 }
 
 impl StakingApiError {
@@ -3,6 +3,7 @@
 /// defined in `packages/proto/src/gen/babylon.finality.v1.rs`
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Binary;
+use derivative::Derivative;
 
 use babylon_merkle::Proof;
 
@@ -16,6 +17,10 @@ pub enum ExecuteMsg {
     /// Set the BTC staking addr.
     /// Only admin or the babylon contract can set this
     UpdateStaking { staking: String },
+    /// Update the BTC finality params.
+    /// Only admin or the babylon contract can update them.
+    /// The new params apply from the next block onwards
+    UpdateParams { params: Params },
     /// Committing a sequence of public randomness for EOTS
     CommitPublicRandomness {
         /// `fp_pubkey_hex` is the BTC PK of the finality provider that commits the public randomness
@@ -50,6 +55,21 @@ pub enum ExecuteMsg {
     },
 }
 
+// TODO: Add / enable param entries as needed
+#[cw_serde]
+#[derive(Derivative)]
+#[derivative(Default)]
+pub struct Params {
+    /// `max_active_finality_providers` is the maximum number of active finality providers in the
+    /// BTC staking protocol
+    #[derivative(Default(value = "100"))]
+    pub max_active_finality_providers: u32,
+    /// `min_pub_rand` is the minimum amount of public randomness each public randomness commitment
+    /// should commit
+    #[derivative(Default(value = "1"))]
+    pub min_pub_rand: u64,
+}
+
 /// `IndexedBlock` is the necessary metadata and finalization status of a block
 #[cw_serde]
 pub struct IndexedBlock {
@@ -1,10 +1,10 @@
 use babylon_bitcoin::{deserialize, Transaction};
 
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError};
 
 use crate::btc_staking_api::{
-    ActiveBtcDelegation, FinalityProviderDescription, NewFinalityProvider, ProofOfPossessionBtc,
-    SlashedBtcDelegation, UnbondedBtcDelegation, HASH_SIZE,
+    ActiveBtcDelegation, EditFinalityProvider, FinalityProviderDescription, NewFinalityProvider,
+    ProofOfPossessionBtc, SlashedBtcDelegation, UnbondedBtcDelegation, HASH_SIZE,
 };
 use crate::error::StakingApiError;
 
@@ -20,6 +20,11 @@ impl Validate for NewFinalityProvider {
             .map(FinalityProviderDescription::validate)
             .transpose()?;
 
+        // The commission rate must be a proper rate
+        if self.commission > Decimal::one() {
+            return Err(StakingApiError::InvalidCommission(self.commission));
+        }
+
         if self.btc_pk_hex.is_empty() {
             return Err(StakingApiError::EmptyBtcPk);
         }
@@ -45,6 +50,30 @@ impl Validate for NewFinalityProvider {
     }
 }
 
+impl Validate for EditFinalityProvider {
+    fn validate(&self) -> Result<(), StakingApiError> {
+        if self.btc_pk_hex.is_empty() {
+            return Err(StakingApiError::EmptyBtcPk);
+        }
+
+        let _btc_pk = hex::decode(&self.btc_pk_hex)?;
+
+        self.description
+            .as_ref()
+            .map(FinalityProviderDescription::validate)
+            .transpose()?;
+
+        // The commission rate must be a proper rate
+        if let Some(commission) = self.commission {
+            if commission > Decimal::one() {
+                return Err(StakingApiError::InvalidCommission(commission));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl Validate for FinalityProviderDescription {
     fn validate(&self) -> Result<(), StakingApiError> {
         if self.moniker.is_empty() {
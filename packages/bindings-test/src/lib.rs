@@ -2,5 +2,5 @@ mod multitest;
 
 pub use multitest::{
     mock_deps_babylon, BabylonApp, BabylonAppWrapped, BabylonDeps, BabylonError, BabylonModule,
-    BLOCK_TIME,
+    EmptyQueryContract, BLOCK_TIME,
 };
@@ -12,15 +12,19 @@ use std::marker::PhantomData;
 
 use cosmwasm_std::Order::Ascending;
 use cosmwasm_std::{
-    Addr, Api, Binary, BlockInfo, CustomQuery, Empty, Querier, QuerierResult, StdError, StdResult,
+    to_json_binary, Addr, Api, Binary, BlockInfo, CustomMsg, CustomQuery, Deps, DepsMut, Empty,
+    Env, MessageInfo, Querier, QuerierResult, QuerierWrapper, Reply, Response, StdError, StdResult,
     Storage, Timestamp,
 };
 use cw_multi_test::{
-    App, AppResponse, BankKeeper, BasicAppBuilder, CosmosRouter, Module, WasmKeeper,
+    App, AppResponse, BankKeeper, BasicAppBuilder, Contract, CosmosRouter, Module, WasmKeeper,
 };
-use cw_storage_plus::{Item, Map};
+use cw_storage_plus::{Bound, Item, Map};
 
-use babylon_bindings::{BabylonMsg, BabylonQuery};
+use babylon_bindings::{
+    BabylonMsg, BabylonQuery, BtcTipResponse, CurrentEpochResponse, FinalityProviderResponse,
+    VotingPowerResponse,
+};
 
 pub struct BabylonModule {}
 
@@ -31,6 +35,11 @@ pub const BLOCK_TIME: u64 = 5;
 const PINNED: Item<Vec<u64>> = Item::new("pinned");
 // const PLANNED_UPGRADE: Item<UpgradePlan> = Item::new("planned_upgrade");
 const PARAMS: Map<String, String> = Map::new("params");
+/// Mocked Consumer chain state, answering `BabylonQuery`s
+const FINALITY_PROVIDERS: Map<&str, FinalityProviderResponse> = Map::new("finality_providers");
+const VOTING_POWERS: Map<(&str, u64), u64> = Map::new("voting_powers");
+const BTC_TIP: Item<BtcTipResponse> = Item::new("btc_tip");
+const CURRENT_EPOCH: Item<CurrentEpochResponse> = Item::new("current_epoch");
 
 pub type BabylonDeps = OwnedDeps<MockStorage, MockApi, MockQuerier, BabylonQuery>;
 
@@ -63,11 +72,63 @@ impl BabylonModule {
     pub fn get_params(&self, storage: &dyn Storage) -> StdResult<Vec<(String, String)>> {
         PARAMS.range(storage, None, None, Ascending).collect()
     }
+
+    pub fn set_finality_provider(
+        &self,
+        storage: &mut dyn Storage,
+        fp: &FinalityProviderResponse,
+    ) -> StdResult<()> {
+        FINALITY_PROVIDERS.save(storage, &fp.btc_pk_hex, fp)
+    }
+
+    /// Sets the voting power of the finality provider from the given height on
+    pub fn set_voting_power(
+        &self,
+        storage: &mut dyn Storage,
+        btc_pk_hex: &str,
+        height: u64,
+        voting_power: u64,
+    ) -> StdResult<()> {
+        VOTING_POWERS.save(storage, (btc_pk_hex, height), &voting_power)
+    }
+
+    pub fn set_btc_tip(&self, storage: &mut dyn Storage, tip: &BtcTipResponse) -> StdResult<()> {
+        BTC_TIP.save(storage, tip)
+    }
+
+    pub fn set_current_epoch(
+        &self,
+        storage: &mut dyn Storage,
+        epoch: &CurrentEpochResponse,
+    ) -> StdResult<()> {
+        CURRENT_EPOCH.save(storage, epoch)
+    }
+
+    fn voting_power_at(
+        &self,
+        storage: &dyn Storage,
+        btc_pk_hex: &str,
+        height: u64,
+    ) -> StdResult<u64> {
+        let voting_power = VOTING_POWERS
+            .prefix(btc_pk_hex)
+            .range(
+                storage,
+                None,
+                Some(Bound::inclusive(height)),
+                cosmwasm_std::Order::Descending,
+            )
+            .next()
+            .transpose()?
+            .map(|(_, voting_power)| voting_power)
+            .unwrap_or_default();
+        Ok(voting_power)
+    }
 }
 
 impl Module for BabylonModule {
     type ExecT = BabylonMsg;
-    type QueryT = Empty;
+    type QueryT = BabylonQuery;
     type SudoT = Empty;
 
     fn execute<ExecC, QueryC>(
@@ -94,12 +155,23 @@ impl Module for BabylonModule {
     fn query(
         &self,
         _api: &dyn Api,
-        _storage: &dyn Storage,
+        storage: &dyn Storage,
         _querier: &dyn Querier,
         _block: &BlockInfo,
-        _request: BabylonQuery,
+        request: BabylonQuery,
     ) -> anyhow::Result<Binary> {
-        bail!("query not implemented for BabylonModule")
+        match request {
+            BabylonQuery::FinalityProvider { btc_pk_hex } => {
+                let fp = FINALITY_PROVIDERS.load(storage, &btc_pk_hex)?;
+                Ok(to_json_binary(&fp)?)
+            }
+            BabylonQuery::VotingPowerAt { btc_pk_hex, height } => {
+                let voting_power = self.voting_power_at(storage, &btc_pk_hex, height)?;
+                Ok(to_json_binary(&VotingPowerResponse { voting_power })?)
+            }
+            BabylonQuery::BtcTip {} => Ok(to_json_binary(&BTC_TIP.load(storage)?)?),
+            BabylonQuery::CurrentEpoch {} => Ok(to_json_binary(&CURRENT_EPOCH.load(storage)?)?),
+        }
     }
 
     fn sudo<ExecC, QueryC>(
@@ -118,6 +190,87 @@ impl Module for BabylonModule {
     }
 }
 
+/// EmptyQueryContract adapts a contract that doesn't send custom queries (i.e. whose entry
+/// points take `Deps<Empty>`) so that it can be stored in a `BabylonApp`
+pub struct EmptyQueryContract<C: CustomMsg>(Box<dyn Contract<C, Empty>>);
+
+impl<C: CustomMsg> EmptyQueryContract<C> {
+    pub fn new(contract: impl Contract<C, Empty> + 'static) -> Self {
+        Self(Box::new(contract))
+    }
+}
+
+fn decustomize_deps_mut<'a>(deps: &'a mut DepsMut<BabylonQuery>) -> DepsMut<'a, Empty> {
+    DepsMut {
+        storage: deps.storage,
+        api: deps.api,
+        querier: QuerierWrapper::new(deps.querier.deref()),
+    }
+}
+
+fn decustomize_deps<'a>(deps: &'a Deps<'a, BabylonQuery>) -> Deps<'a, Empty> {
+    Deps {
+        storage: deps.storage,
+        api: deps.api,
+        querier: QuerierWrapper::new(deps.querier.deref()),
+    }
+}
+
+impl<C: CustomMsg> Contract<C, BabylonQuery> for EmptyQueryContract<C> {
+    fn execute(
+        &self,
+        mut deps: DepsMut<BabylonQuery>,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response<C>> {
+        self.0
+            .execute(decustomize_deps_mut(&mut deps), env, info, msg)
+    }
+
+    fn instantiate(
+        &self,
+        mut deps: DepsMut<BabylonQuery>,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response<C>> {
+        self.0
+            .instantiate(decustomize_deps_mut(&mut deps), env, info, msg)
+    }
+
+    fn query(&self, deps: Deps<BabylonQuery>, env: Env, msg: Vec<u8>) -> AnyResult<Binary> {
+        self.0.query(decustomize_deps(&deps), env, msg)
+    }
+
+    fn sudo(
+        &self,
+        mut deps: DepsMut<BabylonQuery>,
+        env: Env,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response<C>> {
+        self.0.sudo(decustomize_deps_mut(&mut deps), env, msg)
+    }
+
+    fn reply(
+        &self,
+        mut deps: DepsMut<BabylonQuery>,
+        env: Env,
+        msg: Reply,
+    ) -> AnyResult<Response<C>> {
+        self.0.reply(decustomize_deps_mut(&mut deps), env, msg)
+    }
+
+    fn migrate(
+        &self,
+        mut deps: DepsMut<BabylonQuery>,
+        env: Env,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response<C>> {
+        self.0.migrate(decustomize_deps_mut(&mut deps), env, msg)
+    }
+}
+
 #[derive(Error, Debug, PartialEq)]
 pub enum BabylonError {
     #[error("{0}")]
@@ -367,3 +520,63 @@ mod tests {
     // TODO: Delegate / Undelegate tests
 }
 */
+
+#[cfg(test)]
+mod query_tests {
+    use super::*;
+
+    #[test]
+    fn mocked_babylon_queries_work() {
+        let mut app = BabylonApp::new("owner");
+        app.init_modules(|router, _, storage| {
+            router
+                .custom
+                .set_voting_power(storage, "fp1", 10, 100)
+                .unwrap();
+            router
+                .custom
+                .set_voting_power(storage, "fp1", 20, 0)
+                .unwrap();
+            router
+                .custom
+                .set_btc_tip(
+                    storage,
+                    &BtcTipResponse {
+                        height: 42,
+                        hash: "00".repeat(32),
+                    },
+                )
+                .unwrap();
+        });
+
+        let voting_power = |height| {
+            app.wrap()
+                .query::<VotingPowerResponse>(
+                    &BabylonQuery::VotingPowerAt {
+                        btc_pk_hex: "fp1".to_string(),
+                        height,
+                    }
+                    .into(),
+                )
+                .unwrap()
+                .voting_power
+        };
+        assert_eq!(voting_power(5), 0);
+        assert_eq!(voting_power(10), 100);
+        assert_eq!(voting_power(15), 100);
+        assert_eq!(voting_power(25), 0);
+
+        let tip: BtcTipResponse = app.wrap().query(&BabylonQuery::BtcTip {}.into()).unwrap();
+        assert_eq!(tip.height, 42);
+
+        // unknown finality providers are not found
+        app.wrap()
+            .query::<FinalityProviderResponse>(
+                &BabylonQuery::FinalityProvider {
+                    btc_pk_hex: "fp2".to_string(),
+                }
+                .into(),
+            )
+            .unwrap_err();
+    }
+}
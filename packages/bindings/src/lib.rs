@@ -1,3 +1,8 @@
 pub mod msg;
+pub mod query;
 
-pub use msg::{BabylonMsg, BabylonQuery, BabylonSudoMsg};
+pub use msg::{BabylonMsg, BabylonSudoMsg};
+pub use query::{
+    BabylonQuery, BtcTipResponse, CurrentEpochResponse, FinalityProviderResponse,
+    VotingPowerResponse,
+};
@@ -20,7 +20,6 @@ pub enum BabylonMsg {
 }
 
 pub type BabylonSudoMsg = Empty;
-pub type BabylonQuery = Empty;
 
 // make BabylonMsg to implement CosmosMsg::CustomMsg
 impl cosmwasm_std::CustomMsg for BabylonMsg {}
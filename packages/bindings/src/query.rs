@@ -0,0 +1,72 @@
+//! query::bindings is the module that includes custom queries that contracts can send to the
+//! Babylon module of the Cosmos zone. The queries include:
+//! - FinalityProvider: the metadata and slashing status of a finality provider
+//! - VotingPowerAt: the voting power of a finality provider at a given height
+//! - BtcTip: the tip of the BTC light client
+//! - CurrentEpoch: the current Babylon epoch
+
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::CustomQuery;
+
+/// BabylonQuery is the query that contracts can send to the Cosmos zone.
+/// The Cosmos zone has to integrate https://github.com/babylonlabs-io/wasmbinding for
+/// handling these queries
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum BabylonQuery {
+    /// FinalityProvider returns the finality provider with the given BTC PK
+    #[returns(FinalityProviderResponse)]
+    FinalityProvider { btc_pk_hex: String },
+    /// VotingPowerAt returns the voting power of the finality provider with the given BTC PK
+    /// at the given (Consumer) height
+    #[returns(VotingPowerResponse)]
+    VotingPowerAt { btc_pk_hex: String, height: u64 },
+    /// BtcTip returns the tip of the BTC light client
+    #[returns(BtcTipResponse)]
+    BtcTip {},
+    /// CurrentEpoch returns the current Babylon epoch
+    #[returns(CurrentEpochResponse)]
+    CurrentEpoch {},
+}
+
+#[cw_serde]
+pub struct FinalityProviderResponse {
+    /// `btc_pk_hex` is the Bitcoin secp256k1 PK of the finality provider, in hex format
+    pub btc_pk_hex: String,
+    /// `slashed_babylon_height` is the Babylon height when the finality provider was slashed.
+    /// 0 if the finality provider is not slashed
+    pub slashed_babylon_height: u64,
+    /// `slashed_btc_height` is the BTC height when the finality provider was slashed.
+    /// 0 if the finality provider is not slashed
+    pub slashed_btc_height: u64,
+    /// `height` is the Consumer height the finality provider's voting power is reported at
+    pub height: u64,
+    /// `voting_power` is the finality provider's voting power at `height`
+    pub voting_power: u64,
+    /// `consumer_id` is the ID of the Consumer the finality provider is securing
+    pub consumer_id: String,
+}
+
+#[cw_serde]
+pub struct VotingPowerResponse {
+    pub voting_power: u64,
+}
+
+#[cw_serde]
+pub struct BtcTipResponse {
+    /// `height` is the height of the BTC tip
+    pub height: u32,
+    /// `hash` is the hash of the BTC tip, in hex format
+    pub hash: String,
+}
+
+#[cw_serde]
+pub struct CurrentEpochResponse {
+    /// `current_epoch` is the current epoch number
+    pub current_epoch: u64,
+    /// `epoch_boundary` is the Babylon height of the last block of the current epoch
+    pub epoch_boundary: u64,
+}
+
+// make BabylonQuery to implement QueryRequest::CustomQuery
+impl CustomQuery for BabylonQuery {}
@@ -0,0 +1,362 @@
+//! BIP-322 generic signed message verification.
+//!
+//! Only the "simple" signature format is supported, for single-key P2WPKH and
+//! (key path spend) P2TR addresses.
+//! See https://github.com/bitcoin/bips/blob/master/bip-0322.mediawiki
+//!
+//! NOTE: key tweaking and signature verification are done with the k256 library,
+//! to avoid using the secp256k1 FFI that bloats the binary size
+use crate::error::Error;
+use crate::schnorr::verify_digest;
+use crate::Result;
+use bitcoin::absolute::LockTime;
+use bitcoin::blockdata::opcodes::all::OP_RETURN;
+use bitcoin::blockdata::opcodes::OP_0;
+use bitcoin::consensus::deserialize;
+use bitcoin::hashes::{hash160, Hash};
+use bitcoin::script::Builder;
+use bitcoin::sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType};
+use bitcoin::transaction::Version;
+use bitcoin::{
+    Address, Amount, OutPoint, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+};
+use k256::ecdsa::signature::hazmat::PrehashVerifier;
+use k256::elliptic_curve::group::prime::PrimeCurveAffine;
+use k256::elliptic_curve::ops::MulByGenerator;
+use k256::elliptic_curve::point::DecompressPoint;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::elliptic_curve::subtle::Choice;
+use k256::elliptic_curve::PrimeField;
+use k256::{AffinePoint, ProjectivePoint, Scalar};
+use sha2::{Digest, Sha256};
+
+const BIP322_TAG: &[u8] = b"BIP0322-signed-message";
+const TAP_TWEAK_TAG: &[u8] = b"TapTweak";
+
+fn tagged_hash(tag: &[u8], msg: &[u8]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag);
+    Sha256::new()
+        .chain_update(tag_hash)
+        .chain_update(tag_hash)
+        .chain_update(msg)
+        .finalize()
+        .into()
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidBip322Signature(reason.to_string())
+}
+
+/// hash_message computes the BIP-322 tagged hash of the given message
+pub fn hash_message(msg: &[u8]) -> [u8; 32] {
+    tagged_hash(BIP322_TAG, msg)
+}
+
+/// to_spend builds the virtual `to_spend` transaction committing to the message and
+/// the address' script pubkey
+pub fn to_spend(msg: &[u8], script_pubkey: &Script) -> Transaction {
+    let script_sig = Builder::new()
+        .push_opcode(OP_0)
+        .push_slice(hash_message(msg))
+        .into_script();
+    Transaction {
+        version: Version(0),
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: Txid::all_zeros(),
+                vout: 0xFFFFFFFF,
+            },
+            script_sig,
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: script_pubkey.to_owned(),
+        }],
+    }
+}
+
+/// to_sign builds the virtual `to_sign` transaction spending the `to_spend` transaction
+/// with the given witness
+pub fn to_sign(to_spend: &Transaction, witness: Witness) -> Transaction {
+    Transaction {
+        version: Version(0),
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: to_spend.txid(),
+                vout: 0,
+            },
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ZERO,
+            witness,
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: Builder::new().push_opcode(OP_RETURN).into_script(),
+        }],
+    }
+}
+
+/// verify_simple verifies a BIP-322 simple signature, i.e. a consensus-encoded witness,
+/// of the message by the given address
+pub fn verify_simple(msg: &[u8], address: &Address, sig: &[u8]) -> Result<()> {
+    let witness: Witness =
+        deserialize(sig).map_err(|e| Error::InvalidBip322Signature(e.to_string()))?;
+    verify(msg, address, &witness)
+}
+
+/// verify verifies the witness of the BIP-322 `to_sign` transaction of the message by
+/// the given address
+pub fn verify(msg: &[u8], address: &Address, witness: &Witness) -> Result<()> {
+    let script_pubkey = address.script_pubkey();
+    let to_spend = to_spend(msg, &script_pubkey);
+    let to_sign = to_sign(&to_spend, witness.clone());
+
+    if script_pubkey.is_p2wpkh() {
+        verify_p2wpkh(&to_sign, &script_pubkey, witness)
+    } else if script_pubkey.is_p2tr() {
+        verify_p2tr(&to_sign, &to_spend.output[0], witness)
+    } else {
+        Err(invalid("unsupported address type"))
+    }
+}
+
+fn verify_p2wpkh(to_sign: &Transaction, script_pubkey: &Script, witness: &Witness) -> Result<()> {
+    if witness.len() != 2 {
+        return Err(invalid("P2WPKH witness must have 2 elements"));
+    }
+    let sig_bytes = witness.nth(0).unwrap(); // checked above
+    let pk_bytes = witness.nth(1).unwrap(); // checked above
+
+    // the public key must be compressed and commit to the witness program
+    if pk_bytes.len() != 33 {
+        return Err(invalid("P2WPKH public key must be compressed"));
+    }
+    if hash160::Hash::hash(pk_bytes).as_byte_array() != &script_pubkey.as_bytes()[2..] {
+        return Err(invalid("public key does not match the address"));
+    }
+
+    let (sighash_type, der_sig) = sig_bytes
+        .split_last()
+        .ok_or_else(|| invalid("empty signature"))?;
+    let sighash_type = EcdsaSighashType::from_standard(*sighash_type as u32)
+        .map_err(|e| Error::InvalidBip322Signature(e.to_string()))?;
+    let sighash = SighashCache::new(to_sign)
+        .p2wpkh_signature_hash(0, script_pubkey, Amount::ZERO, sighash_type)
+        .map_err(|e| Error::InvalidBip322Signature(e.to_string()))?;
+
+    let pk = k256::ecdsa::VerifyingKey::from_sec1_bytes(pk_bytes)
+        .map_err(|e| Error::FailedToParsePublicKey(e.to_string()))?;
+    let sig = k256::ecdsa::Signature::from_der(der_sig)
+        .map_err(|e| Error::InvalidBip322Signature(e.to_string()))?;
+    pk.verify_prehash(sighash.as_byte_array(), &sig)
+        .map_err(|e| Error::InvalidBip322Signature(e.to_string()))
+}
+
+fn verify_p2tr(to_sign: &Transaction, prevout: &TxOut, witness: &Witness) -> Result<()> {
+    if witness.len() != 1 {
+        return Err(invalid("P2TR key path witness must have 1 element"));
+    }
+    let sig_bytes = witness.nth(0).unwrap(); // checked above
+
+    let (sig_bytes, sighash_type) = match sig_bytes.len() {
+        64 => (sig_bytes, TapSighashType::Default),
+        65 if sig_bytes[64] != 0 => (
+            &sig_bytes[..64],
+            TapSighashType::from_consensus_u8(sig_bytes[64])
+                .map_err(|e| Error::InvalidBip322Signature(e.to_string()))?,
+        ),
+        _ => return Err(invalid("invalid P2TR signature length")),
+    };
+    let sighash = SighashCache::new(to_sign)
+        .taproot_key_spend_signature_hash(0, &Prevouts::All(&[prevout]), sighash_type)
+        .map_err(|e| Error::InvalidBip322Signature(e.to_string()))?;
+
+    // the witness program is the tweaked output key
+    let output_key =
+        k256::schnorr::VerifyingKey::from_bytes(&prevout.script_pubkey.as_bytes()[2..])
+            .map_err(|e| Error::FailedToParsePublicKey(e.to_string()))?;
+    let sig = k256::schnorr::Signature::try_from(sig_bytes)
+        .map_err(|e| Error::InvalidSchnorrSignature(e.to_string()))?;
+    verify_digest(&output_key, sighash.as_byte_array(), &sig)
+}
+
+/// taproot_output_key computes the BIP-86 output key, i.e. the key tweaked without a
+/// script tree, of the given x-only internal key
+pub fn taproot_output_key(internal_key: &[u8; 32]) -> Result<[u8; 32]> {
+    // lift the internal key to the point with even y
+    let internal_point = Option::<AffinePoint>::from(AffinePoint::decompress(
+        k256::FieldBytes::from_slice(internal_key),
+        Choice::from(0),
+    ))
+    .ok_or_else(|| Error::FailedToParsePublicKey(hex_string(internal_key)))?;
+
+    let tweak = tagged_hash(TAP_TWEAK_TAG, internal_key);
+    let tweak = Option::<Scalar>::from(Scalar::from_repr(tweak.into()))
+        .ok_or_else(|| invalid("taproot tweak overflows the curve order"))?;
+
+    let output_point = (ProjectivePoint::from(internal_point)
+        + ProjectivePoint::mul_by_generator(&tweak))
+    .to_affine();
+    if output_point.is_identity().into() {
+        return Err(invalid("taproot output key is at infinity"));
+    }
+    let encoded = output_point.to_encoded_point(false);
+    Ok(encoded.x().unwrap().as_slice().try_into().unwrap()) // cannot fail
+}
+
+/// verify_signer checks that the BIP-322 witness for the given address was produced by
+/// the given BIP-340 (x-only) public key
+pub fn verify_signer(address: &Address, witness: &Witness, pk: &[u8; 32]) -> Result<()> {
+    let script_pubkey = address.script_pubkey();
+    if script_pubkey.is_p2wpkh() {
+        // the witness public key is checked against the address upon verification
+        match witness.nth(1) {
+            Some(pk_bytes) if pk_bytes.len() == 33 && &pk_bytes[1..] == pk => Ok(()),
+            _ => Err(invalid("signer does not match the public key")),
+        }
+    } else if script_pubkey.is_p2tr() {
+        if taproot_output_key(pk)? == script_pubkey.as_bytes()[2..] {
+            Ok(())
+        } else {
+            Err(invalid(
+                "address is not the BIP-86 address of the public key",
+            ))
+        }
+    } else {
+        Err(invalid("unsupported address type"))
+    }
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::hashes::hex::FromHex;
+    use bitcoin::Network;
+    use std::str::FromStr;
+
+    // Test vectors from BIP-322
+    const P2WPKH_ADDRESS: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+    const P2TR_ADDRESS: &str = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+    const P2WPKH_SIG_EMPTY: &str = "AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+    const P2WPKH_SIG_HELLO: &str = "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+    const P2TR_SIG_HELLO: &str =
+        "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ==";
+    const SIGNER_PK_HEX: &str = "c7f12003196442943d8588e01aee840423cc54fc1521526a3b85c2b0cbd58872";
+
+    fn address(addr: &str) -> Address {
+        Address::from_str(addr)
+            .unwrap()
+            .require_network(Network::Bitcoin)
+            .unwrap()
+    }
+
+    fn base64_decode(s: &str) -> Vec<u8> {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut bits = 0u32;
+        let mut n_bits = 0;
+        let mut out = vec![];
+        for c in s.bytes().filter(|c| *c != b'=') {
+            bits = (bits << 6) | ALPHABET.iter().position(|a| *a == c).unwrap() as u32;
+            n_bits += 6;
+            if n_bits >= 8 {
+                n_bits -= 8;
+                out.push((bits >> n_bits) as u8);
+                bits &= (1 << n_bits) - 1;
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_hash_message() {
+        assert_eq!(
+            hash_message(b"").to_vec(),
+            Vec::<u8>::from_hex("c90c269c4f8fcbe6880f72a721ddfbf1914268a794cbb21cfafee13770ae19f1")
+                .unwrap()
+        );
+        assert_eq!(
+            hash_message(b"Hello World").to_vec(),
+            Vec::<u8>::from_hex("f0eb03b1a75ac6d9847f55c624a99169b5dccba2a31f5b23bea77ba270de0a7a")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_virtual_txs() {
+        let script_pubkey = address(P2WPKH_ADDRESS).script_pubkey();
+
+        let to_spend_empty = to_spend(b"", &script_pubkey);
+        assert_eq!(
+            to_spend_empty.txid().to_string(),
+            "c5680aa69bb8d860bf82d4e9cd3504b55dde018de765a91bb566283c545a99a7"
+        );
+        let to_sign_empty = to_sign(&to_spend_empty, Witness::new());
+        assert_eq!(
+            to_sign_empty.txid().to_string(),
+            "1e9654e951a5ba44c8604c4de6c67fd78a27e81dcadcfe1edf638ba3aaebaed6"
+        );
+
+        let to_spend_hello = to_spend(b"Hello World", &script_pubkey);
+        assert_eq!(
+            to_spend_hello.txid().to_string(),
+            "b79d196740ad5217771c1098fc4a4b51e0535c32236c71f1ea4d61a2d603352b"
+        );
+        let to_sign_hello = to_sign(&to_spend_hello, Witness::new());
+        assert_eq!(
+            to_sign_hello.txid().to_string(),
+            "88737ae86f2077145f93cc4b153ae9a1cb8d56afa511988c149c5c8c9d93bddf"
+        );
+    }
+
+    #[test]
+    fn test_verify_p2wpkh() {
+        let addr = address(P2WPKH_ADDRESS);
+        verify_simple(b"", &addr, &base64_decode(P2WPKH_SIG_EMPTY)).unwrap();
+        verify_simple(b"Hello World", &addr, &base64_decode(P2WPKH_SIG_HELLO)).unwrap();
+
+        // signatures are bound to the message
+        verify_simple(b"Hello World", &addr, &base64_decode(P2WPKH_SIG_EMPTY)).unwrap_err();
+        verify_simple(b"", &addr, &base64_decode(P2WPKH_SIG_HELLO)).unwrap_err();
+        // and to the address
+        verify_simple(
+            b"Hello World",
+            &address(P2TR_ADDRESS),
+            &base64_decode(P2WPKH_SIG_HELLO),
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_p2tr() {
+        let addr = address(P2TR_ADDRESS);
+        verify_simple(b"Hello World", &addr, &base64_decode(P2TR_SIG_HELLO)).unwrap();
+        verify_simple(b"", &addr, &base64_decode(P2TR_SIG_HELLO)).unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_signer() {
+        let pk: [u8; 32] = Vec::<u8>::from_hex(SIGNER_PK_HEX)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let other_pk = [2u8; 32];
+
+        let witness: Witness = deserialize(&base64_decode(P2WPKH_SIG_HELLO)).unwrap();
+        let addr = address(P2WPKH_ADDRESS);
+        verify_signer(&addr, &witness, &pk).unwrap();
+        verify_signer(&addr, &witness, &other_pk).unwrap_err();
+
+        let witness: Witness = deserialize(&base64_decode(P2TR_SIG_HELLO)).unwrap();
+        let addr = address(P2TR_ADDRESS);
+        verify_signer(&addr, &witness, &pk).unwrap();
+        verify_signer(&addr, &witness, &other_pk).unwrap_err();
+    }
+}
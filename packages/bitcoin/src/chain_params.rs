@@ -1,7 +1,12 @@
 pub use bitcoin::consensus::Params;
+use bitcoin::Target;
+use cosmwasm_std::Uint256;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::error::Error;
+use crate::Result;
+
 // we re-implement the enum here since `rust-bitcoin`'s enum implementation
 // does not have `#[derive(Serialize, Deserialize)]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -11,6 +16,71 @@ pub enum Network {
     Testnet,
     Signet,
     Regtest,
+    /// Custom network, i.e. a base network with some of its chain parameters overridden.
+    /// Mostly useful for private regtest / test networks
+    Custom(ChainParamsOverride),
+}
+
+/// ChainParamsOverride defines the chain parameters of a custom network.
+/// Parameters that are not set are taken from the base network
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ChainParamsOverride {
+    /// `base` is the network whose chain parameters are overridden.
+    /// It also determines the custom network's address encoding
+    pub base: Box<Network>,
+    /// `pow_limit` is the highest target a header can have, i.e. the lowest difficulty
+    pub pow_limit: Option<Uint256>,
+    /// `pow_target_spacing` is the expected time (in seconds) to mine a block
+    pub pow_target_spacing: Option<u64>,
+    /// `pow_target_timespan` is the time (in seconds) between difficulty retargets.
+    /// The retarget interval is thus `pow_target_timespan / pow_target_spacing` blocks
+    pub pow_target_timespan: Option<u64>,
+    /// `allow_min_difficulty_blocks` determines whether minimal difficulty may be used for blocks
+    pub allow_min_difficulty_blocks: Option<bool>,
+    /// `no_pow_retargeting` determines whether difficulty retargeting is disabled
+    pub no_pow_retargeting: Option<bool>,
+}
+
+impl Network {
+    /// validate ensures the chain parameters of a custom network are consistent
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Network::Custom(overrides) => overrides.validate(),
+            _ => Ok(()),
+        }
+    }
+
+    /// is_signet returns whether the network is signet, or a custom network based on it
+    pub fn is_signet(&self) -> bool {
+        match self {
+            Network::Signet => true,
+            Network::Custom(overrides) => overrides.base.is_signet(),
+            _ => false,
+        }
+    }
+}
+
+impl ChainParamsOverride {
+    pub fn validate(&self) -> Result<()> {
+        self.base.validate()?;
+        if self.pow_limit == Some(Uint256::zero()) {
+            return Err(Error::InvalidChainParams(
+                "pow_limit cannot be zero".to_string(),
+            ));
+        }
+        let params = get_chain_params(Network::Custom(self.clone()));
+        if params.pow_target_spacing == 0 {
+            return Err(Error::InvalidChainParams(
+                "pow_target_spacing cannot be zero".to_string(),
+            ));
+        }
+        if params.pow_target_timespan < params.pow_target_spacing {
+            return Err(Error::InvalidChainParams(
+                "pow_target_timespan cannot be smaller than pow_target_spacing".to_string(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 pub fn get_chain_params(net: Network) -> Params {
@@ -19,6 +89,25 @@ pub fn get_chain_params(net: Network) -> Params {
         Network::Testnet => Params::new(bitcoin::Network::Testnet),
         Network::Signet => Params::new(bitcoin::Network::Signet),
         Network::Regtest => Params::new(bitcoin::Network::Regtest),
+        Network::Custom(overrides) => {
+            let mut params = get_chain_params(*overrides.base);
+            if let Some(pow_limit) = overrides.pow_limit {
+                params.pow_limit = Target::from_be_bytes(pow_limit.to_be_bytes());
+            }
+            if let Some(pow_target_spacing) = overrides.pow_target_spacing {
+                params.pow_target_spacing = pow_target_spacing;
+            }
+            if let Some(pow_target_timespan) = overrides.pow_target_timespan {
+                params.pow_target_timespan = pow_target_timespan;
+            }
+            if let Some(allow_min_difficulty_blocks) = overrides.allow_min_difficulty_blocks {
+                params.allow_min_difficulty_blocks = allow_min_difficulty_blocks;
+            }
+            if let Some(no_pow_retargeting) = overrides.no_pow_retargeting {
+                params.no_pow_retargeting = no_pow_retargeting;
+            }
+            params
+        }
     }
 }
 
@@ -28,5 +117,81 @@ pub fn get_bitcoin_network(net: Network) -> bitcoin::Network {
         Network::Testnet => bitcoin::Network::Testnet,
         Network::Signet => bitcoin::Network::Signet,
         Network::Regtest => bitcoin::Network::Regtest,
+        Network::Custom(overrides) => get_bitcoin_network(*overrides.base),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_chain_params_work() {
+        let net: Network = serde_json::from_str(
+            r#"{"custom":{"base":"regtest","pow_limit":"1000","pow_target_timespan":6000,"allow_min_difficulty_blocks":false}}"#,
+        )
+        .unwrap();
+        net.validate().unwrap();
+        assert_eq!(get_bitcoin_network(net.clone()), bitcoin::Network::Regtest);
+
+        let params = get_chain_params(net);
+        let regtest_params = get_chain_params(Network::Regtest);
+        assert_eq!(params.network, bitcoin::Network::Regtest);
+        assert_eq!(
+            params.pow_limit,
+            Target::from_be_bytes(Uint256::from(1000u64).to_be_bytes())
+        );
+        assert_eq!(params.pow_target_timespan, 6000);
+        assert!(!params.allow_min_difficulty_blocks);
+        // non-overridden params are taken from the base network
+        assert_eq!(params.pow_target_spacing, regtest_params.pow_target_spacing);
+        assert_eq!(params.no_pow_retargeting, regtest_params.no_pow_retargeting);
+    }
+
+    #[test]
+    fn signet_based_networks_are_signet() {
+        assert!(Network::Signet.is_signet());
+        assert!(!Network::Regtest.is_signet());
+
+        let overrides = ChainParamsOverride {
+            base: Box::new(Network::Signet),
+            pow_limit: None,
+            pow_target_spacing: Some(60),
+            pow_target_timespan: None,
+            allow_min_difficulty_blocks: None,
+            no_pow_retargeting: None,
+        };
+        assert!(Network::Custom(overrides.clone()).is_signet());
+        let overrides = ChainParamsOverride {
+            base: Box::new(Network::Regtest),
+            ..overrides
+        };
+        assert!(!Network::Custom(overrides).is_signet());
+    }
+
+    #[test]
+    fn invalid_custom_chain_params_fail() {
+        let overrides = ChainParamsOverride {
+            base: Box::new(Network::Regtest),
+            pow_limit: None,
+            pow_target_spacing: Some(600),
+            pow_target_timespan: Some(60),
+            allow_min_difficulty_blocks: None,
+            no_pow_retargeting: None,
+        };
+        assert!(matches!(
+            Network::Custom(overrides.clone()).validate(),
+            Err(Error::InvalidChainParams(_))
+        ));
+
+        let overrides = ChainParamsOverride {
+            pow_limit: Some(Uint256::zero()),
+            pow_target_timespan: None,
+            ..overrides
+        };
+        assert!(matches!(
+            Network::Custom(overrides).validate(),
+            Err(Error::InvalidChainParams(_))
+        ));
     }
 }
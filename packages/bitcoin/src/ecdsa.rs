@@ -0,0 +1,110 @@
+//! Bitcoin signed message verification.
+//!
+//! The message is prefixed with "Bitcoin Signed Message:\n" and double SHA-256 hashed,
+//! and signed with a compact recoverable ECDSA signature, whose header byte encodes the
+//! recovery id (and the address type, which is ignored here).
+//! See https://github.com/bitcoin/bips/blob/master/bip-0137.mediawiki
+//!
+//! NOTE: key recovery is done with the k256 library, to avoid using the secp256k1 FFI
+//! that bloats the binary size
+use crate::error::Error;
+use crate::Result;
+use bitcoin::hashes::Hash;
+use bitcoin::sign_message::signed_msg_hash;
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+/// Size of a compact recoverable signature, i.e. header byte + r + s
+pub const COMPACT_SIG_SIZE: usize = 65;
+
+// Range of valid header bytes, for P2PKH (uncompressed and compressed), P2SH-P2WPKH
+// and P2WPKH addresses
+const MIN_HEADER: u8 = 27;
+const MAX_HEADER: u8 = 42;
+
+/// recover_pubkey recovers the public key that produced the compact signature over
+/// the given message
+pub fn recover_pubkey(msg: &str, sig: &[u8]) -> Result<VerifyingKey> {
+    if sig.len() != COMPACT_SIG_SIZE {
+        return Err(Error::InvalidEcdsaSignature(format!(
+            "expected {COMPACT_SIG_SIZE} bytes, got {}",
+            sig.len()
+        )));
+    }
+    let header = sig[0];
+    if !(MIN_HEADER..=MAX_HEADER).contains(&header) {
+        return Err(Error::InvalidEcdsaSignature(format!(
+            "invalid header byte {header}"
+        )));
+    }
+    let recovery_id = RecoveryId::from_byte((header - MIN_HEADER) & 3).unwrap(); // cannot fail
+    let signature = Signature::from_slice(&sig[1..])
+        .map_err(|e| Error::InvalidEcdsaSignature(e.to_string()))?;
+
+    let msg_hash = signed_msg_hash(msg);
+    VerifyingKey::recover_from_prehash(msg_hash.as_byte_array(), &signature, recovery_id)
+        .map_err(|e| Error::InvalidEcdsaSignature(e.to_string()))
+}
+
+/// verify verifies the compact signature over the given message against the given
+/// BIP-340 (x-only) public key
+pub fn verify(msg: &str, sig: &[u8], pk: &[u8; 32]) -> Result<()> {
+    let recovered_pk = recover_pubkey(msg, sig)?;
+    let encoded_pk = recovered_pk.to_encoded_point(true);
+    if encoded_pk.x().map(|x| x.as_slice()) != Some(pk.as_slice()) {
+        return Err(Error::InvalidEcdsaSignature(
+            "signer does not match the public key".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::SigningKey;
+
+    fn sign(sk: &SigningKey, msg: &str, compressed: bool) -> Vec<u8> {
+        let msg_hash = signed_msg_hash(msg);
+        let (sig, recovery_id) = sk
+            .sign_prehash_recoverable(msg_hash.as_byte_array())
+            .unwrap();
+        let header = MIN_HEADER + recovery_id.to_byte() + if compressed { 4 } else { 0 };
+        let mut sig_bytes = vec![header];
+        sig_bytes.extend_from_slice(&sig.to_bytes());
+        sig_bytes
+    }
+
+    fn x_only(sk: &SigningKey) -> [u8; 32] {
+        let pk = sk.verifying_key().to_encoded_point(true);
+        pk.x().unwrap().as_slice().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_recover_pubkey() {
+        let sk = SigningKey::from_slice(&[1u8; 32]).unwrap();
+        for compressed in [false, true] {
+            let sig = sign(&sk, "hello babylon", compressed);
+            let pk = recover_pubkey("hello babylon", &sig).unwrap();
+            assert_eq!(&pk, sk.verifying_key());
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let sk = SigningKey::from_slice(&[1u8; 32]).unwrap();
+        let other_sk = SigningKey::from_slice(&[2u8; 32]).unwrap();
+        let sig = sign(&sk, "hello babylon", true);
+
+        verify("hello babylon", &sig, &x_only(&sk)).unwrap();
+        // wrong key
+        verify("hello babylon", &sig, &x_only(&other_sk)).unwrap_err();
+        // wrong message
+        verify("hello", &sig, &x_only(&sk)).unwrap_err();
+        // invalid header
+        let mut bad_sig = sig.clone();
+        bad_sig[0] = 0;
+        verify("hello babylon", &bad_sig, &x_only(&sk)).unwrap_err();
+        // invalid length
+        verify("hello babylon", &sig[1..], &x_only(&sk)).unwrap_err();
+    }
+}
@@ -6,4 +6,10 @@ pub enum Error {
     FailedToParsePublicKey(String),
     #[error("Invalid schnorr signature")]
     InvalidSchnorrSignature(String),
+    #[error("Invalid BIP-322 signature: {0}")]
+    InvalidBip322Signature(String),
+    #[error("Invalid ECDSA signature: {0}")]
+    InvalidEcdsaSignature(String),
+    #[error("Invalid chain params: {0}")]
+    InvalidChainParams(String),
 }
@@ -4,17 +4,20 @@ pub use bitcoin::{
     consensus::{deserialize, serialize},
     hash_types,
     hashes::hex::HexToArrayError as HexError,
-    BlockHash, CompactTarget, Target, Transaction, Work,
+    BlockHash, CompactTarget, ScriptBuf, Target, Transaction, Work,
 };
 
 pub use cosmwasm_std::Uint256;
 
+pub mod bip322;
 pub mod chain_params;
+pub mod ecdsa;
 pub mod error;
 pub mod merkle;
 pub mod op_return;
 pub mod pow;
 pub mod schnorr;
+pub mod signet;
 
 pub type Result<T> = std::result::Result<T, error::Error>;
 
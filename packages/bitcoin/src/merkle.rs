@@ -7,6 +7,12 @@ pub fn verify_merkle_proof(
     tx_index: usize,
     root: &sha256d::Hash,
 ) -> bool {
+    &compute_merkle_root(tx, proof, tx_index) == root
+}
+
+/// compute_merkle_root computes the merkle root committing to the given tx, by folding
+/// the tx's hash with the given merkle proof
+pub fn compute_merkle_root(tx: &Transaction, proof: &[&[u8]], tx_index: usize) -> sha256d::Hash {
     let mut current_hash = *tx.txid().as_raw_hash();
 
     for (i, next_hash) in proof.iter().enumerate() {
@@ -27,5 +33,5 @@ pub fn verify_merkle_proof(
         current_hash = sha256d::Hash::hash(&concat);
     }
 
-    &current_hash == root
+    current_hash
 }
@@ -0,0 +1,468 @@
+//! signet implements the verification of BIP325 signet block solutions.
+//!
+//! On signet, a block is only valid if its coinbase carries a solution, i.e. a scriptSig and a
+//! witness, satisfying the network's challenge script. Headers do not carry the coinbase, so the
+//! coinbase tx along with its merkle proof has to be provided on the side.
+//! See https://github.com/bitcoin/bips/blob/master/bip-0325.mediawiki
+use bitcoin::absolute::LockTime;
+use bitcoin::blockdata::opcodes::all::{OP_PUSHBYTES_0, OP_PUSHNUM_1, OP_PUSHNUM_16, OP_RETURN};
+use bitcoin::blockdata::opcodes::Opcode;
+use bitcoin::blockdata::script::{Builder, Instruction};
+use bitcoin::consensus::encode::{deserialize, deserialize_partial};
+use bitcoin::hashes::{hash160, Hash};
+use bitcoin::sighash::{EcdsaSighashType, SighashCache};
+use bitcoin::transaction::Version;
+use bitcoin::{Amount, OutPoint, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness};
+use k256::ecdsa::signature::hazmat::PrehashVerifier;
+use k256::ecdsa::{Signature, VerifyingKey};
+
+use crate::merkle::{compute_merkle_root, verify_merkle_proof};
+use crate::BlockHeader;
+
+/// SIGNET_HEADER prefixes the push carrying the signet solution in the coinbase's witness
+/// commitment output
+pub const SIGNET_HEADER: [u8; 4] = [0xec, 0xc7, 0xda, 0xa2];
+
+// OP_RETURN, a 36-byte push, and the witness commitment header, as in BIP141
+const WITNESS_COMMITMENT_PREFIX: [u8; 6] = [0x6a, 0x24, 0xaa, 0x21, 0xa9, 0xed];
+const MINIMUM_WITNESS_COMMITMENT: usize = 38;
+
+/// verify_signet_solution ensures the block of the given header satisfies the signet challenge,
+/// i.e.
+/// - the coinbase tx is committed to by the header's merkle root under the given merkle proof, and
+/// - the signet solution in the coinbase tx satisfies the challenge script.
+///
+/// The merkle proof is the list of sibling hashes (in internal byte order) from the coinbase tx
+/// up to the merkle root.
+///
+/// As there is no script interpreter available, only the standard challenge scripts are
+/// supported: `OP_TRUE`, P2PK, P2WPKH, and bare multisig (e.g. the default signet's 1-of-2).
+pub fn verify_signet_solution(
+    challenge: &Script,
+    header: &BlockHeader,
+    coinbase_tx: &Transaction,
+    coinbase_proof: &[&[u8]],
+) -> Result<(), String> {
+    if !coinbase_tx.is_coinbase() {
+        return Err("the given tx is not a coinbase tx".to_string());
+    }
+    // the coinbase tx is always the first tx of the block
+    if !verify_merkle_proof(
+        coinbase_tx,
+        coinbase_proof,
+        0,
+        header.merkle_root.as_raw_hash(),
+    ) {
+        return Err("the coinbase tx is not included in the block".to_string());
+    }
+
+    let (script_sig, witness, modified_coinbase_tx) = extract_solution(coinbase_tx)?;
+    let (_, to_sign) = signet_txs(
+        challenge,
+        header,
+        &modified_coinbase_tx,
+        coinbase_proof,
+        script_sig,
+        witness,
+    );
+    verify_challenge(challenge, &to_sign)
+}
+
+/// extract_solution returns the signet solution's scriptSig and witness, along with the coinbase
+/// tx with the solution stripped from its witness commitment output.
+/// A coinbase tx without a solution yields an empty solution, which only satisfies `OP_TRUE`.
+fn extract_solution(
+    coinbase_tx: &Transaction,
+) -> Result<(ScriptBuf, Witness, Transaction), String> {
+    // the witness commitment is the last output matching the commitment pattern
+    let commitment_idx = coinbase_tx
+        .output
+        .iter()
+        .rposition(|output| {
+            let script = output.script_pubkey.as_bytes();
+            script.len() >= MINIMUM_WITNESS_COMMITMENT
+                && script.starts_with(&WITNESS_COMMITMENT_PREFIX)
+        })
+        .ok_or("the coinbase tx has no witness commitment")?;
+
+    let mut replacement = Builder::new();
+    let mut solution = None;
+    for instruction in coinbase_tx.output[commitment_idx]
+        .script_pubkey
+        .instructions()
+    {
+        match instruction {
+            Ok(Instruction::PushBytes(data)) => {
+                let bytes = data.as_bytes();
+                // only the first push with the header _and_ some data counts
+                if solution.is_none()
+                    && bytes.len() > SIGNET_HEADER.len()
+                    && bytes.starts_with(&SIGNET_HEADER)
+                {
+                    solution = Some(bytes[SIGNET_HEADER.len()..].to_vec());
+                    replacement = replacement.push_slice(SIGNET_HEADER);
+                } else {
+                    replacement = replacement.push_slice(data);
+                }
+            }
+            Ok(Instruction::Op(op)) => replacement = replacement.push_opcode(op),
+            Err(_) => break,
+        }
+    }
+
+    let mut modified_coinbase_tx = coinbase_tx.clone();
+    let Some(solution) = solution else {
+        return Ok((ScriptBuf::new(), Witness::new(), modified_coinbase_tx));
+    };
+    modified_coinbase_tx.output[commitment_idx].script_pubkey = replacement.into_script();
+
+    let (script_sig, consumed): (ScriptBuf, usize) = deserialize_partial(&solution)
+        .map_err(|e| format!("failed to decode the signet solution's scriptSig: {e}"))?;
+    let witness: Witness = deserialize(&solution[consumed..])
+        .map_err(|e| format!("failed to decode the signet solution's witness: {e}"))?;
+    Ok((script_sig, witness, modified_coinbase_tx))
+}
+
+/// signet_txs builds the virtual `to_spend` and `to_sign` txs of BIP325, whose spending of the
+/// challenge script commits to the block
+fn signet_txs(
+    challenge: &Script,
+    header: &BlockHeader,
+    modified_coinbase_tx: &Transaction,
+    coinbase_proof: &[&[u8]],
+    script_sig: ScriptBuf,
+    witness: Witness,
+) -> (Transaction, Transaction) {
+    // the signet merkle root commits to the coinbase tx without the solution
+    let signet_merkle_root = compute_merkle_root(modified_coinbase_tx, coinbase_proof, 0);
+
+    let mut block_data = [0u8; 72];
+    block_data[0..4].copy_from_slice(&header.version.to_consensus().to_le_bytes());
+    block_data[4..36].copy_from_slice(&header.prev_blockhash.to_byte_array());
+    block_data[36..68].copy_from_slice(&signet_merkle_root.to_byte_array());
+    block_data[68..72].copy_from_slice(&header.time.to_le_bytes());
+
+    let to_spend = Transaction {
+        version: Version(0),
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: Builder::new()
+                .push_opcode(OP_PUSHBYTES_0)
+                .push_slice(block_data)
+                .into_script(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: challenge.to_owned(),
+        }],
+    };
+    let to_sign = Transaction {
+        version: Version(0),
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(to_spend.txid(), 0),
+            script_sig,
+            sequence: Sequence::ZERO,
+            witness,
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: Builder::new().push_opcode(OP_RETURN).into_script(),
+        }],
+    };
+    (to_spend, to_sign)
+}
+
+/// verify_challenge verifies that the `to_sign` tx's input satisfies the challenge script
+fn verify_challenge(challenge: &Script, to_sign: &Transaction) -> Result<(), String> {
+    let input = &to_sign.input[0];
+
+    // OP_TRUE is the trivial challenge, satisfied by any solution
+    if challenge.as_bytes() == [OP_PUSHNUM_1.to_u8()] {
+        return Ok(());
+    }
+
+    if challenge.is_p2wpkh() {
+        if !input.script_sig.is_empty() {
+            return Err("the signet solution has a scriptSig for a witness challenge".to_string());
+        }
+        let (Some(sig), Some(pk), None) = (
+            input.witness.nth(0),
+            input.witness.nth(1),
+            input.witness.nth(2),
+        ) else {
+            return Err("the signet solution's witness is not a P2WPKH witness".to_string());
+        };
+        if hash160::Hash::hash(pk).as_byte_array() != &challenge.as_bytes()[2..] {
+            return Err(
+                "the signet solution's public key does not match the challenge".to_string(),
+            );
+        }
+        let (sighash_type, der_sig) = sig
+            .split_last()
+            .ok_or("the signet solution's signature is empty")?;
+        let sighash = SighashCache::new(to_sign)
+            .p2wpkh_signature_hash(
+                0,
+                challenge,
+                Amount::ZERO,
+                EcdsaSighashType::from_consensus(*sighash_type as u32),
+            )
+            .map_err(|e| e.to_string())?;
+        if !verify_ecdsa_sig(pk, &sighash.to_byte_array(), der_sig) {
+            return Err("the signet solution's signature is invalid".to_string());
+        }
+        return Ok(());
+    }
+
+    if !input.witness.is_empty() {
+        return Err("the signet solution has a witness for a non-witness challenge".to_string());
+    }
+    let mut pushes = vec![];
+    for instruction in input.script_sig.instructions() {
+        match instruction.map_err(|e| e.to_string())? {
+            Instruction::PushBytes(data) => pushes.push(data.as_bytes()),
+            Instruction::Op(_) => {
+                return Err("the signet solution's scriptSig is not push-only".to_string())
+            }
+        }
+    }
+
+    if challenge.is_p2pk() {
+        let pk = &challenge.as_bytes()[1..challenge.len() - 1];
+        let [sig] = pushes.as_slice() else {
+            return Err("the signet solution's scriptSig is not a P2PK scriptSig".to_string());
+        };
+        if !verify_legacy_sig(to_sign, challenge, pk, sig) {
+            return Err("the signet solution's signature is invalid".to_string());
+        }
+        return Ok(());
+    }
+
+    if challenge.is_multisig() {
+        let (quorum, pks) = parse_multisig(challenge)?;
+        // the leading dummy element has to be empty (NULLDUMMY)
+        let Some((dummy, sigs)) = pushes.split_first() else {
+            return Err("the signet solution's scriptSig is empty".to_string());
+        };
+        if !dummy.is_empty() || sigs.len() != quorum {
+            return Err("the signet solution's scriptSig is not a multisig scriptSig".to_string());
+        }
+        // as in OP_CHECKMULTISIG, signatures have to be in the same order as public keys
+        let mut pks = pks.into_iter();
+        for sig in sigs {
+            if !pks.any(|pk| verify_legacy_sig(to_sign, challenge, pk, sig)) {
+                return Err("the signet solution's signatures are invalid".to_string());
+            }
+        }
+        return Ok(());
+    }
+
+    Err("unsupported signet challenge script".to_string())
+}
+
+/// parse_multisig returns the number of required signatures and the public keys of a bare
+/// multisig script
+fn parse_multisig(script: &Script) -> Result<(usize, Vec<&[u8]>), String> {
+    let mut quorum = None;
+    let mut pks = vec![];
+    for instruction in script.instructions() {
+        match instruction.map_err(|e| e.to_string())? {
+            Instruction::PushBytes(pk) => pks.push(pk.as_bytes()),
+            Instruction::Op(op) if quorum.is_none() => quorum = decode_pushnum(op),
+            Instruction::Op(_) => {}
+        }
+    }
+    let quorum = quorum.ok_or("the multisig challenge has no quorum")?;
+    Ok((quorum as usize, pks))
+}
+
+fn decode_pushnum(op: Opcode) -> Option<u8> {
+    let op = op.to_u8();
+    (OP_PUSHNUM_1.to_u8()..=OP_PUSHNUM_16.to_u8())
+        .contains(&op)
+        .then(|| op - OP_PUSHNUM_1.to_u8() + 1)
+}
+
+/// verify_legacy_sig verifies a pre-segwit signature, with its trailing sighash type, over the
+/// `to_sign` tx spending the given script
+fn verify_legacy_sig(to_sign: &Transaction, script: &Script, pk: &[u8], sig: &[u8]) -> bool {
+    let Some((sighash_type, der_sig)) = sig.split_last() else {
+        return false;
+    };
+    let Ok(sighash) =
+        SighashCache::new(to_sign).legacy_signature_hash(0, script, *sighash_type as u32)
+    else {
+        return false;
+    };
+    verify_ecdsa_sig(pk, &sighash.to_byte_array(), der_sig)
+}
+
+/// verify_ecdsa_sig verifies a DER-encoded ECDSA signature over a sighash.
+/// High-S signatures are accepted, as they are valid under the signet block validation rules.
+fn verify_ecdsa_sig(pk: &[u8], sighash: &[u8; 32], der_sig: &[u8]) -> bool {
+    let (Ok(pk), Ok(sig)) = (
+        VerifyingKey::from_sec1_bytes(pk),
+        Signature::from_der(der_sig),
+    ) else {
+        return false;
+    };
+    let sig = sig.normalize_s().unwrap_or(sig);
+    pk.verify_prehash(sighash, &sig).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::blockdata::opcodes::all::OP_CHECKMULTISIG;
+    use bitcoin::consensus::serialize;
+    use bitcoin::hash_types::TxMerkleNode;
+    use bitcoin::{BlockHash, CompactTarget, WPubkeyHash};
+    use k256::ecdsa::signature::hazmat::PrehashSigner;
+    use k256::ecdsa::SigningKey;
+
+    // the witness commitment output, carrying the given data after the commitment
+    fn witness_commitment(signet_push: &[u8]) -> ScriptBuf {
+        let mut commitment = [0u8; 36];
+        commitment[..4].copy_from_slice(&WITNESS_COMMITMENT_PREFIX[2..]);
+        let mut script = Builder::new()
+            .push_opcode(OP_RETURN)
+            .push_slice(commitment)
+            .into_script();
+        script.push_slice(<&bitcoin::script::PushBytes>::try_from(signet_push).unwrap());
+        script
+    }
+
+    fn build_coinbase_tx(signet_push: &[u8]) -> Transaction {
+        Transaction {
+            version: Version(2),
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: Builder::new().push_int(1234).into_script(),
+                sequence: Sequence::MAX,
+                witness: Witness::from_slice(&[[0u8; 32]]),
+            }],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(5_000_000_000),
+                    script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()),
+                },
+                TxOut {
+                    value: Amount::ZERO,
+                    script_pubkey: witness_commitment(signet_push),
+                },
+            ],
+        }
+    }
+
+    fn build_header(merkle_root: TxMerkleNode) -> BlockHeader {
+        BlockHeader {
+            version: crate::Version::from_consensus(0x20000000),
+            prev_blockhash: BlockHash::from_byte_array([0x11; 32]),
+            merkle_root,
+            time: 1_700_000_000,
+            bits: CompactTarget::from_consensus(0x1e0377ae),
+            nonce: 42,
+        }
+    }
+
+    fn multisig_challenge(pks: &[&SigningKey], quorum: i64) -> ScriptBuf {
+        let mut builder = Builder::new().push_int(quorum);
+        for pk in pks {
+            let pk_bytes = pk.verifying_key().to_encoded_point(true);
+            let pk_bytes: [u8; 33] = pk_bytes.as_bytes().try_into().unwrap();
+            builder = builder.push_slice(pk_bytes);
+        }
+        builder
+            .push_int(pks.len() as i64)
+            .push_opcode(OP_CHECKMULTISIG)
+            .into_script()
+    }
+
+    fn sign_legacy(to_sign: &Transaction, challenge: &Script, sk: &SigningKey) -> Vec<u8> {
+        let sighash = SighashCache::new(to_sign)
+            .legacy_signature_hash(0, challenge, EcdsaSighashType::All as u32)
+            .unwrap();
+        let sig: Signature = sk.sign_prehash(&sighash.to_byte_array()).unwrap();
+        let mut sig = sig.to_der().as_bytes().to_vec();
+        sig.push(EcdsaSighashType::All as u8);
+        sig
+    }
+
+    /// mines a single-tx signet block, whose coinbase carries a multisig solution signed by `signer`
+    fn signed_block(challenge: &Script, signer: &SigningKey) -> (BlockHeader, Transaction) {
+        // the block commits to the coinbase tx with the solution stripped off
+        let modified_coinbase_tx = build_coinbase_tx(&SIGNET_HEADER);
+        let unsigned_header = build_header(TxMerkleNode::from_raw_hash(
+            *modified_coinbase_tx.txid().as_raw_hash(),
+        ));
+        let (_, to_sign) = signet_txs(
+            challenge,
+            &unsigned_header,
+            &modified_coinbase_tx,
+            &[],
+            ScriptBuf::new(),
+            Witness::new(),
+        );
+        let sig = sign_legacy(&to_sign, challenge, signer);
+        let script_sig = Builder::new()
+            .push_opcode(OP_PUSHBYTES_0)
+            .push_slice(<&bitcoin::script::PushBytes>::try_from(sig.as_slice()).unwrap())
+            .into_script();
+
+        let mut signet_push = SIGNET_HEADER.to_vec();
+        signet_push.extend(serialize(&script_sig));
+        signet_push.extend(serialize(&Witness::new()));
+        let coinbase_tx = build_coinbase_tx(&signet_push);
+        let header = build_header(TxMerkleNode::from_raw_hash(
+            *coinbase_tx.txid().as_raw_hash(),
+        ));
+        (header, coinbase_tx)
+    }
+
+    #[test]
+    fn verify_signet_solution_works() {
+        let sk1 = SigningKey::from_slice(&[0x01; 32]).unwrap();
+        let sk2 = SigningKey::from_slice(&[0x02; 32]).unwrap();
+        let challenge = multisig_challenge(&[&sk1, &sk2], 1);
+
+        let (header, coinbase_tx) = signed_block(&challenge, &sk2);
+        verify_signet_solution(&challenge, &header, &coinbase_tx, &[]).unwrap();
+    }
+
+    #[test]
+    fn verify_signet_solution_rejects_bad_solutions() {
+        let sk1 = SigningKey::from_slice(&[0x01; 32]).unwrap();
+        let sk2 = SigningKey::from_slice(&[0x02; 32]).unwrap();
+        let challenge = multisig_challenge(&[&sk1], 1);
+
+        // signed by a key outside of the challenge
+        let (header, coinbase_tx) = signed_block(&challenge, &sk2);
+        assert!(verify_signet_solution(&challenge, &header, &coinbase_tx, &[]).is_err());
+
+        // the signature does not commit to a modified header
+        let (mut header, coinbase_tx) = signed_block(&challenge, &sk1);
+        verify_signet_solution(&challenge, &header, &coinbase_tx, &[]).unwrap();
+        header.time += 1;
+        assert!(verify_signet_solution(&challenge, &header, &coinbase_tx, &[]).is_err());
+
+        // the coinbase tx is not in the block
+        let (header, coinbase_tx) = signed_block(&challenge, &sk1);
+        let sibling = [0x22u8; 32];
+        assert!(verify_signet_solution(&challenge, &header, &coinbase_tx, &[&sibling]).is_err());
+
+        // no solution at all
+        let coinbase_tx = build_coinbase_tx(&SIGNET_HEADER);
+        let header = build_header(TxMerkleNode::from_raw_hash(
+            *coinbase_tx.txid().as_raw_hash(),
+        ));
+        assert!(verify_signet_solution(&challenge, &header, &coinbase_tx, &[]).is_err());
+        // which is fine for the trivial challenge
+        let op_true = Builder::new().push_opcode(OP_PUSHNUM_1).into_script();
+        verify_signet_solution(&op_true, &header, &coinbase_tx, &[]).unwrap();
+    }
+}
@@ -30,6 +30,8 @@ pub enum Error {
     AddLeafFailed {},
     #[error("Failed to finalize taproot")]
     FinalizeTaprootFailed {},
+    #[error("Invalid taproot address: {0}")]
+    InvalidTaprootAddress(String),
     #[error("Tx input count mismatch: expected {0}, got {1}")]
     TxInputCountMismatch(usize, usize),
     #[error("Tx output count mismatch: expected {0}, got {1}")]
@@ -60,4 +62,16 @@ pub enum Error {
     InvalidFundingOutputIndex(u32, usize),
     #[error("Slashing transaction must spend staking output")]
     StakingOutputNotSpentBySlashingTx {},
+    #[error("Invalid unbonding rate")]
+    InvalidUnbondingRate {},
+    #[error("Unbonding transaction must spend staking output")]
+    StakingOutputNotSpentByUnbondingTx {},
+    #[error("Unbonding time {0} must be larger than the minimum unbonding time {1}")]
+    InsufficientUnbondingTime(u16, u64),
+    #[error("Unbonding transaction must not spend more than the staking transaction")]
+    UnbondingTxOverspend {},
+    #[error("Unbonding transaction fee must be at least {0}")]
+    InsufficientUnbondingFee(u64),
+    #[error("Unbonding output value must be at least {0} satoshis")]
+    InsufficientUnbondingValue(u64),
 }
@@ -6,10 +6,10 @@ use bitcoin::opcodes::all::{
     OP_PUSHNUM_1,
 };
 
-use bitcoin::secp256k1::PublicKey;
+use bitcoin::hashes::{Hash, HashEngine};
 use bitcoin::taproot::LeafVersion;
-use bitcoin::ScriptBuf;
-use bitcoin::{TapNodeHash, TapTweakHash, XOnlyPublicKey};
+use bitcoin::{Address, Network, ScriptBuf};
+use bitcoin::{TapNodeHash, TapTweakHash};
 
 use k256::elliptic_curve::sec1::ToEncodedPoint;
 use k256::elliptic_curve::subtle::Choice;
@@ -21,13 +21,13 @@ use k256::{
 
 const UNSPENDABLE_KEY: &str = "0250929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0";
 
-fn unspendable_key_path_internal_pub_key() -> XOnlyPublicKey {
+/// unspendable_key_path_internal_pub_key returns the x-only bytes of the unspendable key
+/// NOTE: this avoids parsing the key with rust-bitcoin, as it uses secp256k1 FFI and
+/// will bloat the binary size
+fn unspendable_key_path_internal_pub_key() -> [u8; 32] {
     let key_bytes = hex::decode(UNSPENDABLE_KEY).unwrap();
-
-    let (pk_x, _) = PublicKey::from_slice(&key_bytes)
-        .unwrap()
-        .x_only_public_key();
-    pk_x
+    // drop the parity byte of the compressed key
+    key_bytes[1..].try_into().unwrap() // cannot fail
 }
 
 // sort_keys sorts public keys in lexicographical order
@@ -140,15 +140,17 @@ fn compute_tweaked_key_bytes(merkle_root: TapNodeHash) -> [u8; 32] {
     let internal_key = unspendable_key_path_internal_pub_key();
 
     // compute tweak point
-    let tweak = TapTweakHash::from_key_and_tweak(internal_key, Some(merkle_root)).to_scalar();
-    let tweak_bytes = &tweak.to_be_bytes();
-    let tweak_bytes = k256::FieldBytes::from_slice(tweak_bytes);
+    // NOTE: this is `TapTweakHash::from_key_and_tweak`, without the x-only key type
+    let mut engine = TapTweakHash::engine();
+    engine.input(&internal_key);
+    engine.input(merkle_root.as_ref());
+    let tweak = TapTweakHash::from_engine(engine).to_byte_array();
+    let tweak_bytes = k256::FieldBytes::from_slice(&tweak);
     let tweak_scalar = Scalar::from_repr_vartime(*tweak_bytes).unwrap();
     let tweak_point = ProjectivePoint::mul_by_generator(&tweak_scalar);
 
     // compute internal key point
-    let internal_key_bytes = internal_key.serialize();
-    let x = k256::FieldBytes::from_slice(internal_key_bytes.as_slice());
+    let x = k256::FieldBytes::from_slice(internal_key.as_slice());
     let ap_option = AffinePoint::decompress(x, Choice::from(false as u8));
     let internal_key_point = ProjectivePoint::from(ap_option.unwrap());
 
@@ -158,9 +160,22 @@ fn compute_tweaked_key_bytes(merkle_root: TapNodeHash) -> [u8; 32] {
     point_to_bytes(tweaked_point)
 }
 
-/// build_relative_time_lock_pk_script builds a relative timelocked taproot script
+/// build_taproot_pk_script builds the Taproot output script committing to the given
+/// Merkle root, with the unspendable key as internal key
 /// NOTE: this function is heavily optimised by manually computing the tweaked key
 /// This is to avoid using any secp256k1 FFI that will bloat the binary size
+fn build_taproot_pk_script(merkle_root: TapNodeHash) -> ScriptBuf {
+    // compute the tweaked key in bytes
+    let tweaked_key_bytes = compute_tweaked_key_bytes(merkle_root);
+    // construct the Taproot output script
+    let mut builder = Builder::new();
+    builder = builder
+        .push_opcode(OP_PUSHNUM_1)
+        .push_slice(tweaked_key_bytes);
+    builder.into_script()
+}
+
+/// build_relative_time_lock_pk_script builds a relative timelocked taproot script
 pub fn build_relative_time_lock_pk_script(pk: &VerifyingKey, lock_time: u16) -> Result<ScriptBuf> {
     // build timelock script
     let script = build_time_lock_script(pk, lock_time)?;
@@ -169,15 +184,7 @@ pub fn build_relative_time_lock_pk_script(pk: &VerifyingKey, lock_time: u16) ->
     // NOTE: avoid using TaprootBuilder as this bloats the binary size
     let merkle_root = TapNodeHash::from_script(&script, LeafVersion::TapScript);
 
-    // compute the tweaked key in bytes
-    let tweaked_key_bytes = compute_tweaked_key_bytes(merkle_root);
-    // construct the Taproot output script
-    let mut builder = Builder::new();
-    builder = builder
-        .push_opcode(OP_PUSHNUM_1)
-        .push_slice(tweaked_key_bytes);
-    let taproot_pk_script = builder.into_script();
-    Ok(taproot_pk_script)
+    Ok(build_taproot_pk_script(merkle_root))
 }
 
 fn aggregate_scripts(scripts: &[ScriptBuf]) -> ScriptBuf {
@@ -237,13 +244,66 @@ impl BabylonScriptPaths {
         })
     }
 
-    // TODO: implement a function for aggregating all scripts to a single ScriptBuf
+    /// staking_merkle_root computes the Merkle root of the script tree of the staking
+    /// output, which commits to all paths
+    /// The tree is assembled as in btcd's `AssembleTaprootScriptTree`, i.e., the
+    /// timelock and unbonding leaves are paired, and then paired with the slashing leaf
+    /// NOTE: avoid using TaprootBuilder as this bloats the binary size
+    pub fn staking_merkle_root(&self) -> TapNodeHash {
+        let time_lock_leaf =
+            TapNodeHash::from_script(&self.time_lock_path_script, LeafVersion::TapScript);
+        let unbonding_leaf =
+            TapNodeHash::from_script(&self.unbonding_path_script, LeafVersion::TapScript);
+        let slashing_leaf =
+            TapNodeHash::from_script(&self.slashing_path_script, LeafVersion::TapScript);
+
+        let branch = TapNodeHash::from_node_hashes(time_lock_leaf, unbonding_leaf);
+        TapNodeHash::from_node_hashes(branch, slashing_leaf)
+    }
+
+    /// unbonding_merkle_root computes the Merkle root of the script tree of the unbonding
+    /// output, which commits to the timelock and slashing paths only
+    pub fn unbonding_merkle_root(&self) -> TapNodeHash {
+        let time_lock_leaf =
+            TapNodeHash::from_script(&self.time_lock_path_script, LeafVersion::TapScript);
+        let slashing_leaf =
+            TapNodeHash::from_script(&self.slashing_path_script, LeafVersion::TapScript);
+
+        TapNodeHash::from_node_hashes(time_lock_leaf, slashing_leaf)
+    }
+
+    /// staking_pk_script builds the Taproot output script of the staking output,
+    /// with the unspendable key as internal key so that the key path cannot be used
+    pub fn staking_pk_script(&self) -> ScriptBuf {
+        build_taproot_pk_script(self.staking_merkle_root())
+    }
+
+    /// unbonding_pk_script builds the Taproot output script of the unbonding output,
+    /// with the unspendable key as internal key so that the key path cannot be used
+    pub fn unbonding_pk_script(&self) -> ScriptBuf {
+        build_taproot_pk_script(self.unbonding_merkle_root())
+    }
+
+    /// staking_address builds the P2TR address of the staking output
+    pub fn staking_address(&self, network: Network) -> Result<Address> {
+        Address::from_script(&self.staking_pk_script(), network)
+            .map_err(|e| Error::InvalidTaprootAddress(e.to_string()))
+    }
+
+    /// unbonding_address builds the P2TR address of the unbonding output
+    pub fn unbonding_address(&self, network: Network) -> Result<Address> {
+        Address::from_script(&self.unbonding_pk_script(), network)
+            .map_err(|e| Error::InvalidTaprootAddress(e.to_string()))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bitcoin::consensus::deserialize;
     use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+    use bitcoin::Transaction;
+    use test_utils::{get_btc_delegation, get_params};
 
     // Function to generate a public key from a secret key
     fn generate_public_key(data: &[u8]) -> VerifyingKey {
@@ -276,4 +336,64 @@ mod tests {
             "Keys should be sorted lexicographically"
         );
     }
+
+    #[test]
+    fn test_taproot_pk_script() {
+        let btc_del = get_btc_delegation(1, vec![1]);
+        let params = get_params();
+
+        let staker_pk = VerifyingKey::from_bytes(&btc_del.btc_pk).unwrap();
+        let fp_pks: Vec<VerifyingKey> = btc_del
+            .fp_btc_pk_list
+            .iter()
+            .map(|bytes| VerifyingKey::from_bytes(bytes).unwrap())
+            .collect();
+        let cov_pks: Vec<VerifyingKey> = params
+            .covenant_pks
+            .iter()
+            .map(|bytes| VerifyingKey::from_bytes(bytes).unwrap())
+            .collect();
+
+        // the staking output commits to the staking script paths
+        let staking_tx: Transaction = deserialize(&btc_del.staking_tx).unwrap();
+        let staking_paths = BabylonScriptPaths::new(
+            &staker_pk,
+            &fp_pks,
+            &cov_pks,
+            params.covenant_quorum as usize,
+            (btc_del.end_height - btc_del.start_height) as u16,
+        )
+        .unwrap();
+        let staking_out = &staking_tx.output[btc_del.staking_output_idx as usize];
+        assert_eq!(staking_paths.staking_pk_script(), staking_out.script_pubkey);
+        let staking_address = staking_paths.staking_address(Network::Regtest).unwrap();
+        assert_eq!(staking_address.script_pubkey(), staking_out.script_pubkey);
+
+        // the unbonding output commits to the unbonding script paths
+        let btc_undel = btc_del.btc_undelegation.unwrap();
+        let unbonding_tx: Transaction = deserialize(&btc_undel.unbonding_tx).unwrap();
+        let unbonding_paths = BabylonScriptPaths::new(
+            &staker_pk,
+            &fp_pks,
+            &cov_pks,
+            params.covenant_quorum as usize,
+            btc_del.unbonding_time as u16,
+        )
+        .unwrap();
+        assert_eq!(
+            unbonding_paths.unbonding_pk_script(),
+            unbonding_tx.output[0].script_pubkey
+        );
+        let unbonding_address = unbonding_paths.unbonding_address(Network::Regtest).unwrap();
+        assert_eq!(
+            unbonding_address.script_pubkey(),
+            unbonding_tx.output[0].script_pubkey
+        );
+
+        // the unbonding output does not commit to the staking script tree
+        assert_ne!(
+            unbonding_paths.staking_pk_script(),
+            unbonding_tx.output[0].script_pubkey
+        );
+    }
 }
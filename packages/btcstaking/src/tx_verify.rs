@@ -2,9 +2,12 @@ use crate::error::Error;
 use crate::scripts_utils;
 use crate::Result;
 
-use bitcoin::{address::Address, Transaction};
+use bitcoin::{address::Address, Sequence, Transaction};
+use cosmwasm_std::Decimal;
 use k256::schnorr::VerifyingKey;
-use rust_decimal::{prelude::*, Decimal};
+
+/// Number of decimal places a rate (slashing rate, unbonding rate) can have
+const RATE_PRECISION: u32 = 2;
 
 /// Checks if a transaction has exactly one input and one output.
 fn is_transfer_tx(tx: &Transaction) -> Result<()> {
@@ -21,11 +24,10 @@ fn is_transfer_tx(tx: &Transaction) -> Result<()> {
 
 /// Checks if a transaction is a simple transfer, meaning it has exactly one input and one output,
 /// is not replaceable (sequence number is max), and has no locktime.
-#[allow(dead_code)]
 fn is_simple_transfer(tx: &Transaction) -> Result<()> {
     is_transfer_tx(tx)?; // Reuse the is_transfer_tx check and propagate error if any
 
-    if !tx.input[0].sequence.is_rbf() {
+    if tx.input[0].sequence != Sequence::MAX {
         return Err(Error::TxIsReplaceable {});
     }
 
@@ -41,7 +43,7 @@ fn is_simple_transfer(tx: &Transaction) -> Result<()> {
 fn validate_slashing_tx(
     slashing_tx: &Transaction,
     slashing_address: &Address,
-    slashing_rate: f64,
+    slashing_rate: Decimal,
     slashing_tx_min_fee: u64,
     staking_output_value: u64,
     staker_pk: &VerifyingKey,
@@ -51,7 +53,7 @@ fn validate_slashing_tx(
         return Err(Error::TxInputCountMismatch(1, slashing_tx.input.len()));
     }
 
-    if slashing_tx.input[0].sequence.is_rbf() {
+    if slashing_tx.input[0].sequence != Sequence::MAX {
         return Err(Error::TxIsReplaceable {});
     }
 
@@ -63,7 +65,7 @@ fn validate_slashing_tx(
         return Err(Error::TxOutputCountMismatch(2, slashing_tx.output.len()));
     }
 
-    let expected_slashing_amount = (staking_output_value as f64 * slashing_rate).round() as u64;
+    let expected_slashing_amount = slashing_amount(staking_output_value, slashing_rate);
     if slashing_tx.output[0].value.to_sat() < expected_slashing_amount {
         return Err(Error::InsufficientSlashingAmount(expected_slashing_amount));
     }
@@ -116,20 +118,46 @@ fn validate_slashing_tx(
 }
 
 /// Checks if the given rate is between the valid range i.e., (0,1) with a precision of at most 2 decimal places.
-fn is_rate_valid(rate: f64) -> bool {
-    // Check if the slashing rate is between 0 and 1
-    if rate <= 0.0 || rate >= 1.0 {
+pub fn is_rate_valid(rate: Decimal) -> bool {
+    // Check if the rate is between 0 and 1
+    if rate.is_zero() || rate >= Decimal::one() {
         return false;
     }
 
-    // Multiply by 100 to move the decimal places and check if precision is at most 2 decimal places
-    let multiplied_rate = Decimal::from_f64(rate * 100.0).unwrap();
+    // Check if the precision is at most 2 decimal places, i.e. the trailing atomics are zero
+    let unit = 10u128.pow(Decimal::DECIMAL_PLACES - RATE_PRECISION);
+    rate.atomics().u128().is_multiple_of(unit)
+}
 
-    // Truncate the rate to remove decimal places
-    let truncated_rate = multiplied_rate.trunc();
+/// Multiplies the given amount (in satoshis) by the given rate, in integer arithmetic.
+/// Returns the product in atomics, i.e. scaled by 10^`Decimal::DECIMAL_PLACES`, along with the scale.
+/// This cannot overflow, as both the amount and the rate atomics are below 2^64
+fn mul_rate(amount: u64, rate: Decimal) -> (u128, u128) {
+    (
+        amount as u128 * rate.atomics().u128(),
+        10u128.pow(Decimal::DECIMAL_PLACES),
+    )
+}
+
+/// Computes the minimum amount (in satoshis) that a slashing tx must slash from the given staking
+/// output value.
+/// The amount is rounded half up, as specified by Babylon's
+/// `btcutil.Amount(stakingOutputValue).MulF64(slashingRate)`.
+/// NOTE: Babylon computes the product in float64, so on some half-satoshi ties where the float
+/// product falls just below the tie (e.g. 50 * 0.29 = 14.499999999999998), this amount is
+/// 1 satoshi higher than Babylon's
+pub fn slashing_amount(staking_output_value: u64, slashing_rate: Decimal) -> u64 {
+    let (product, scale) = mul_rate(staking_output_value, slashing_rate);
+    ((product + scale / 2) / scale) as u64
+}
 
-    // Check if the truncated rate is equal to the original rate
-    multiplied_rate == truncated_rate
+/// Computes the minimum value (in satoshis) that an unbonding output must carry for the given
+/// staking output value.
+/// The value is truncated, matching Babylon's
+/// `math.LegacyNewDec(stakingOutputValue).Mul(minUnbondingRate).TruncateInt64()`
+pub fn min_unbonding_value(staking_output_value: u64, min_unbonding_rate: Decimal) -> u64 {
+    let (product, scale) = mul_rate(staking_output_value, min_unbonding_rate);
+    (product / scale) as u64
 }
 
 /// Validates all relevant data of slashing and funding transactions.
@@ -139,7 +167,7 @@ pub fn check_transactions(
     funding_transaction: &Transaction,
     funding_output_idx: u32,
     slashing_tx_min_fee: u64,
-    slashing_rate: f64,
+    slashing_rate: Decimal,
     slashing_address: &Address,
     staker_pk: &VerifyingKey,
     slashing_change_lock_time: u16,
@@ -192,6 +220,71 @@ pub fn check_transactions(
     Ok(())
 }
 
+/// Validates the unbonding transaction against the staking transaction and the unbonding policy,
+/// i.e., the unbonding tx
+/// - is a simple transfer spending the staking output,
+/// - has a time-lock larger than `min_unbonding_time` BTC blocks,
+/// - pays a positive fee of at least `unbonding_fee_sat`, and
+/// - has an output value of at least `min_unbonding_rate` of the staking output value.
+#[allow(clippy::too_many_arguments)]
+pub fn check_unbonding_tx(
+    unbonding_tx: &Transaction,
+    staking_tx: &Transaction,
+    staking_output_idx: u32,
+    unbonding_time: u16,
+    min_unbonding_time: u64,
+    unbonding_fee_sat: u64,
+    min_unbonding_rate: Decimal,
+) -> Result<()> {
+    // Check if the unbonding rate is in the valid range (0,1)
+    if !is_rate_valid(min_unbonding_rate) {
+        return Err(Error::InvalidUnbondingRate {});
+    }
+
+    is_simple_transfer(unbonding_tx)?;
+
+    if staking_output_idx >= staking_tx.output.len() as u32 {
+        return Err(Error::InvalidFundingOutputIndex(
+            staking_output_idx,
+            staking_tx.output.len(),
+        ));
+    }
+    let staking_output = &staking_tx.output[staking_output_idx as usize];
+
+    // Check that the unbonding tx input is pointing to the staking output
+    let previous_output = &unbonding_tx.input[0].previous_output;
+    if previous_output.txid != staking_tx.txid() || previous_output.vout != staking_output_idx {
+        return Err(Error::StakingOutputNotSpentByUnbondingTx {});
+    }
+
+    // Check the unbonding time, which must be strictly larger than the minimum
+    if (unbonding_time as u64) <= min_unbonding_time {
+        return Err(Error::InsufficientUnbondingTime(
+            unbonding_time,
+            min_unbonding_time,
+        ));
+    }
+
+    // Check fees
+    let staking_output_value = staking_output.value.to_sat();
+    let unbonding_output_value = unbonding_tx.output[0].value.to_sat();
+    if staking_output_value <= unbonding_output_value {
+        return Err(Error::UnbondingTxOverspend {});
+    }
+    let fee = staking_output_value - unbonding_output_value;
+    if fee < unbonding_fee_sat {
+        return Err(Error::InsufficientUnbondingFee(unbonding_fee_sat));
+    }
+
+    // Check the unbonding output value
+    let min_unbonding_value = min_unbonding_value(staking_output_value, min_unbonding_rate);
+    if unbonding_output_value < min_unbonding_value {
+        return Err(Error::InsufficientUnbondingValue(min_unbonding_value));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use self::scripts_utils::BabylonScriptPaths;
@@ -202,9 +295,79 @@ mod tests {
     };
     use bitcoin::address::Address;
     use bitcoin::consensus::deserialize;
+    use std::str::FromStr;
 
     use test_utils::{get_btc_delegation, get_params};
 
+    #[test]
+    fn test_is_rate_valid() {
+        assert!(is_rate_valid(Decimal::percent(1)));
+        assert!(is_rate_valid(Decimal::percent(99)));
+        assert!(is_rate_valid(Decimal::from_str("0.5").unwrap()));
+        assert!(!is_rate_valid(Decimal::zero()));
+        assert!(!is_rate_valid(Decimal::one()));
+        assert!(!is_rate_valid(Decimal::from_str("0.001").unwrap()));
+        assert!(!is_rate_valid(Decimal::from_str("0.125").unwrap()));
+    }
+
+    /// Cross-check vectors against Babylon's Go implementation, i.e.
+    /// `btcutil.Amount(value).MulF64(rate)` for the slashing amount, and
+    /// `math.LegacyNewDec(value).Mul(rate).TruncateInt64()` for the min unbonding value
+    #[test]
+    fn test_rate_amounts() {
+        // (staking output value, rate, slashing amount, min unbonding value)
+        let vectors = [
+            (50, "0.01", 1, 0),
+            (150, "0.01", 2, 1),
+            (999, "0.05", 50, 49),
+            (1000, "0.1", 100, 100),
+            (12345, "0.01", 123, 123),
+            (123_456_789, "0.33", 40_740_740, 40_740_740),
+            (2_000_000_000, "0.1", 200_000_000, 200_000_000),
+            (2_000_000_000, "0.8", 1_600_000_000, 1_600_000_000),
+            (
+                2_100_000_000_000_000,
+                "0.99",
+                2_079_000_000_000_000,
+                2_079_000_000_000_000,
+            ),
+        ];
+        for (value, rate, expected_slashing_amount, expected_min_unbonding_value) in vectors {
+            let rate = Decimal::from_str(rate).unwrap();
+            assert_eq!(
+                slashing_amount(value, rate),
+                expected_slashing_amount,
+                "slashing amount of {value} at {rate}"
+            );
+            assert_eq!(
+                min_unbonding_value(value, rate),
+                expected_min_unbonding_value,
+                "min unbonding value of {value} at {rate}"
+            );
+        }
+    }
+
+    /// Known differences with Babylon's slashing amounts, on half-satoshi ties where its float64
+    /// product falls just below the tie. The exact amount is rounded up, and is 1 satoshi higher
+    #[test]
+    fn test_slashing_amount_float_ties() {
+        // (staking output value, rate, Babylon's float64 product, Babylon's slashing amount)
+        let ties = [
+            (50, "0.29", 14.499999999999998, 14),
+            (90, "0.35", 31.499999999999996, 31),
+            (3450, "0.29", 1000.4999999999999, 1000),
+        ];
+        for (value, rate, babylon_product, babylon_amount) in ties {
+            assert_eq!((babylon_product + 0.5) as u64, babylon_amount);
+            let rate = Decimal::from_str(rate).unwrap();
+            assert_eq!(
+                slashing_amount(value, rate),
+                babylon_amount + 1,
+                "slashing amount of {value} at {rate}"
+            );
+        }
+    }
+
     #[test]
     fn test_check_transactions() {
         let btc_del = get_btc_delegation(1, vec![1]);
@@ -214,7 +377,7 @@ mod tests {
         let slashing_tx: Transaction = deserialize(&btc_del.slashing_tx).unwrap();
         let funding_out_idx: u32 = 0;
         let slashing_tx_min_fee: u64 = 1;
-        let slashing_rate: f64 = 0.01;
+        let slashing_rate = Decimal::percent(1);
         let slashing_address: Address = Address::from_str(&params.slashing_address)
             .unwrap()
             .assume_checked();
@@ -235,6 +398,122 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn test_check_unbonding_tx() {
+        let btc_del = get_btc_delegation(1, vec![1]);
+        let btc_undel = btc_del.btc_undelegation.unwrap();
+
+        let staking_tx: Transaction = deserialize(&btc_del.staking_tx).unwrap();
+        let unbonding_tx: Transaction = deserialize(&btc_undel.unbonding_tx).unwrap();
+        let staking_output_idx = btc_del.staking_output_idx;
+        let unbonding_time = btc_del.unbonding_time as u16;
+        let min_unbonding_time = unbonding_time as u64 - 1;
+        let staking_value = staking_tx.output[staking_output_idx as usize].value;
+        let fee = (staking_value - unbonding_tx.output[0].value).to_sat();
+        let min_unbonding_rate = Decimal::percent(80);
+
+        check_unbonding_tx(
+            &unbonding_tx,
+            &staking_tx,
+            staking_output_idx,
+            unbonding_time,
+            min_unbonding_time,
+            fee,
+            min_unbonding_rate,
+        )
+        .unwrap();
+
+        // the unbonding tx must spend the staking output
+        let err = check_unbonding_tx(
+            &unbonding_tx,
+            &staking_tx,
+            staking_output_idx + 1,
+            unbonding_time,
+            min_unbonding_time,
+            fee,
+            min_unbonding_rate,
+        )
+        .unwrap_err();
+        assert_eq!(err, Error::StakingOutputNotSpentByUnbondingTx {});
+
+        // the unbonding time must be strictly larger than the minimum unbonding time
+        let err = check_unbonding_tx(
+            &unbonding_tx,
+            &staking_tx,
+            staking_output_idx,
+            unbonding_time,
+            unbonding_time as u64,
+            fee,
+            min_unbonding_rate,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            Error::InsufficientUnbondingTime(unbonding_time, unbonding_time as u64)
+        );
+
+        // the unbonding fee must be at least the unbonding fee
+        let err = check_unbonding_tx(
+            &unbonding_tx,
+            &staking_tx,
+            staking_output_idx,
+            unbonding_time,
+            min_unbonding_time,
+            fee + 1,
+            min_unbonding_rate,
+        )
+        .unwrap_err();
+        assert_eq!(err, Error::InsufficientUnbondingFee(fee + 1));
+
+        // the unbonding output value must be at least the minimum unbonding rate
+        let mut low_value_unbonding_tx = unbonding_tx.clone();
+        low_value_unbonding_tx.output[0].value = staking_value / 2;
+        let err = check_unbonding_tx(
+            &low_value_unbonding_tx,
+            &staking_tx,
+            staking_output_idx,
+            unbonding_time,
+            min_unbonding_time,
+            fee,
+            min_unbonding_rate,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            Error::InsufficientUnbondingValue(staking_value.to_sat() / 5 * 4)
+        );
+
+        // the unbonding tx must not be replaceable
+        let mut rbf_unbonding_tx = unbonding_tx.clone();
+        rbf_unbonding_tx.input[0].sequence = bitcoin::Sequence::ENABLE_RBF_NO_LOCKTIME;
+        let err = check_unbonding_tx(
+            &rbf_unbonding_tx,
+            &staking_tx,
+            staking_output_idx,
+            unbonding_time,
+            min_unbonding_time,
+            fee,
+            min_unbonding_rate,
+        )
+        .unwrap_err();
+        assert_eq!(err, Error::TxIsReplaceable {});
+
+        // the unbonding tx must have the max sequence, even if not signalling RBF
+        let mut non_final_unbonding_tx = unbonding_tx.clone();
+        non_final_unbonding_tx.input[0].sequence = bitcoin::Sequence::ENABLE_LOCKTIME_NO_RBF;
+        let err = check_unbonding_tx(
+            &non_final_unbonding_tx,
+            &staking_tx,
+            staking_output_idx,
+            unbonding_time,
+            min_unbonding_time,
+            fee,
+            min_unbonding_rate,
+        )
+        .unwrap_err();
+        assert_eq!(err, Error::TxIsReplaceable {});
+    }
+
     #[test]
     fn test_verify_unbonding_tx_schnorr_sig() {
         let btc_del = get_btc_delegation(1, vec![1]);
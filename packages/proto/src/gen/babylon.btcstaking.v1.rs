@@ -13,6 +13,18 @@ pub struct ProofOfPossessionBtc {
     #[prost(bytes="bytes", tag="2")]
     pub btc_sig: ::prost::bytes::Bytes,
 }
+/// BIP322Sig is a BIP-322 signature together with the address corresponding to
+/// the signer
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Bip322Sig {
+    /// address is the signer's address
+    #[prost(string, tag="1")]
+    pub address: ::prost::alloc::string::String,
+    /// sig is the actual signature in BIP-322 format
+    #[prost(bytes="bytes", tag="2")]
+    pub sig: ::prost::bytes::Bytes,
+}
 /// BTCSigType indicates the type of btc_sig in a pop
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -265,6 +277,8 @@ pub struct BtcStakingIbcPacket {
     pub slashed_del: ::prost::alloc::vec::Vec<SlashedBtcDelegation>,
     #[prost(message, repeated, tag="4")]
     pub unbonded_del: ::prost::alloc::vec::Vec<UnbondedBtcDelegation>,
+    #[prost(message, repeated, tag="5")]
+    pub edit_fp: ::prost::alloc::vec::Vec<EditFinalityProvider>,
 }
 /// NewFinalityProvider is an IBC packet sent from Babylon to consumer
 /// upon a newly registered finality provider on this consumer
@@ -293,6 +307,25 @@ pub struct NewFinalityProvider {
     #[prost(string, tag="8")]
     pub consumer_id: ::prost::alloc::string::String,
 }
+/// EditFinalityProvider is an IBC packet sent from Babylon to consumer
+/// upon an edit of a finality provider operating on this consumer
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EditFinalityProvider {
+    /// btc_pk_hex is the Bitcoin secp256k1 PK of the finality provider to edit
+    /// the PK follows encoding in BIP-340 spec in hex format
+    #[prost(string, tag="1")]
+    pub btc_pk_hex: ::prost::alloc::string::String,
+    /// description defines the new description terms for the finality provider.
+    /// If it's missing, the description is left unchanged.
+    #[prost(message, optional, tag="2")]
+    pub description: ::core::option::Option<cosmos_sdk_proto::cosmos::staking::v1beta1::Description>,
+    /// commission defines the new commission rate of the finality provider.
+    /// It forms as a string converted from "cosmossdk.io/math.LegacyDec"
+    /// If it's empty, the commission is left unchanged.
+    #[prost(string, tag="3")]
+    pub commission: ::prost::alloc::string::String,
+}
 /// ActiveBTCDelegation is an IBC packet sent from Babylon to consumer
 /// upon a BTC delegation newly receives covenant signatures and thus becomes active
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -350,6 +383,23 @@ pub struct ActiveBtcDelegation {
     /// params version used to validate delegation
     #[prost(uint32, tag="15")]
     pub params_version: u32,
+    /// staking_tx_inclusion_proof is the inclusion proof of the staking tx in a BTC block
+    #[prost(message, optional, tag="16")]
+    pub staking_tx_inclusion_proof: ::core::option::Option<InclusionProof>,
+}
+/// InclusionProof proves the existence of a tx on BTC blockchain
+/// including
+/// - the position of the tx on BTC blockchain
+/// - the Merkle proof that this tx is on the above position
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InclusionProof {
+    /// key is the position (txIdx, blockHash) of this tx on BTC blockchain
+    #[prost(message, optional, tag="1")]
+    pub key: ::core::option::Option<super::super::btccheckpoint::v1::TransactionKey>,
+    /// proof is the Merkle proof that this tx is included in the position in `key`
+    #[prost(bytes="bytes", tag="2")]
+    pub proof: ::prost::bytes::Bytes,
 }
 /// BTCUndelegationInfo provides all necessary info about the undeleagation
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -154,7 +154,7 @@ pub struct ProofFinalizedChainInfo {
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ZoneconciergePacketData {
     /// packet is the actual message carried in the IBC packet
-    #[prost(oneof="zoneconcierge_packet_data::Packet", tags="1, 2, 3, 4")]
+    #[prost(oneof="zoneconcierge_packet_data::Packet", tags="1, 2, 3, 4, 5")]
     pub packet: ::core::option::Option<zoneconcierge_packet_data::Packet>,
 }
 /// Nested message and enum types in `ZoneconciergePacketData`.
@@ -171,6 +171,8 @@ pub mod zoneconcierge_packet_data {
         ConsumerRegister(super::ConsumerRegisterIbcPacket),
         #[prost(message, tag="4")]
         ConsumerSlashing(super::ConsumerSlashingIbcPacket),
+        #[prost(message, tag="5")]
+        ConsumerUpdate(super::ConsumerUpdateIbcPacket),
     }
 }
 /// BTCTimestamp is a BTC timestamp that carries information of a BTC-finalised epoch
@@ -221,6 +223,18 @@ pub struct ConsumerRegisterIbcPacket {
     #[prost(string, tag="2")]
     pub consumer_description: ::prost::alloc::string::String,
 }
+/// ConsumerUpdateIBCPacket defines the packet data for updating the metadata of a registered
+/// Consumer, or re-registering it
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConsumerUpdateIbcPacket {
+    /// consumer_name is a unique identifier for the consumer chain
+    #[prost(string, tag="1")]
+    pub consumer_name: ::prost::alloc::string::String,
+    /// consumer_description is a brief explanation of the consumer chain's purpose
+    #[prost(string, tag="2")]
+    pub consumer_description: ::prost::alloc::string::String,
+}
 /// ConsumerSlashingIBCPacket defines the slashing information that a Consumer sends to Babylon's ZoneConcierge upon a
 /// Consumer slashing event.
 /// It includes the FP public key, the Consumer block height at the slashing event, and the double sign evidence.
@@ -54,6 +54,9 @@ pub mod babylon {
 #[cfg(test)]
 mod test {
     use crate::babylon::checkpointing::v1::RawCheckpoint;
+    use crate::babylon::zoneconcierge::v1::{
+        zoneconcierge_packet_data::Packet, ConsumerUpdateIbcPacket, ZoneconciergePacketData,
+    };
     use prost::Message;
     use std::fs;
 
@@ -64,4 +67,19 @@ mod test {
         let raw_ckpt = RawCheckpoint::decode(testdata).unwrap();
         assert!(raw_ckpt.epoch_num == 12345);
     }
+
+    #[test]
+    fn test_consumer_update_packet_roundtrip() {
+        let packet = ZoneconciergePacketData {
+            packet: Some(Packet::ConsumerUpdate(ConsumerUpdateIbcPacket {
+                consumer_name: "name".to_string(),
+                consumer_description: "description".to_string(),
+            })),
+        };
+        let bytes = packet.encode_to_vec();
+        // The packet is the length-delimited field 5 of the oneof
+        assert_eq!(bytes[0], 5 << 3 | 2);
+        let decoded = ZoneconciergePacketData::decode(bytes.as_slice()).unwrap();
+        assert_eq!(decoded, packet);
+    }
 }
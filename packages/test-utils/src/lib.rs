@@ -8,11 +8,11 @@ use std::path::PathBuf;
 use std::str::FromStr;
 use std::{env, fs};
 
-use cosmwasm_std::{Binary, Decimal};
+use cosmwasm_std::{Binary, Decimal, Uint128};
 
 use babylon_apis::btc_staking_api::{
     ActiveBtcDelegation, BtcUndelegationInfo, CovenantAdaptorSignatures,
-    FinalityProviderDescription, NewFinalityProvider, ProofOfPossessionBtc,
+    FinalityProviderDescription, NewFinalityProvider, ProofOfPossessionBtc, SignatureInfo,
 };
 use babylon_apis::finality_api::PubRandCommit;
 use babylon_bitcoin::{deserialize, BlockHash, BlockHeader};
@@ -244,7 +244,8 @@ pub fn new_finality_provider(fp: FinalityProvider) -> NewFinalityProvider {
             security_contact: desc.security_contact,
             details: desc.details,
         }),
-        commission: Decimal::from_str(&fp.commission).unwrap(),
+        // The testdata commission is a `LegacyDec`, serialised as its 18-decimal atomics
+        commission: Decimal::from_atomics(Uint128::from_str(&fp.commission).unwrap(), 18).unwrap(),
         btc_pk_hex: fp.btc_pk.encode_hex(),
         pop: match fp.pop {
             Some(pop) => Some(ProofOfPossessionBtc {
@@ -293,10 +294,29 @@ pub fn new_active_btc_delegation(del: BtcDelegation) -> ActiveBtcDelegation {
             slashing_tx: Binary::new(btc_undelegation.slashing_tx.to_vec()),
             delegator_unbonding_sig: Binary::new(btc_undelegation.delegator_unbonding_sig.to_vec()),
             delegator_slashing_sig: Binary::new(btc_undelegation.delegator_slashing_sig.to_vec()),
-            covenant_unbonding_sig_list: vec![],
-            covenant_slashing_sigs: vec![],
+            covenant_unbonding_sig_list: btc_undelegation
+                .covenant_unbonding_sig_list
+                .iter()
+                .map(|cov_sig| SignatureInfo {
+                    pk: Binary::new(cov_sig.pk.to_vec()),
+                    sig: Binary::new(cov_sig.sig.to_vec()),
+                })
+                .collect(),
+            covenant_slashing_sigs: btc_undelegation
+                .covenant_slashing_sigs
+                .iter()
+                .map(|cov_sig| CovenantAdaptorSignatures {
+                    cov_pk: Binary::new(cov_sig.cov_pk.to_vec()),
+                    adaptor_sigs: cov_sig
+                        .adaptor_sigs
+                        .iter()
+                        .map(|adaptor_sig| Binary::new(adaptor_sig.to_vec()))
+                        .collect(),
+                })
+                .collect(),
         },
         params_version: del.params_version,
+        staking_tx_inclusion_proof: None,
     }
 }
 